@@ -2,7 +2,9 @@
 
 use wasm_bindgen::JsValue;
 use crate::console_log;
-use bitcoin::{Address, Network, ScriptBuf, XOnlyPublicKey, secp256k1::Secp256k1, Script, PublicKey};
+use bitcoin::{Address, Network, ScriptBuf, XOnlyPublicKey, Script, PublicKey};
+use bitcoin::key::TweakedPublicKey;
+use crate::utils::secp;
 use miniscript::{Miniscript, Tap, Segwitv0, Descriptor};
 use std::sync::Arc;
 use miniscript::descriptor::TapTree;
@@ -27,6 +29,8 @@ pub struct AddressGenerationResult {
     pub address: String,
     pub script_type: String,
     pub network: Network,
+    /// Taproot internal key origin: "NUMS", "NUMS-derived", "extracted" or "user-provided" (None for other script types)
+    pub internal_key_source: Option<String>,
 }
 
 /// Address generation error type
@@ -113,6 +117,7 @@ pub fn generate_address(input: AddressInput) -> Result<AddressGenerationResult,
                 address: address.to_string(),
                 script_type: input.script_type,
                 network,
+                internal_key_source: None,
             })
         },
         
@@ -139,6 +144,7 @@ pub fn generate_address(input: AddressInput) -> Result<AddressGenerationResult,
                 address: address.to_string(),
                 script_type: input.script_type,
                 network,
+                internal_key_source: None,
             })
         },
         
@@ -147,21 +153,25 @@ pub fn generate_address(input: AddressInput) -> Result<AddressGenerationResult,
             console_log!("Generating Taproot address with miniscript: {} for network: {:?}", 
                         input.script_or_miniscript, network);
             
-            // Determine the taproot mode based on input parameters
-            let mode = if let Some(key) = input.internal_key {
-                if key == crate::taproot::utils::NUMS_POINT {
+            // Determine the taproot mode and internal key based on input parameters
+            let nums = crate::taproot::utils::NUMS_POINT;
+            let (mode, internal_key, key_source) = match input.internal_key.as_deref() {
+                Some(key) if key == nums => {
                     console_log!("Using script-path mode (NUMS key provided)");
-                    "script-path"
-                } else {
-                    console_log!("Using multi-leaf mode (custom internal key provided)");
-                    "multi-leaf"
-                }
-            } else if input.use_single_leaf.unwrap_or(false) {
-                console_log!("Using single-leaf mode (use_single_leaf=true)");
-                "single-leaf"
-            } else {
-                console_log!("Using multi-leaf mode (extract internal key from miniscript)");
-                "multi-leaf"
+                    ("script-path", nums, "NUMS")
+                },
+                Some(key) => {
+                    console_log!("Using script-path mode with custom internal key: {}", key);
+                    ("script-path", key, "user-provided")
+                },
+                None if input.use_single_leaf.unwrap_or(false) => {
+                    console_log!("Using single-leaf mode (use_single_leaf=true)");
+                    ("single-leaf", nums, "NUMS")
+                },
+                None => {
+                    console_log!("Using multi-leaf mode (extract internal key from miniscript)");
+                    ("multi-leaf", nums, "extracted")
+                },
             };
             
             // Dispatch to the appropriate taproot compilation function
@@ -170,10 +180,10 @@ pub fn generate_address(input: AddressInput) -> Result<AddressGenerationResult,
                     crate::compile::modes::compile_taproot_multi_leaf(&input.script_or_miniscript, network, false)
                 },
                 "single-leaf" => {
-                    crate::compile::modes::compile_taproot_single_leaf(&input.script_or_miniscript, crate::taproot::utils::NUMS_POINT, network, false)
+                    crate::compile::modes::compile_taproot_single_leaf(&input.script_or_miniscript, internal_key, network, false)
                 },
                 "script-path" => {
-                    crate::compile::modes::compile_taproot_script_path(&input.script_or_miniscript, crate::taproot::utils::NUMS_POINT, network, false)
+                    crate::compile::modes::compile_taproot_script_path(&input.script_or_miniscript, internal_key, network, false)
                 },
                 _ => return Err(AddressError::DescriptorParse("Invalid taproot mode".to_string()))
             };
@@ -189,6 +199,7 @@ pub fn generate_address(input: AddressInput) -> Result<AddressGenerationResult,
                 address,
                 script_type: "Taproot".to_string(),
                 network,
+                internal_key_source: Some(key_source.to_string()),
             })
         },
         
@@ -227,6 +238,267 @@ pub(crate) fn generate_address_for_network(script_hex: &str, script_type: &str,
 }
 
 
+/// Error for combo() where exactly one output is needed
+pub(crate) const COMBO_SINGLE_OUTPUT_ERROR: &str =
+    "combo() expands to several outputs (P2PK, P2PKH, P2WPKH, P2SH-P2WPKH), not one; list them with combo_outputs";
+
+/// Output script derived from a descriptor at a single index
+#[derive(Debug)]
+pub struct ScriptPubKeyAtIndex {
+    pub script_hex: String,
+    /// None for descriptors without an address form (bare scripts)
+    pub address: Option<String>,
+}
+
+/// Derive the scriptPubKey (and address) of a ranged descriptor at `index`
+/// Non-wildcard descriptors ignore the index, as in Bitcoin Core's deriveaddresses
+pub fn script_pubkey_at_index(descriptor: &str, index: u32, network: &str) -> Result<ScriptPubKeyAtIndex, AddressError> {
+    use miniscript::DescriptorPublicKey;
+    use std::str::FromStr;
+
+    let network = parse_network(network)?;
+    if let Some(output_key) = rawtr_output_key(descriptor.trim(), index)? {
+        let script_pubkey = ScriptBuf::new_p2tr_tweaked(output_key);
+        console_log!("rawtr() scriptPubKey at index {}: {}", index, script_pubkey.to_hex_string());
+        return Ok(ScriptPubKeyAtIndex {
+            script_hex: script_pubkey.to_hex_string(),
+            address: Some(Address::p2tr_tweaked(output_key, network).to_string()),
+        });
+    }
+
+    if is_combo_descriptor(descriptor) {
+        return Err(AddressError::DescriptorParse(COMBO_SINGLE_OUTPUT_ERROR.to_string()));
+    }
+
+    let descriptor = Descriptor::<DescriptorPublicKey>::from_str(descriptor.trim())
+        .map_err(|e| AddressError::DescriptorParse(e.to_string()))?;
+
+    if descriptor.is_multipath() {
+        return Err(AddressError::DescriptorParse(
+            "Multipath descriptors (<a;b>) must be split into single paths before deriving".to_string()
+        ));
+    }
+
+    let derived = descriptor.at_derivation_index(index)
+        .map_err(|e| AddressError::KeyParse(format!("Cannot derive index {}: {}", index, e)))?;
+
+    let script_pubkey = derived.script_pubkey();
+    let address = derived.address(network).ok().map(|a| a.to_string());
+    console_log!("scriptPubKey at index {}: {}", index, script_pubkey.to_hex_string());
+
+    Ok(ScriptPubKeyAtIndex {
+        script_hex: script_pubkey.to_hex_string(),
+        address,
+    })
+}
+
+/// Output key of a `rawtr(KEY)` descriptor at `index`, or None for any other descriptor
+///
+/// rust-miniscript does not parse rawtr(), so its single key argument is handled here.
+/// The key is the taproot output key itself and is used as-is, without a tweak.
+fn rawtr_output_key(descriptor: &str, index: u32) -> Result<Option<TweakedPublicKey>, AddressError> {
+    let Some(key) = single_key_argument(descriptor, "rawtr")? else {
+        return Ok(None);
+    };
+    let output_key = derive_descriptor_key(key, index, "rawtr")?;
+    Ok(Some(TweakedPublicKey::dangerous_assume_tweaked(output_key.inner.x_only_public_key().0)))
+}
+
+/// Expand a `combo(KEY)` descriptor at `index` into every output it stands for
+///
+/// As in Bitcoin Core, a key yields P2PK and P2PKH outputs, plus P2WPKH and P2SH-P2WPKH
+/// when it is compressed. rust-miniscript does not parse combo(), so the key is handled here.
+pub fn combo_outputs(descriptor: &str, index: u32, network: &str) -> Result<Vec<crate::types::ComboOutput>, AddressError> {
+    use bitcoin::CompressedPublicKey;
+
+    let network = parse_network(network)?;
+    let Some(key) = single_key_argument(descriptor.trim(), "combo")? else {
+        return Err(AddressError::DescriptorParse("Not a combo() descriptor".to_string()));
+    };
+    let key = derive_descriptor_key(key, index, "combo")?;
+
+    let output = |output_type: &str, script: ScriptBuf, address: Option<Address>| crate::types::ComboOutput {
+        output_type: output_type.to_string(),
+        script_hex: script.to_hex_string(),
+        address: address.map(|a| a.to_string()),
+    };
+    let mut outputs = vec![
+        output("P2PK", ScriptBuf::new_p2pk(&key), None),
+        output("P2PKH", ScriptBuf::new_p2pkh(&key.pubkey_hash()), Some(Address::p2pkh(key, network))),
+    ];
+    if let Ok(compressed) = CompressedPublicKey::try_from(key) {
+        let p2wpkh = Address::p2wpkh(&compressed, network);
+        let p2sh_p2wpkh = Address::p2shwpkh(&compressed, network);
+        outputs.push(output("P2WPKH", p2wpkh.script_pubkey(), Some(p2wpkh)));
+        outputs.push(output("P2SH-P2WPKH", p2sh_p2wpkh.script_pubkey(), Some(p2sh_p2wpkh)));
+    }
+    console_log!("combo() at index {} expands to {} outputs", index, outputs.len());
+    Ok(outputs)
+}
+
+/// Whether a descriptor is `combo(...)`, which has several outputs rather than one
+pub(crate) fn is_combo_descriptor(descriptor: &str) -> bool {
+    descriptor.trim_start().starts_with("combo(")
+}
+
+/// The key argument of a single-key descriptor `name(KEY)`, verifying any checksum
+/// None when the descriptor is not `name(...)`
+fn single_key_argument<'a>(descriptor: &'a str, name: &str) -> Result<Option<&'a str>, AddressError> {
+    use miniscript::descriptor::checksum::desc_checksum;
+
+    let (body, checksum) = match descriptor.split_once('#') {
+        Some((body, checksum)) => (body, Some(checksum)),
+        None => (descriptor, None),
+    };
+    let Some(key) = body.strip_prefix(name).and_then(|rest| rest.strip_prefix('(')).and_then(|rest| rest.strip_suffix(')')) else {
+        return Ok(None);
+    };
+    if let Some(checksum) = checksum {
+        let expected = desc_checksum(body).map_err(|e| AddressError::DescriptorParse(e.to_string()))?;
+        if checksum != expected {
+            return Err(AddressError::DescriptorParse(format!("Invalid checksum {}, expected {}", checksum, expected)));
+        }
+    }
+    Ok(Some(key))
+}
+
+/// Parse a descriptor key and derive it at `index` (fixed keys ignore the index)
+fn derive_descriptor_key(key: &str, index: u32, name: &str) -> Result<PublicKey, AddressError> {
+    use miniscript::DescriptorPublicKey;
+    use std::str::FromStr;
+
+    let key = DescriptorPublicKey::from_str(key)
+        .map_err(|e| AddressError::KeyParse(format!("{}() key: {}", name, e)))?;
+    if key.is_multipath() {
+        return Err(AddressError::DescriptorParse(
+            "Multipath descriptors (<a;b>) must be split into single paths before deriving".to_string()
+        ));
+    }
+    key.at_derivation_index(index)
+        .map_err(|e| AddressError::KeyParse(format!("Cannot derive index {}: {}", index, e)))?
+        .derive_public_key(secp())
+        .map_err(|e| AddressError::KeyParse(format!("Cannot derive index {}: {}", index, e)))
+}
+
+/// Outcome of comparing the outputs of two descriptors
+#[derive(Debug)]
+pub struct DescriptorEquivalence {
+    pub equivalent: bool,
+    /// First sampled index whose scriptPubKeys differ
+    pub diverging_index: Option<u32>,
+}
+
+/// Check whether two descriptors produce the same scriptPubKeys at the sampled indices
+///
+/// Fixed (non-ranged) descriptors have a single output, so when neither side is ranged only
+/// that output is compared. With no indices given, index 0 is sampled.
+pub fn descriptors_equivalent(desc_a: &str, desc_b: &str, network: &str, sample_indices: &[u32]) -> Result<DescriptorEquivalence, AddressError> {
+    let ranged = |desc: &str| desc.split('#').next().unwrap_or(desc).contains('*');
+    let indices: &[u32] = if sample_indices.is_empty() || (!ranged(desc_a) && !ranged(desc_b)) {
+        &[0]
+    } else {
+        sample_indices
+    };
+
+    for &index in indices {
+        let a = script_pubkey_at_index(desc_a, index, network)?;
+        let b = script_pubkey_at_index(desc_b, index, network)?;
+        if a.script_hex != b.script_hex {
+            console_log!("Descriptors diverge at index {}: {} vs {}", index, a.script_hex, b.script_hex);
+            return Ok(DescriptorEquivalence { equivalent: false, diverging_index: Some(index) });
+        }
+    }
+
+    Ok(DescriptorEquivalence { equivalent: true, diverging_index: None })
+}
+
+/// Outcome of checking a candidate script against a known address
+#[derive(Debug)]
+pub struct AddressScriptMatch {
+    pub matches: bool,
+    /// Output type the address commits to, e.g. "P2WSH" or "P2SH-P2WSH (nested segwit)"
+    pub output_type: String,
+}
+
+/// Rebuild the scriptPubKey a redeem/witness/leaf script would produce and compare it to an address
+///
+/// P2SH addresses are checked as both a bare redeem script and a nested P2WSH witness script.
+/// A P2TR address is checked as a single leaf under the NUMS point and under the tree-derived
+/// unspendable key, since any other internal key cannot be recovered from the script alone.
+pub fn match_address_to_script(address: &str, script_hex: &str, network: &str) -> Result<AddressScriptMatch, AddressError> {
+    use bitcoin::address::{AddressType, NetworkUnchecked};
+    use bitcoin::taproot::{LeafVersion, TapLeafHash, TapNodeHash};
+    use bitcoin::key::TapTweak;
+    use std::str::FromStr;
+
+    let network = parse_network(network)?;
+    let address = Address::<NetworkUnchecked>::from_str(address.trim())
+        .map_err(|e| AddressError::AddressCreation(format!("Invalid address: {}", e)))?
+        .require_network(network)
+        .map_err(|e| AddressError::AddressCreation(e.to_string()))?;
+    let script = ScriptBuf::from_bytes(hex::decode(script_hex.trim())
+        .map_err(|e| AddressError::ScriptDecode(e.to_string()))?);
+    let target = address.script_pubkey();
+
+    let (matches, output_type) = match address.address_type() {
+        Some(AddressType::P2wsh) => (ScriptBuf::new_p2wsh(&script.wscript_hash()) == target, "P2WSH"),
+        Some(AddressType::P2sh) => {
+            if ScriptBuf::new_p2sh(&script.script_hash()) == target {
+                (true, "P2SH")
+            } else {
+                let nested = ScriptBuf::new_p2wsh(&script.wscript_hash());
+                (ScriptBuf::new_p2sh(&nested.script_hash()) == target, "P2SH-P2WSH (nested segwit)")
+            }
+        }
+        Some(AddressType::P2tr) => {
+            let merkle_root = TapNodeHash::from(TapLeafHash::from_script(&script, LeafVersion::TapScript));
+            let unique_key = crate::taproot::utils::derive_unspendable_internal_key(merkle_root)?;
+            let matches = [crate::taproot::utils::get_nums_point(), unique_key].into_iter().any(|internal_key| {
+                let (output_key, _parity) = internal_key.tap_tweak(secp(), Some(merkle_root));
+                ScriptBuf::new_p2tr_tweaked(output_key) == target
+            });
+            (matches, "P2TR")
+        }
+        other => {
+            return Err(AddressError::AddressCreation(format!(
+                "{} addresses commit to a key, not a script",
+                other.map(|t| t.to_string()).unwrap_or_else(|| "Unknown".to_string())
+            )));
+        }
+    };
+    console_log!("Address {} {} the given script as {}", address, if matches { "matches" } else { "does not match" }, output_type);
+
+    Ok(AddressScriptMatch { matches, output_type: output_type.to_string() })
+}
+
+/// Explain a failed address comparison where both addresses encode the same scriptPubKey
+/// on different networks, e.g. "network mismatch: expected mainnet, compiled testnet"
+/// None when either address is invalid or the scripts differ
+pub fn network_mismatch(expected: &str, actual: &str) -> Option<String> {
+    use bitcoin::address::NetworkUnchecked;
+    use std::str::FromStr;
+
+    let expected = Address::<NetworkUnchecked>::from_str(expected.trim()).ok()?;
+    let actual = Address::<NetworkUnchecked>::from_str(actual.trim()).ok()?;
+    let (expected_network, actual_network) = (address_network_name(&expected), address_network_name(&actual));
+    if expected_network == actual_network
+        || expected.assume_checked_ref().script_pubkey() != actual.assume_checked_ref().script_pubkey() {
+        return None;
+    }
+    Some(format!("network mismatch: expected {}, compiled {}", expected_network, actual_network))
+}
+
+/// Network family an address was encoded for; signet shares testnet's encoding
+fn address_network_name(address: &Address<bitcoin::address::NetworkUnchecked>) -> &'static str {
+    if address.is_valid_for_network(Network::Bitcoin) {
+        "mainnet"
+    } else if address.is_valid_for_network(Network::Testnet) {
+        "testnet"
+    } else {
+        "regtest"
+    }
+}
+
 
 /// Generate a Taproot address with a specific internal key and script
 /// This is for advanced use cases where you have a raw script and internal key
@@ -237,18 +509,18 @@ pub fn generate_taproot_address_with_key(script: &Script, internal_key: XOnlyPub
     let script_bytes = script.as_bytes();
     if script_bytes.len() == 34 && script_bytes[0] == 0x20 && script_bytes[33] == 0xac {
         // This is a simple pk() script (32-byte key push + OP_CHECKSIG)
-        return Some(Address::p2tr(&Secp256k1::verification_only(), internal_key, None, network).to_string());
+        return Some(Address::p2tr(secp(), internal_key, None, network).to_string());
     }
     
     // For complex scripts, create a taproot tree with the script
     match TaprootBuilder::new()
         .add_leaf(0, script.to_owned())
-        .map(|builder| builder.finalize(&Secp256k1::verification_only(), internal_key))
+        .map(|builder| builder.finalize(secp(), internal_key))
     {
         Ok(Ok(spend_info)) => {
             // Create the P2TR address with both key-path and script-path
             let output_key = spend_info.output_key();
-            let address = Address::p2tr(&Secp256k1::verification_only(), output_key.to_x_only_public_key(), None, network);
+            let address = Address::p2tr(secp(), output_key.to_x_only_public_key(), None, network);
             Some(address.to_string())
         },
         _ => {
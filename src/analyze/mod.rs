@@ -3,31 +3,34 @@
 //! Provides semantic analysis of miniscripts and policies, extracting
 //! spending paths, key information, timelocks, hashlocks, and security properties.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use wasm_bindgen::JsValue;
-use miniscript::{Miniscript, MiniscriptKey, ScriptContext, Legacy, Segwitv0, Tap};
+use miniscript::{AnalysisError, ExtParams, Miniscript, MiniscriptKey, ScriptContext, Legacy, Segwitv0, SigType, Tap, Terminal, Threshold};
 use miniscript::policy::{Liftable, semantic::Policy as SemanticPolicy};
 
 use crate::types::{
     AnalysisResult, KeyAnalysis, TimelockAnalysis, TimelockInfo,
     HashlockAnalysis, SecurityAnalysis, SizeAnalysis, PolicyTreeNode,
-    ComplexityAnalysis, SpendingPathGroup,
+    ComplexityAnalysis, SpendingPathGroup, KeyPathEntry, PathSatisfiability,
+    PathCondition, StructuredPath,
 };
 use crate::console_log;
+use crate::validation::{check_expression_length, check_non_ascii, validate_arguments, MAX_EXPRESSION_LENGTH};
+use crate::parse::helpers::unwrap_script_descriptor;
+
+pub mod spend;
 
 /// Analyze a miniscript expression and return rich analysis data
 pub fn analyze_miniscript(expression: &str, context: &str) -> JsValue {
-    console_log!("Analyzing miniscript: {} with context: {}", expression, context);
+    analyze_miniscript_with_limits(expression, context, PathLimits::default())
+}
 
-    let result = match context.to_lowercase().as_str() {
-        "legacy" | "p2sh" => perform_miniscript_analysis::<Legacy>(expression),
-        "segwit" | "segwitv0" | "p2wsh" => perform_miniscript_analysis::<Segwitv0>(expression),
-        "taproot" | "tap" | "p2tr" => perform_miniscript_analysis::<Tap>(expression),
-        _ => Err(format!("Unknown context: {}. Use legacy, segwit, or taproot.", context)),
-    };
+/// `analyze_miniscript` under caller-supplied enumeration caps
+pub fn analyze_miniscript_with_limits(expression: &str, context: &str, limits: PathLimits) -> JsValue {
+    console_log!("Analyzing miniscript: {} with context: {}", expression, context);
 
-    let analysis = match result {
+    let analysis = match analyze_miniscript_expression_with_limits(expression, context, &limits) {
         Ok(mut a) => {
             a.source = Some("miniscript".to_string());
             a
@@ -46,18 +49,59 @@ pub fn analyze_miniscript(expression: &str, context: &str) -> JsValue {
             size: None,
             tree_structure: None,
             warnings: None,
+            paths_truncated: None,
             source: Some("miniscript".to_string()),
+            output_type: None,
+            original_expression: None,
+            normalized_expression: None,
         },
     };
 
     serde_wasm_bindgen::to_value(&analysis).unwrap()
 }
 
+/// Analyze a miniscript, unwrapping sh()/wsh()/sh(wsh()) descriptors first
+///
+/// A wrapped miniscript is analyzed under the context its wrapper implies
+/// (overriding `context`), and the wrapper's output type is reported.
+pub fn analyze_miniscript_expression(expression: &str, context: &str) -> Result<AnalysisResult, String> {
+    analyze_miniscript_expression_with_limits(expression, context, &PathLimits::default())
+}
+
+/// `analyze_miniscript_expression` under caller-supplied enumeration caps
+pub fn analyze_miniscript_expression_with_limits(expression: &str, context: &str, limits: &PathLimits) -> Result<AnalysisResult, String> {
+    let original = expression;
+    let (expression, context, output_type) = match unwrap_script_descriptor(expression) {
+        Some((inner, wrapped_context, output_type)) => {
+            console_log!("Unwrapped {} descriptor, analyzing inner miniscript under {}", output_type, wrapped_context);
+            (inner, wrapped_context, Some(output_type))
+        }
+        None => (expression, context, None),
+    };
+
+    let mut analysis = match context.to_lowercase().as_str() {
+        "legacy" | "p2sh" => perform_miniscript_analysis::<Legacy>(expression, limits),
+        "segwit" | "segwitv0" | "p2wsh" => perform_miniscript_analysis::<Segwitv0>(expression, limits),
+        "taproot" | "tap" | "p2tr" => perform_miniscript_analysis::<Tap>(expression, limits),
+        _ => Err(format!("Unknown context: {}. Use legacy, segwit, or taproot.", context)),
+    }?;
+    analysis.output_type = output_type.map(str::to_string);
+    analysis.original_expression = Some(original.to_string());
+    Ok(analysis)
+}
+
 /// Analyze a policy expression and return rich analysis data
-pub fn analyze_policy(policy_str: &str) -> JsValue {
+///
+/// With `include_weights`, each top-level `or` branch group carries its share of the weights.
+pub fn analyze_policy(policy_str: &str, include_weights: bool) -> JsValue {
+    analyze_policy_with_limits(policy_str, include_weights, PathLimits::default())
+}
+
+/// `analyze_policy` under caller-supplied enumeration caps
+pub fn analyze_policy_with_limits(policy_str: &str, include_weights: bool, limits: PathLimits) -> JsValue {
     console_log!("Analyzing policy: {}", policy_str);
 
-    let result = perform_policy_analysis(policy_str);
+    let result = perform_policy_analysis_with_limits(policy_str, include_weights, &limits);
 
     let analysis = match result {
         Ok(mut a) => {
@@ -78,40 +122,432 @@ pub fn analyze_policy(policy_str: &str) -> JsValue {
             size: None,
             tree_structure: None,
             warnings: None,
+            paths_truncated: None,
             source: Some("policy".to_string()),
+            output_type: None,
+            original_expression: None,
+            normalized_expression: None,
         },
     };
 
     serde_wasm_bindgen::to_value(&analysis).unwrap()
 }
 
+/// Summarize a top-level multisig or timelocked threshold in one line, e.g. "2-of-3 multisig: {A, B, C}"
+/// Returns None for shapes without a short description
+pub fn shape_summary(expression: &str, context: &str) -> Option<String> {
+    let semantic = lift_for_context(expression, context).ok()?.normalized();
+    match &semantic {
+        SemanticPolicy::Thresh(thresh) if thresh.n() > 1 => {
+            let children: Vec<_> = thresh.iter().cloned().collect();
+            let all_keys = children.iter().all(|c| matches!(c.as_ref(), SemanticPolicy::Key(_)));
+            let has_timelock = children.iter()
+                .any(|c| matches!(c.as_ref(), SemanticPolicy::After(_) | SemanticPolicy::Older(_)));
+            if all_keys || has_timelock {
+                Some(generate_thresh_summary(&children, thresh.k(), thresh.n()))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Report, for each key, which spending paths it participates in
+/// Context is "policy" for concrete policies, or legacy/segwit/taproot for miniscript
+pub fn key_path_matrix(expression: &str, context: &str) -> Result<Vec<KeyPathEntry>, String> {
+    let semantic = lift_for_context(expression, context)?;
+    let paths = get_all_paths(&semantic);
+    let mut keys = Vec::new();
+    collect_keys(&semantic, &mut keys, &mut HashSet::new());
+
+    let mut entries: Vec<KeyPathEntry> = Vec::new();
+    for key in keys {
+        if entries.iter().any(|e| e.key == key) {
+            continue;
+        }
+        let condition = format!("{} signs", key);
+        let key_paths = paths.iter()
+            .enumerate()
+            .filter(|(_, conditions)| conditions.contains(&condition))
+            .map(|(i, _)| i + 1)
+            .collect();
+        entries.push(KeyPathEntry { key, paths: key_paths });
+    }
+
+    Ok(entries)
+}
+
+/// Split the spending paths into those satisfiable with the given keys and preimages and those that are not
+/// Keys are hex (compressed keys also match their x-only form); preimages are hex and matched by hash.
+/// Timelocks never block a path since waiting is always possible. Unsatisfiable entries list what is missing.
+pub fn satisfiable_paths(
+    expression: &str,
+    context: &str,
+    available_keys: &[String],
+    available_preimages: &[String],
+) -> Result<(Vec<PathSatisfiability>, Vec<PathSatisfiability>), String> {
+    let semantic = lift_for_context(expression, context)?;
+    let preimage_conditions = preimage_conditions(available_preimages)?;
+
+    let mut satisfiable = Vec::new();
+    let mut unsatisfiable = Vec::new();
+    for (i, conditions) in get_all_paths(&semantic).into_iter().enumerate() {
+        let missing: Vec<String> = conditions.iter()
+            .filter(|condition| !condition_available(condition, available_keys, &preimage_conditions))
+            .cloned()
+            .collect();
+        let entry = PathSatisfiability { path: i + 1, conditions, missing };
+        if entry.missing.is_empty() {
+            satisfiable.push(entry);
+        } else {
+            unsatisfiable.push(entry);
+        }
+    }
+
+    Ok((satisfiable, unsatisfiable))
+}
+
+/// Enumerate the spending paths with typed conditions instead of prose strings
+/// Paths come in the same order as spending_paths. Witness weights are planned for
+/// legacy/segwit/taproot expressions with concrete keys and are None otherwise.
+pub fn spending_paths_structured(expression: &str, context: &str) -> Result<Vec<StructuredPath>, String> {
+    let semantic = lift_for_context(expression, context)?;
+    let paths = collect_paths(&semantic, &path_condition, &|k, n, signed| PathCondition {
+        kind: "threshold_summary".to_string(),
+        detail: summarize_thresh_paths(k, n, signed),
+        key: None,
+        value: None,
+        hash: None,
+    }, &PathLimits::default());
+    let details: Vec<Vec<String>> = paths.iter()
+        .map(|conditions| conditions.iter().map(|c| c.detail.clone()).collect())
+        .collect();
+    let weights = spend::path_witness_weights(expression, context, &details);
+
+    Ok(paths.into_iter()
+        .zip(weights)
+        .map(|(conditions, witness_weight)| StructuredPath {
+            // Summarized thresholds count as signed only when every selection needs a key, as in spending_paths
+            requires_signature: conditions.iter().any(|c| c.kind == "signature" || (c.kind == "threshold_summary" && path_has_signature(&c.detail))),
+            conditions,
+            witness_weight,
+        })
+        .collect())
+}
+
+/// Type a leaf of the semantic policy as a path condition
+fn path_condition<Pk: MiniscriptKey + std::fmt::Display>(policy: &SemanticPolicy<Pk>) -> PathCondition {
+    let (kind, key, value, hash) = match policy {
+        SemanticPolicy::Key(pk) => ("signature", Some(pk.to_string()), None, None),
+        SemanticPolicy::After(t) => ("after", None, Some(t.to_consensus_u32()), None),
+        SemanticPolicy::Older(t) => ("older", None, Some(t.to_consensus_u32()), None),
+        SemanticPolicy::Sha256(h) => ("sha256", None, None, Some(h.to_string())),
+        SemanticPolicy::Hash256(h) => ("hash256", None, None, Some(h.to_string())),
+        SemanticPolicy::Ripemd160(h) => ("ripemd160", None, None, Some(h.to_string())),
+        SemanticPolicy::Hash160(h) => ("hash160", None, None, Some(h.to_string())),
+        _ => ("trivial", None, None, None),
+    };
+    PathCondition { kind: kind.to_string(), detail: describe_condition(policy), key, value, hash }
+}
+
+/// The x-only part of a compressed hex key (`02`/`03` prefix followed by 64 hex digits)
+fn xonly_part(key: &str) -> Option<&str> {
+    key.strip_prefix("02").or_else(|| key.strip_prefix("03"))
+        .filter(|rest| rest.len() == 64 && rest.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Whether a single path condition can be met with the available material
+fn condition_available(condition: &str, available_keys: &[String], preimage_conditions: &[String]) -> bool {
+    if let Some(key) = condition.strip_suffix(" signs") {
+        let key = key.to_lowercase();
+        available_keys.iter().any(|available| {
+            let available = available.trim().to_lowercase();
+            available == key
+                || xonly_part(&available) == Some(key.as_str())
+                || xonly_part(&key) == Some(available.as_str())
+        })
+    } else if condition.starts_with("provide ") {
+        preimage_conditions.iter().any(|c| c.eq_ignore_ascii_case(condition))
+    } else {
+        // Timelocks and trivially true conditions; summarized thresholds cannot be checked
+        !condition.contains(NOT_ENUMERATED)
+    }
+}
+
+/// Build the hashlock conditions (as written by `get_all_paths`) that the given preimages unlock
+fn preimage_conditions(preimages: &[String]) -> Result<Vec<String>, String> {
+    use bitcoin::hashes::{hash160, ripemd160, sha256, sha256d, Hash};
+
+    let mut conditions = Vec::new();
+    for preimage in preimages {
+        let bytes = hex::decode(preimage.trim())
+            .map_err(|e| format!("Invalid preimage hex '{}': {}", preimage, e))?;
+        let hashes = [
+            ("SHA256", sha256::Hash::hash(&bytes).to_string()),
+            // Miniscript writes hash256 digests in forward byte order, unlike sha256d's Display
+            ("HASH256", hex::encode(sha256d::Hash::hash(&bytes).to_byte_array())),
+            ("RIPEMD160", ripemd160::Hash::hash(&bytes).to_string()),
+            ("HASH160", hash160::Hash::hash(&bytes).to_string()),
+        ];
+        for (name, digest) in hashes {
+            conditions.push(format!("provide {} preimage for {}", name, &digest[..8]));
+        }
+    }
+    Ok(conditions)
+}
+
+/// Render the spending policy as numbered English sentences, e.g.
+/// "Funds can be spent by: (1) Alice and Bob together, or (2) Carol alone after 6 months."
+/// Branches follow the grouped spending paths; keys found in `key_names` are shown by name.
+pub fn contract_summary(
+    expression: &str,
+    context: &str,
+    key_names: &HashMap<String, String>,
+) -> Result<String, String> {
+    let semantic = lift_for_context(expression, context)?.normalized();
+    let branches: Vec<String> = match &semantic {
+        SemanticPolicy::Thresh(thresh) if thresh.k() == 1 && thresh.n() > 1 => thresh.iter()
+            .map(|child| describe_branch_prose(child.as_ref(), key_names))
+            .collect(),
+        other => vec![describe_branch_prose(other, key_names)],
+    };
+
+    let numbered: Vec<String> = branches.iter()
+        .enumerate()
+        .map(|(i, branch)| format!("({}) {}", i + 1, branch))
+        .collect();
+    let sentence = match numbered.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{}, or {}", rest.join(", "), last),
+        _ => numbered.join(""),
+    };
+    Ok(format!("Funds can be spent by: {}.", sentence))
+}
+
+/// Describe one spending branch in prose: who signs, then any timelock or hashlock
+fn describe_branch_prose(policy: &SemanticPolicy<String>, key_names: &HashMap<String, String>) -> String {
+    let name = |pk: &String| key_names.get(pk).cloned().unwrap_or_else(|| pk.clone());
+    match policy {
+        SemanticPolicy::Unsatisfiable => "nobody".to_string(),
+        SemanticPolicy::Trivial => "anyone at any time".to_string(),
+        SemanticPolicy::Key(pk) => format!("{} alone", name(pk)),
+        SemanticPolicy::Thresh(thresh) if thresh.k() == thresh.n() => {
+            let mut signers = Vec::new();
+            let mut conditions = Vec::new();
+            for child in thresh.iter() {
+                match child.as_ref() {
+                    SemanticPolicy::Key(pk) => signers.push(name(pk)),
+                    SemanticPolicy::Thresh(_) => conditions.push(format!("with {}", describe_branch_prose(child.as_ref(), key_names))),
+                    other => conditions.push(describe_condition_prose(other)),
+                }
+            }
+            let who = match signers.len() {
+                0 => "anyone".to_string(),
+                1 => format!("{} alone", signers[0]),
+                _ => format!("{} together", join_prose(&signers, "and")),
+            };
+            std::iter::once(who).chain(conditions).collect::<Vec<_>>().join(" ")
+        }
+        SemanticPolicy::Thresh(thresh) => {
+            let parts: Vec<String> = thresh.iter()
+                .map(|child| match child.as_ref() {
+                    SemanticPolicy::Key(pk) => name(pk),
+                    other => describe_branch_prose(other, key_names),
+                })
+                .collect();
+            if thresh.k() == 1 {
+                format!("either {}", join_prose(&parts, "or"))
+            } else {
+                format!("any {} of {}", thresh.k(), join_prose(&parts, "and"))
+            }
+        }
+        other => format!("anyone {}", describe_condition_prose(other)),
+    }
+}
+
+/// Describe a timelock or hashlock as a trailing clause, e.g. "after 6 months"
+fn describe_condition_prose(policy: &SemanticPolicy<String>) -> String {
+    match policy {
+        SemanticPolicy::Older(t) if t.is_height_locked() => {
+            format!("after {}", humanize_days(t.to_consensus_u32() as f64 / 144.0))
+        }
+        SemanticPolicy::Older(t) => {
+            let seconds = (t.to_consensus_u32() & 0x0000FFFF) as f64 * 512.0;
+            format!("after {}", humanize_days(seconds / 86400.0))
+        }
+        SemanticPolicy::After(t) if t.is_block_height() => format!("after block {}", t.to_consensus_u32()),
+        SemanticPolicy::After(t) => format!("after {}", format_unix_timestamp(t.to_consensus_u32() as i64)),
+        SemanticPolicy::Sha256(h) => format!("with the SHA256 preimage of {}...", short_hash(&h.to_string())),
+        SemanticPolicy::Hash256(h) => format!("with the HASH256 preimage of {}...", short_hash(&h.to_string())),
+        SemanticPolicy::Ripemd160(h) => format!("with the RIPEMD160 preimage of {}...", short_hash(&h.to_string())),
+        SemanticPolicy::Hash160(h) => format!("with the HASH160 preimage of {}...", short_hash(&h.to_string())),
+        _ => String::new(),
+    }
+}
+
+/// Turn a number of days into "6 months", "~3 days", "2 years"; "~" marks a rounded value
+fn humanize_days(days: f64) -> String {
+    let (value, unit) = if days < 1.0 {
+        (days * 24.0, "hour")
+    } else if days < 60.0 {
+        (days, "day")
+    } else if days < 730.0 {
+        (days / 30.0, "month")
+    } else {
+        (days / 365.0, "year")
+    };
+    let rounded = value.round().max(1.0);
+    let approx = if (value - rounded).abs() > 0.01 { "~" } else { "" };
+    let plural = if rounded == 1.0 { "" } else { "s" };
+    format!("{}{:.0} {}{}", approx, rounded, unit, plural)
+}
+
+/// Join items as "A", "A and B" or "A, B and C"
+fn join_prose(items: &[String], conjunction: &str) -> String {
+    match items.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} {} {}", rest.join(", "), conjunction, last),
+        _ => items.join(""),
+    }
+}
+
+/// Estimate how many spending paths `get_all_paths` would produce, without enumerating them
+/// Counts above 2^53 are clamped so the value stays exact in JavaScript
+pub fn estimate_path_count(expression: &str, context: &str) -> Result<u64, String> {
+    let semantic = lift_for_context(expression, context)?;
+    Ok(count_all_paths(&semantic).min(PATH_COUNT_CAP))
+}
+
+/// Largest path count reported by `estimate_path_count` (JavaScript's exact integer range)
+const PATH_COUNT_CAP: u64 = 1 << 53;
+
+/// Lift an expression to a semantic policy
+/// Context is "policy" for concrete policies, or legacy/segwit/taproot for miniscript
+fn lift_for_context(expression: &str, context: &str) -> Result<SemanticPolicy<String>, String> {
+    use miniscript::policy::Concrete;
+
+    match context.to_lowercase().as_str() {
+        "policy" => {
+            let policy: Concrete<String> = expression.trim().parse()
+                .map_err(|e| format!("Failed to parse policy: {}", e))?;
+            policy.lift().map_err(|e| format!("Failed to lift policy: {}", e))
+        }
+        "legacy" | "p2sh" => lift_miniscript_for_context::<Legacy>(expression),
+        "segwit" | "segwitv0" | "p2wsh" => lift_miniscript_for_context::<Segwitv0>(expression),
+        "taproot" | "tap" | "p2tr" => lift_miniscript_for_context::<Tap>(expression),
+        _ => Err(format!("Unknown context: {}. Use policy, legacy, segwit, or taproot.", context)),
+    }
+}
+
+/// Parse (without sanity check) and lift a miniscript for a specific context
+fn lift_miniscript_for_context<Ctx: ScriptContext>(expression: &str) -> Result<SemanticPolicy<String>, String> {
+    let ms: Miniscript<String, Ctx> = Miniscript::from_str_insane(expression.trim())
+        .map_err(|e| format!("Failed to parse miniscript: {}", e))?;
+    lift_miniscript(&ms)
+}
+
+/// Lift a parsed miniscript, mapping scripts with no valid satisfaction to `Unsatisfiable`
+/// rust-miniscript refuses to lift these (e.g. a bare `0` or `and_v(v:pk(A),0)`) as exceeding resource limits
+fn lift_miniscript<Ctx: ScriptContext>(ms: &Miniscript<String, Ctx>) -> Result<SemanticPolicy<String>, String> {
+    use miniscript::policy::LiftError;
+
+    match ms.lift() {
+        Err(miniscript::Error::LiftError(LiftError::BranchExceedResourceLimits)) => {
+            let semantic = ms.node.lift()
+                .map_err(|e| format!("Failed to lift miniscript: {}", e))?
+                .normalized();
+            if semantic.is_unsatisfiable() {
+                Ok(semantic)
+            } else {
+                Err(format!("Failed to lift miniscript: {}", LiftError::BranchExceedResourceLimits))
+            }
+        }
+        result => result.map_err(|e| format!("Failed to lift miniscript: {}", e)),
+    }
+}
+
+/// Explain why no address should be generated for an expression that is trivially
+/// satisfiable or unsatisfiable at the top level; None for ordinary scripts
+/// Context is "policy" for concrete policies, or legacy/segwit/taproot for miniscript
+pub fn degenerate_spend_warning(expression: &str, context: &str) -> Option<String> {
+    let semantic = lift_for_context(expression, context).ok()?.normalized();
+    if semantic.is_trivial() {
+        Some("❌ Address generation blocked: the script is trivially satisfiable - anyone could spend from it".to_string())
+    } else if semantic.is_unsatisfiable() {
+        Some("❌ Address generation blocked: the script is unsatisfiable - funds sent to it could never be spent".to_string())
+    } else {
+        None
+    }
+}
+
+/// Parse a miniscript for analysis, accepting a sigless andor/and_n branch
+///
+/// andor(X,Y,Z) and and_n(X,Y) with a branch that needs no signature fail only the
+/// sigless-branch sanity rule; they are still worth analyzing, and the failed sanity
+/// check is reported through passes_sanity_check and a warning. Every other
+/// expression, including one whose sigless path comes from another fragment, must
+/// parse as sane.
+fn parse_for_analysis<Ctx: ScriptContext>(expression: &str) -> Result<Miniscript<String, Ctx>, miniscript::Error> {
+    let sane_err = match expression.parse::<Miniscript<String, Ctx>>() {
+        Ok(ms) => return Ok(ms),
+        Err(e) => e,
+    };
+    if !matches!(sane_err, miniscript::Error::AnalysisError(AnalysisError::SiglessBranch)) {
+        return Err(sane_err);
+    }
+    match Miniscript::<String, Ctx>::from_str_ext(expression, &ExtParams::sane().top_unsafe()) {
+        Ok(ms) if sigless_paths_from_andor(&ms) => Ok(ms),
+        _ => Err(sane_err),
+    }
+}
+
+// Whether every signature-free path runs through a sigless andor/and_n branch:
+// the script needs a signature once those branches are treated as signed
+fn sigless_paths_from_andor<Ctx: ScriptContext>(ms: &Miniscript<String, Ctx>) -> bool {
+    fn signed<Ctx: ScriptContext>(ms: &Miniscript<String, Ctx>) -> bool {
+        match &ms.node {
+            Terminal::AndOr(..) => true,
+            Terminal::Alt(sub) | Terminal::Swap(sub) | Terminal::Check(sub) | Terminal::DupIf(sub)
+            | Terminal::Verify(sub) | Terminal::NonZero(sub) | Terminal::ZeroNotEqual(sub) => signed(sub),
+            Terminal::AndV(l, r) | Terminal::AndB(l, r) => signed(l) || signed(r),
+            Terminal::OrB(l, r) | Terminal::OrD(l, r) | Terminal::OrC(l, r) | Terminal::OrI(l, r) => signed(l) && signed(r),
+            Terminal::Thresh(thresh) => thresh.iter().filter(|sub| !signed(sub)).count() < thresh.k(),
+            _ => ms.requires_sig(),
+        }
+    }
+    signed(ms)
+}
+
 /// Internal function to analyze a miniscript for a specific context
-fn perform_miniscript_analysis<Ctx>(expression: &str) -> Result<AnalysisResult, String>
+fn perform_miniscript_analysis<Ctx>(expression: &str, limits: &PathLimits) -> Result<AnalysisResult, String>
 where
     Ctx: ScriptContext,
     Ctx::Key: MiniscriptKey + std::fmt::Display + std::str::FromStr,
     <Ctx::Key as std::str::FromStr>::Err: std::fmt::Display,
 {
+    check_expression_length(expression, MAX_EXPRESSION_LENGTH)?;
+
     // Parse the miniscript
-    let ms: Miniscript<String, Ctx> = expression
-        .parse()
+    let ms: Miniscript<String, Ctx> = parse_for_analysis(expression)
         .map_err(|e| format!("Failed to parse miniscript: {}", e))?;
 
     // Lift to semantic policy
-    let semantic = ms.lift()
-        .map_err(|e| format!("Failed to lift miniscript: {}", e))?;
+    let semantic = lift_miniscript(&ms)?;
 
     // Extract analysis from semantic policy
     let spending_logic = semantic.to_string();
-    let spending_paths = enumerate_spending_paths(&semantic);
-    let spending_paths_grouped = get_grouped_paths(&semantic);
-    let keys = extract_key_analysis(&semantic, &spending_paths);
+    let spending_paths = enumerate_spending_paths_with_limits(&semantic, limits);
+    let spending_paths_grouped = get_grouped_paths_with_limits(&semantic, limits);
+    let mut keys = extract_key_analysis(&semantic, &spending_paths);
+    if Ctx::sig_type() == SigType::Schnorr {
+        keys.key_forms = Some(keys.unique_keys.iter().map(|key| taproot_key_form(key)).collect());
+    }
     let has_mixed = ms.has_mixed_timelocks();
     let timelocks = extract_timelock_analysis(&semantic, has_mixed);
-    let hashlocks = extract_hashlock_analysis(&semantic);
+    let redundant_hashes = find_redundant_hashlocks(&semantic, limits);
+    let hashlocks = extract_hashlock_analysis(&semantic, redundant_hashes.clone());
     let complexity = extract_complexity(&semantic, spending_paths.len());
     let tree_structure = semantic_to_tree(&semantic, 0);
-    let mut warnings = extract_warnings(&semantic);
+    let mut warnings = extract_warnings(&semantic, &redundant_hashes);
     // Note: This warning is effectively unreachable - rust-miniscript rejects mixed
     // timelocks at parse time, so has_mixed will always be false for valid miniscript.
     // Kept as defensive code in case future library versions change this behavior.
@@ -122,13 +558,15 @@ where
     // Extract security info from miniscript
     let is_non_malleable = ms.is_non_malleable();
     let requires_signature = ms.requires_sig();
+    let sanity = ms.sanity_check();
     let security = Some(SecurityAnalysis {
         is_non_malleable,
         requires_signature,
         has_repeated_keys: ms.has_repeated_keys(),
         within_resource_limits: ms.within_resource_limits(),
-        passes_sanity_check: ms.sanity_check().is_ok(),
+        passes_sanity_check: sanity.is_ok(),
         is_safe: ms.ty.mall.safe,
+        risk_level: risk_level(&semantic, requires_signature, is_non_malleable).to_string(),
     });
 
     // Add security warnings
@@ -145,6 +583,9 @@ where
         };
         warnings.push(warning_msg.to_string());
     }
+    if let Err(e) = &sanity {
+        warnings.push(format!("⚠️ SANITY: This miniscript does not pass the sanity check ({}). Standard wallets and compilers will refuse to use it.", e));
+    }
     if !is_non_malleable {
         warnings.push("⚠️ MALLEABLE: This script is malleable. Third parties could modify the transaction witness without invalidating it, which may cause issues with protocols that rely on transaction IDs (e.g., Lightning, payment channels).".to_string());
     }
@@ -172,14 +613,32 @@ where
         size,
         tree_structure: Some(tree_structure),
         warnings: if warnings.is_empty() { None } else { Some(warnings) },
+        paths_truncated: Some(paths_truncated_with_limits(&semantic, limits)),
         source: None, // Set by caller
+        output_type: None,
+        original_expression: Some(expression.to_string()),
+        normalized_expression: Some(ms.to_string()),
     })
 }
 
-/// Internal function to analyze a concrete policy
-fn perform_policy_analysis(policy_str: &str) -> Result<AnalysisResult, String> {
+/// Analyze a concrete policy and return the analysis directly (analyze_policy wraps this for JavaScript)
+pub fn perform_policy_analysis(policy_str: &str) -> Result<AnalysisResult, String> {
+    perform_policy_analysis_with_weights(policy_str, false)
+}
+
+/// Policy analysis that can also attach the concrete `or` weights, which lifting discards
+pub fn perform_policy_analysis_with_weights(policy_str: &str, include_weights: bool) -> Result<AnalysisResult, String> {
+    perform_policy_analysis_with_limits(policy_str, include_weights, &PathLimits::default())
+}
+
+/// Policy analysis under caller-supplied enumeration caps
+pub fn perform_policy_analysis_with_limits(policy_str: &str, include_weights: bool, limits: &PathLimits) -> Result<AnalysisResult, String> {
     use miniscript::policy::Concrete;
 
+    check_expression_length(policy_str, MAX_EXPRESSION_LENGTH)?;
+    check_non_ascii(policy_str)?;
+    validate_arguments(policy_str)?;
+
     // Parse the concrete policy
     let policy: Concrete<String> = policy_str
         .parse()
@@ -191,8 +650,11 @@ fn perform_policy_analysis(policy_str: &str) -> Result<AnalysisResult, String> {
 
     // Extract analysis from semantic policy
     let spending_logic = semantic.to_string();
-    let spending_paths = enumerate_spending_paths(&semantic);
-    let spending_paths_grouped = get_grouped_paths(&semantic);
+    let spending_paths = enumerate_spending_paths_with_limits(&semantic, limits);
+    let mut spending_paths_grouped = get_grouped_paths_with_limits(&semantic, limits);
+    if include_weights {
+        attach_branch_weights(&policy, &mut spending_paths_grouped);
+    }
     let keys = extract_key_analysis(&semantic, &spending_paths);
 
     // For policy, check for height-vs-time mixing using check_timelocks()
@@ -200,10 +662,11 @@ fn perform_policy_analysis(policy_str: &str) -> Result<AnalysisResult, String> {
     // in the same spending path, which is a Bitcoin consensus issue
     let has_mixed = policy.check_timelocks().is_err();
     let timelocks = extract_timelock_analysis(&semantic, has_mixed);
-    let hashlocks = extract_hashlock_analysis(&semantic);
+    let redundant_hashes = find_redundant_hashlocks(&semantic, limits);
+    let hashlocks = extract_hashlock_analysis(&semantic, redundant_hashes.clone());
     let complexity = extract_complexity(&semantic, spending_paths.len());
     let tree_structure = semantic_to_tree(&semantic, 0);
-    let mut warnings = extract_warnings(&semantic);
+    let mut warnings = extract_warnings(&semantic, &redundant_hashes);
     // Note: This warning is effectively unreachable - rust-miniscript rejects mixed
     // timelocks at parse time, so has_mixed will always be false for valid policies.
     // Kept as defensive code in case future library versions change this behavior.
@@ -225,6 +688,7 @@ fn perform_policy_analysis(policy_str: &str) -> Result<AnalysisResult, String> {
         within_resource_limits: true, // Not known until compiled
         passes_sanity_check: policy.is_valid().is_ok(),
         is_safe,
+        risk_level: risk_level(&semantic, requires_signature, is_non_malleable).to_string(),
     });
 
     // Add security warnings
@@ -259,10 +723,32 @@ fn perform_policy_analysis(policy_str: &str) -> Result<AnalysisResult, String> {
         size: None, // No size info for policy (not compiled)
         tree_structure: Some(tree_structure),
         warnings: if warnings.is_empty() { None } else { Some(warnings) },
+        paths_truncated: Some(paths_truncated_with_limits(&semantic, limits)),
         source: None, // Set by caller
+        output_type: None,
+        original_expression: Some(policy_str.to_string()),
+        normalized_expression: Some(policy.to_string()),
     })
 }
 
+/// Set each top-level `or` branch group's weight as a percentage of the total
+///
+/// Lifting keeps the branches in order, so group `i` is branch `i` of the concrete `or`.
+/// Anything else (no top-level `or`, or groups that no longer line up) is left unweighted.
+fn attach_branch_weights<Pk: MiniscriptKey>(policy: &miniscript::policy::Concrete<Pk>, groups: &mut [SpendingPathGroup]) {
+    let miniscript::policy::Concrete::Or(branches) = policy else {
+        return;
+    };
+    if branches.len() != groups.len() {
+        return;
+    }
+
+    let total: usize = branches.iter().map(|(weight, _)| weight).sum();
+    for ((weight, _), group) in branches.iter().zip(groups.iter_mut()) {
+        group.weight_percent = Some(*weight as f64 * 100.0 / total as f64);
+    }
+}
+
 /// Extract key analysis from semantic policy
 fn extract_key_analysis<Pk: MiniscriptKey + std::fmt::Display>(policy: &SemanticPolicy<Pk>, spending_paths: &[String]) -> KeyAnalysis {
     let mut keys: Vec<String> = Vec::new();
@@ -279,6 +765,31 @@ fn extract_key_analysis<Pk: MiniscriptKey + std::fmt::Display>(policy: &Semantic
         unique_keys: unique.into_iter().collect(),
         min_signatures: min_sigs,
         max_signatures: max_sigs,
+        key_forms: None,
+    }
+}
+
+/// Describe how a taproot key relates to its compressed (segwit) form
+///
+/// A bare x-only key has lost its parity byte, so only descriptor keys (xpubs,
+/// or compressed keys inside a descriptor) can show the compressed key they came from.
+fn taproot_key_form(key: &str) -> String {
+    use miniscript::{DescriptorPublicKey, ToPublicKey};
+
+    if key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return format!("x-only key {} (compressed form ambiguous without parity)", key);
+    }
+    let Ok(pk) = key.parse::<DescriptorPublicKey>() else {
+        return format!("{} (no key material to derive forms from)", key);
+    };
+    let index_note = if pk.has_wildcard() { " at index 0" } else { "" };
+    match pk.at_derivation_index(0) {
+        Ok(derived) => {
+            let compressed = derived.to_public_key();
+            let xonly = bitcoin::XOnlyPublicKey::from(compressed);
+            format!("{} → compressed {}, x-only {}{}", key, compressed, xonly, index_note)
+        }
+        Err(e) => format!("{} (cannot derive a public key: {})", key, e),
     }
 }
 
@@ -328,6 +839,14 @@ fn collect_keys<Pk: MiniscriptKey + std::fmt::Display>(
     }
 }
 
+/// older() compiles to OP_CHECKSEQUENCEVERIFY (BIP112), which checks the BIP68 relative lock
+pub const RELATIVE_TIMELOCK_RULE: &str =
+    "BIP68 relative (OP_CHECKSEQUENCEVERIFY, BIP112): set the input's nSequence and transaction version 2";
+
+/// after() compiles to OP_CHECKLOCKTIMEVERIFY (BIP65), which checks the transaction's nLockTime
+pub const ABSOLUTE_TIMELOCK_RULE: &str =
+    "BIP65 absolute (OP_CHECKLOCKTIMEVERIFY): set the transaction's nLockTime and a non-final input nSequence";
+
 /// Extract timelock analysis from semantic policy
 fn extract_timelock_analysis<Pk: MiniscriptKey>(
     policy: &SemanticPolicy<Pk>,
@@ -338,12 +857,12 @@ fn extract_timelock_analysis<Pk: MiniscriptKey>(
 
     let relative: Vec<TimelockInfo> = relative_values
         .into_iter()
-        .map(|v| TimelockInfo { value: v })
+        .map(|v| TimelockInfo { value: v, consensus_rule: RELATIVE_TIMELOCK_RULE.to_string() })
         .collect();
 
     let absolute: Vec<TimelockInfo> = absolute_values
         .into_iter()
-        .map(|v| TimelockInfo { value: v })
+        .map(|v| TimelockInfo { value: v, consensus_rule: ABSOLUTE_TIMELOCK_RULE.to_string() })
         .collect();
 
     TimelockAnalysis {
@@ -354,7 +873,8 @@ fn extract_timelock_analysis<Pk: MiniscriptKey>(
 }
 
 /// Extract hashlock analysis from semantic policy
-fn extract_hashlock_analysis<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>) -> HashlockAnalysis {
+/// `redundant_hashes` comes from `find_redundant_hashlocks`, shared with the warnings
+fn extract_hashlock_analysis<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>, redundant_hashes: Vec<String>) -> HashlockAnalysis {
     let mut sha256_count = 0;
     let mut hash256_count = 0;
     let mut ripemd160_count = 0;
@@ -362,14 +882,170 @@ fn extract_hashlock_analysis<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>) ->
 
     count_hashlocks(policy, &mut sha256_count, &mut hash256_count, &mut ripemd160_count, &mut hash160_count);
 
+    let mut all_hashes = Vec::new();
+    collect_hashlocks(policy, &mut all_hashes);
+    let mut distinct_hashes: Vec<String> = Vec::new();
+    for hash in all_hashes {
+        if !distinct_hashes.contains(&hash) {
+            distinct_hashes.push(hash);
+        }
+    }
+
     HashlockAnalysis {
         sha256_count,
         hash256_count,
         ripemd160_count,
         hash160_count,
+        distinct_hashes,
+        redundant_hashes,
     }
 }
 
+/// Recursively collect every hashlock as fragment(hash), in policy order
+fn collect_hashlocks<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>, out: &mut Vec<String>) {
+    match policy {
+        SemanticPolicy::Sha256(h) => out.push(format!("sha256({})", h)),
+        SemanticPolicy::Hash256(h) => out.push(format!("hash256({})", h)),
+        SemanticPolicy::Ripemd160(h) => out.push(format!("ripemd160({})", h)),
+        SemanticPolicy::Hash160(h) => out.push(format!("hash160({})", h)),
+        SemanticPolicy::Thresh(thresh) => {
+            for sub in thresh.iter() {
+                collect_hashlocks(sub, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Find OR branches that duplicate or are dominated by an earlier branch of the same OR
+/// e.g. or(pk(A),pk(A)) or or(older(144),older(288)), where the second branch adds nothing
+fn find_redundant_branches<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let SemanticPolicy::Thresh(thresh) = policy else {
+        return warnings;
+    };
+
+    let children: Vec<&Arc<SemanticPolicy<Pk>>> = thresh.iter().collect();
+    if thresh.k() == 1 && children.len() > 1 {
+        for (j, later) in children.iter().enumerate().skip(1) {
+            for (i, earlier) in children.iter().enumerate().take(j) {
+                if earlier == later {
+                    warnings.push(format!("⚠️ Branch {} is redundant: it duplicates Branch {} ({})", j + 1, i + 1, earlier));
+                    break;
+                }
+                let dominated = if timelock_dominates(earlier, later) {
+                    Some((j, later, i, earlier))
+                } else if timelock_dominates(later, earlier) {
+                    Some((i, earlier, j, later))
+                } else {
+                    None
+                };
+                if let Some((weak_pos, weak, strong_pos, strong)) = dominated {
+                    warnings.push(format!(
+                        "⚠️ Branch {} ({}) is dominated by Branch {} ({}), which always unlocks first",
+                        weak_pos + 1, weak, strong_pos + 1, strong
+                    ));
+                    break;
+                }
+            }
+        }
+    }
+
+    for child in children {
+        warnings.extend(find_redundant_branches(child));
+    }
+    warnings
+}
+
+/// Whether timelock `a` always unlocks no later than timelock `b` (same kind and unit, smaller value)
+fn timelock_dominates<Pk: MiniscriptKey>(a: &SemanticPolicy<Pk>, b: &SemanticPolicy<Pk>) -> bool {
+    match (a, b) {
+        (SemanticPolicy::Older(x), SemanticPolicy::Older(y)) => {
+            x.is_height_locked() == y.is_height_locked() && x.to_consensus_u32() < y.to_consensus_u32()
+        }
+        (SemanticPolicy::After(x), SemanticPolicy::After(y)) => {
+            x.is_block_height() == y.is_block_height() && x.to_consensus_u32() < y.to_consensus_u32()
+        }
+        _ => false,
+    }
+}
+
+/// Find hashlocks that must be satisfied more than once in the same spending path
+/// e.g. and(sha256(H),sha256(H)) asks for the same preimage twice
+fn find_redundant_hashlocks<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>, limits: &PathLimits) -> Vec<String> {
+    let mut all_hashes = Vec::new();
+    collect_hashlocks(policy, &mut all_hashes);
+
+    // Only walk paths when some hash occurs more than once somewhere in the policy
+    let unique: HashSet<&String> = all_hashes.iter().collect();
+    if unique.len() == all_hashes.len() {
+        return vec![];
+    }
+
+    let mut redundant: Vec<String> = Vec::new();
+    for path in get_hashlock_paths(policy, limits) {
+        let mut seen: HashSet<&String> = HashSet::new();
+        for hash in &path {
+            if !seen.insert(hash) && !redundant.contains(hash) {
+                redundant.push(hash.clone());
+            }
+        }
+    }
+    redundant
+}
+
+/// Enumerate spending paths keeping only the hashlocks each path requires
+fn get_hashlock_paths<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>, limits: &PathLimits) -> Vec<Vec<String>> {
+    match policy {
+        SemanticPolicy::Unsatisfiable => vec![],
+        SemanticPolicy::Thresh(thresh) => {
+            let k = thresh.k();
+            let n = thresh.n();
+            let child_paths: Vec<Vec<Vec<String>>> = thresh
+                .iter()
+                .map(|child| get_hashlock_paths(child.as_ref(), limits))
+                .collect();
+
+            // Past the combination cap, or once the combined paths would outgrow it,
+            // only repeats within a single child are checked
+            if k == 1 || exceeds_combination_cap(n, k, limits) {
+                return distinct_hash_sets(child_paths.into_iter().flatten().collect());
+            }
+            let mut result = Vec::new();
+            for combo in generate_combinations(n, k) {
+                let selected: Vec<Vec<Vec<String>>> = combo
+                    .iter()
+                    .filter_map(|&idx| child_paths.get(idx).cloned())
+                    .collect();
+                let product_size = selected.iter()
+                    .filter(|paths| !paths.is_empty())
+                    .fold(1u64, |size, paths| size.saturating_mul(paths.len() as u64));
+                if (result.len() as u64).saturating_add(product_size) > limits.max_thresh_combinations {
+                    return distinct_hash_sets(child_paths.into_iter().flatten().collect());
+                }
+                result.extend(cartesian_product(&selected));
+            }
+            distinct_hash_sets(result)
+        }
+        other => {
+            let mut hashes = Vec::new();
+            collect_hashlocks(other, &mut hashes);
+            vec![hashes]
+        }
+    }
+}
+
+/// Sort each path's hashes and drop duplicate paths: redundancy only depends on which
+/// hashes a path needs, so keys and branch order would otherwise multiply identical paths
+fn distinct_hash_sets(mut paths: Vec<Vec<String>>) -> Vec<Vec<String>> {
+    for path in &mut paths {
+        path.sort();
+    }
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
 /// Recursively count hashlocks in semantic policy
 fn count_hashlocks<Pk: MiniscriptKey>(
     policy: &SemanticPolicy<Pk>,
@@ -392,8 +1068,30 @@ fn count_hashlocks<Pk: MiniscriptKey>(
     }
 }
 
+/// Rate the overall risk of a script from its security properties
+///
+/// Rules, first match wins:
+/// - "dangerous": trivially satisfiable (anyone can spend) or unsatisfiable (funds are lost)
+/// - "caution": some spending path needs no signature (`requires_signature` is false),
+///   or the script is malleable
+/// - "safe": every path needs a signature and the script is non-malleable
+pub fn risk_level<Pk: MiniscriptKey>(
+    policy: &SemanticPolicy<Pk>,
+    requires_signature: bool,
+    is_non_malleable: bool,
+) -> &'static str {
+    let normalized = policy.clone().normalized();
+    if normalized.is_trivial() || normalized.is_unsatisfiable() {
+        "dangerous"
+    } else if !requires_signature || !is_non_malleable {
+        "caution"
+    } else {
+        "safe"
+    }
+}
+
 /// Extract warnings from semantic policy
-fn extract_warnings<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>) -> Vec<String> {
+fn extract_warnings<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>, redundant_hashes: &[String]) -> Vec<String> {
     let mut warnings = Vec::new();
 
     if policy.is_trivial() {
@@ -411,6 +1109,12 @@ fn extract_warnings<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>) -> Vec<Strin
         }
     }
 
+    warnings.extend(find_redundant_branches(policy));
+
+    for hash in redundant_hashes {
+        warnings.push(format!("❌ Redundant hashlock: {} is required more than once in the same spending path", hash));
+    }
+
     warnings
 }
 
@@ -475,6 +1179,8 @@ fn build_tree_node<Pk: MiniscriptKey + std::fmt::Display>(
             k: None,
             n: None,
             children: vec![],
+            human: None,
+            lock_kind: None,
         },
         SemanticPolicy::Trivial => PolicyTreeNode {
             node_type: "trivial".to_string(),
@@ -482,6 +1188,8 @@ fn build_tree_node<Pk: MiniscriptKey + std::fmt::Display>(
             k: None,
             n: None,
             children: vec![],
+            human: None,
+            lock_kind: None,
         },
         SemanticPolicy::Key(pk) => PolicyTreeNode {
             node_type: "pk".to_string(),
@@ -489,27 +1197,49 @@ fn build_tree_node<Pk: MiniscriptKey + std::fmt::Display>(
             k: None,
             n: None,
             children: vec![],
+            human: None,
+            lock_kind: None,
         },
-        SemanticPolicy::After(t) => PolicyTreeNode {
-            node_type: "after".to_string(),
-            value: Some(t.to_string()),
-            k: None,
-            n: None,
-            children: vec![],
-        },
-        SemanticPolicy::Older(t) => PolicyTreeNode {
-            node_type: "older".to_string(),
-            value: Some(t.to_string()),
-            k: None,
-            n: None,
-            children: vec![],
-        },
+        SemanticPolicy::After(t) => {
+            let (human, lock_kind) = if t.is_block_height() {
+                (format!("block {}", t.to_consensus_u32()), "height")
+            } else {
+                (format_unix_timestamp(t.to_consensus_u32() as i64), "time")
+            };
+            PolicyTreeNode {
+                node_type: "after".to_string(),
+                value: Some(t.to_string()),
+                k: None,
+                n: None,
+                children: vec![],
+                human: Some(human),
+                lock_kind: Some(lock_kind.to_string()),
+            }
+        }
+        SemanticPolicy::Older(t) => {
+            let (human, lock_kind) = if t.is_height_locked() {
+                (format!("{} blocks", t.to_consensus_u32()), "height")
+            } else {
+                (format_duration_seconds(t.to_consensus_u32()), "time")
+            };
+            PolicyTreeNode {
+                node_type: "older".to_string(),
+                value: Some(t.to_string()),
+                k: None,
+                n: None,
+                children: vec![],
+                human: Some(human),
+                lock_kind: Some(lock_kind.to_string()),
+            }
+        }
         SemanticPolicy::Sha256(h) => PolicyTreeNode {
             node_type: "sha256".to_string(),
             value: Some(h.to_string()),
             k: None,
             n: None,
             children: vec![],
+            human: None,
+            lock_kind: None,
         },
         SemanticPolicy::Hash256(h) => PolicyTreeNode {
             node_type: "hash256".to_string(),
@@ -517,6 +1247,8 @@ fn build_tree_node<Pk: MiniscriptKey + std::fmt::Display>(
             k: None,
             n: None,
             children: vec![],
+            human: None,
+            lock_kind: None,
         },
         SemanticPolicy::Ripemd160(h) => PolicyTreeNode {
             node_type: "ripemd160".to_string(),
@@ -524,6 +1256,8 @@ fn build_tree_node<Pk: MiniscriptKey + std::fmt::Display>(
             k: None,
             n: None,
             children: vec![],
+            human: None,
+            lock_kind: None,
         },
         SemanticPolicy::Hash160(h) => PolicyTreeNode {
             node_type: "hash160".to_string(),
@@ -531,6 +1265,8 @@ fn build_tree_node<Pk: MiniscriptKey + std::fmt::Display>(
             k: None,
             n: None,
             children: vec![],
+            human: None,
+            lock_kind: None,
         },
         SemanticPolicy::Thresh(thresh) => {
             let k = thresh.k();
@@ -555,6 +1291,8 @@ fn build_tree_node<Pk: MiniscriptKey + std::fmt::Display>(
                 k: Some(k),
                 n: Some(n),
                 children,
+                human: None,
+                lock_kind: None,
             }
         }
     }
@@ -565,7 +1303,15 @@ fn build_tree_node<Pk: MiniscriptKey + std::fmt::Display>(
 pub fn enumerate_spending_paths<Pk: MiniscriptKey + std::fmt::Display>(
     policy: &SemanticPolicy<Pk>,
 ) -> Vec<String> {
-    let paths = get_all_paths(policy);
+    enumerate_spending_paths_with_limits(policy, &PathLimits::default())
+}
+
+/// `enumerate_spending_paths` under caller-supplied enumeration caps
+pub fn enumerate_spending_paths_with_limits<Pk: MiniscriptKey + std::fmt::Display>(
+    policy: &SemanticPolicy<Pk>,
+    limits: &PathLimits,
+) -> Vec<String> {
+    let paths = get_all_paths_with_limits(policy, limits);
 
     // Format paths as human-readable strings
     // Add warning for paths that don't require a signature
@@ -573,7 +1319,7 @@ pub fn enumerate_spending_paths<Pk: MiniscriptKey + std::fmt::Display>(
         .enumerate()
         .map(|(i, conditions)| {
             let path_str = conditions.join(" + ");
-            let has_signature = path_str.contains(" signs");
+            let has_signature = path_has_signature(&path_str);
             if has_signature {
                 format!("Path {}: {}", i + 1, path_str)
             } else {
@@ -588,65 +1334,38 @@ pub fn enumerate_spending_paths<Pk: MiniscriptKey + std::fmt::Display>(
 fn get_all_paths<Pk: MiniscriptKey + std::fmt::Display>(
     policy: &SemanticPolicy<Pk>,
 ) -> Vec<Vec<String>> {
+    get_all_paths_with_limits(policy, &PathLimits::default())
+}
+
+/// `get_all_paths` under caller-supplied enumeration caps
+fn get_all_paths_with_limits<Pk: MiniscriptKey + std::fmt::Display>(
+    policy: &SemanticPolicy<Pk>,
+    limits: &PathLimits,
+) -> Vec<Vec<String>> {
+    collect_paths(policy, &describe_condition, &summarize_thresh_paths, limits)
+}
+
+/// Walk the policy into paths, turning each leaf into a condition with `leaf`
+/// and each thresh too large to enumerate into a single condition with `summary`
+fn collect_paths<Pk: MiniscriptKey, T: Clone>(
+    policy: &SemanticPolicy<Pk>,
+    leaf: &dyn Fn(&SemanticPolicy<Pk>) -> T,
+    summary: &dyn Fn(usize, usize, bool) -> T,
+    limits: &PathLimits,
+) -> Vec<Vec<T>> {
     match policy {
         SemanticPolicy::Unsatisfiable => {
             // No valid paths
             vec![]
         }
-        SemanticPolicy::Trivial => {
-            // One path with no conditions (always satisfiable)
-            vec![vec!["(always true)".to_string()]]
-        }
-        SemanticPolicy::Key(pk) => {
-            vec![vec![format!("{} signs", pk)]]
-        }
-        SemanticPolicy::After(t) => {
-            // Check if it's a block height or timestamp
-            if t.is_block_height() {
-                vec![vec![format!("wait until block {}", t.to_consensus_u32())]]
-            } else {
-                // Time-based: convert Unix timestamp to human-readable date
-                let timestamp = t.to_consensus_u32() as i64;
-                let date = format_unix_timestamp(timestamp);
-                vec![vec![format!("wait until {}", date)]]
-            }
-        }
-        SemanticPolicy::Older(t) => {
-            // Check if it's height-locked (blocks) or time-locked (seconds)
-            if t.is_height_locked() {
-                vec![vec![format!("wait {} blocks", t.to_consensus_u32())]]
-            } else {
-                // Time-based: convert seconds to human-readable duration
-                let seconds = t.to_consensus_u32();
-                let duration = format_duration_seconds(seconds);
-                vec![vec![format!("wait {}", duration)]]
-            }
-        }
-        SemanticPolicy::Sha256(h) => {
-            let hash_str = h.to_string();
-            vec![vec![format!("provide SHA256 preimage for {}", &hash_str[..8.min(hash_str.len())])]]
-        }
-        SemanticPolicy::Hash256(h) => {
-            let hash_str = h.to_string();
-            vec![vec![format!("provide HASH256 preimage for {}", &hash_str[..8.min(hash_str.len())])]]
-        }
-        SemanticPolicy::Ripemd160(h) => {
-            let hash_str = h.to_string();
-            vec![vec![format!("provide RIPEMD160 preimage for {}", &hash_str[..8.min(hash_str.len())])]]
-        }
-        SemanticPolicy::Hash160(h) => {
-            let hash_str = h.to_string();
-            vec![vec![format!("provide HASH160 preimage for {}", &hash_str[..8.min(hash_str.len())])]]
-        }
         SemanticPolicy::Thresh(thresh) => {
             let k = thresh.k();
             let n = thresh.n();
-            let children: Vec<Arc<SemanticPolicy<Pk>>> = thresh.iter().cloned().collect();
 
             // Get all paths for each child
-            let child_paths: Vec<Vec<Vec<String>>> = children
+            let child_paths: Vec<Vec<Vec<T>>> = thresh
                 .iter()
-                .map(|child| get_all_paths(child.as_ref()))
+                .map(|child| collect_paths(child.as_ref(), leaf, summary, limits))
                 .collect();
 
             if k == n {
@@ -659,12 +1378,17 @@ fn get_all_paths<Pk: MiniscriptKey + std::fmt::Display>(
                 child_paths.into_iter().flatten().collect()
             } else {
                 // THRESH(k, n): k-of-n children must be satisfied
+                // Too many combinations: summarize instead of exhausting memory
+                if exceeds_combination_cap(n, k, limits) {
+                    return vec![vec![summary(k, n, thresh_always_signed(thresh))]];
+                }
+
                 // Generate all k-combinations, then cartesian product for each
                 let combinations = generate_combinations(n, k);
                 let mut result = Vec::new();
 
                 for combo in combinations {
-                    let selected_child_paths: Vec<Vec<Vec<String>>> = combo
+                    let selected_child_paths: Vec<Vec<Vec<T>>> = combo
                         .iter()
                         .filter_map(|&idx| child_paths.get(idx).cloned())
                         .collect();
@@ -676,12 +1400,88 @@ fn get_all_paths<Pk: MiniscriptKey + std::fmt::Display>(
                 result
             }
         }
+        // One path with a single condition
+        _ => vec![vec![leaf(policy)]],
+    }
+}
+
+/// Describe a leaf of the semantic policy as a path condition
+fn describe_condition<Pk: MiniscriptKey + std::fmt::Display>(policy: &SemanticPolicy<Pk>) -> String {
+    match policy {
+        SemanticPolicy::Key(pk) => format!("{} signs", pk),
+        SemanticPolicy::After(t) => {
+            // Check if it's a block height or timestamp
+            if t.is_block_height() {
+                format!("wait until block {}", t.to_consensus_u32())
+            } else {
+                // Time-based: convert Unix timestamp to human-readable date
+                let timestamp = t.to_consensus_u32() as i64;
+                format!("wait until {}", format_unix_timestamp(timestamp))
+            }
+        }
+        SemanticPolicy::Older(t) => {
+            // Check if it's height-locked (blocks) or time-locked (seconds)
+            if t.is_height_locked() {
+                format!("wait {} blocks", t.to_consensus_u32())
+            } else {
+                // Time-based: convert seconds to human-readable duration
+                format!("wait {}", format_duration_seconds(t.to_consensus_u32()))
+            }
+        }
+        SemanticPolicy::Sha256(h) => hash_condition("SHA256", &h.to_string()),
+        SemanticPolicy::Hash256(h) => hash_condition("HASH256", &h.to_string()),
+        SemanticPolicy::Ripemd160(h) => hash_condition("RIPEMD160", &h.to_string()),
+        SemanticPolicy::Hash160(h) => hash_condition("HASH160", &h.to_string()),
+        // Trivial; Unsatisfiable and Thresh never reach a leaf
+        _ => "(always true)".to_string(),
+    }
+}
+
+fn hash_condition(name: &str, hash_str: &str) -> String {
+    format!("provide {} preimage for {}", name, short_hash(hash_str))
+}
+
+/// First 8 characters of a hash for display; shorter names (e.g. `H` in a policy) are kept whole
+fn short_hash(hash: &str) -> &str {
+    hash.char_indices().nth(8).map_or(hash, |(i, _)| &hash[..i])
+}
+
+/// Count the paths `get_all_paths` would return, using the same tree walk
+/// AND multiplies child counts, OR sums them, thresh sums products over k-combinations
+fn count_all_paths<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>) -> u64 {
+    match policy {
+        SemanticPolicy::Unsatisfiable => 0,
+        SemanticPolicy::Thresh(thresh) => {
+            let k = thresh.k();
+            let n = thresh.n();
+            let child_counts: Vec<u64> = thresh.iter()
+                .map(|child| count_all_paths(child.as_ref()))
+                .collect();
+
+            if k == 1 && n != 1 {
+                return child_counts.iter().fold(0u64, |acc, c| acc.saturating_add(*c));
+            }
+
+            // cartesian_product skips children with no paths, so they contribute a factor of 1
+            let factors: Vec<u64> = child_counts.iter().map(|&c| c.max(1)).collect();
+
+            // Sum of products over all k-combinations (elementary symmetric polynomial e_k)
+            let mut sums = vec![0u64; k + 1];
+            sums[0] = 1;
+            for factor in factors {
+                for j in (1..=k).rev() {
+                    sums[j] = sums[j].saturating_add(sums[j - 1].saturating_mul(factor));
+                }
+            }
+            sums[k]
+        }
+        _ => 1,
     }
 }
 
 /// Compute cartesian product of path sets
 /// Given [[a, b], [c, d]], returns [[a, c], [a, d], [b, c], [b, d]]
-fn cartesian_product(path_sets: &[Vec<Vec<String>>]) -> Vec<Vec<String>> {
+fn cartesian_product<T: Clone>(path_sets: &[Vec<Vec<T>>]) -> Vec<Vec<T>> {
     if path_sets.is_empty() {
         return vec![vec![]];
     }
@@ -707,6 +1507,117 @@ fn cartesian_product(path_sets: &[Vec<Vec<String>>]) -> Vec<Vec<String>> {
     result
 }
 
+/// Largest C(n,k) a single thresh may expand to before paths are summarized
+pub const MAX_THRESH_COMBINATIONS: u64 = 10_000;
+
+/// Hard ceilings on caller-supplied `PathLimits`, so a huge request cannot stall the page
+pub const MAX_PATHS_TO_ENUMERATE_CEILING: usize = 1_000;
+pub const MAX_THRESH_COMBINATIONS_CEILING: u64 = 1_000_000;
+
+/// Runtime caps on spending path enumeration, defaulting to the compile-time constants
+/// Raise them to enumerate large thresholds in full, or lower them on constrained devices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathLimits {
+    /// Paths listed per group before the group collapses to a preview
+    pub max_paths_to_enumerate: usize,
+    /// Largest C(n,k) a single thresh may expand to before it is summarized
+    pub max_thresh_combinations: u64,
+}
+
+impl Default for PathLimits {
+    fn default() -> Self {
+        PathLimits {
+            max_paths_to_enumerate: MAX_PATHS_TO_ENUMERATE,
+            max_thresh_combinations: MAX_THRESH_COMBINATIONS,
+        }
+    }
+}
+
+impl PathLimits {
+    /// Defaults with any caller-supplied caps applied, clamped to the hard ceilings
+    pub fn new(max_paths_to_enumerate: Option<usize>, max_thresh_combinations: Option<u64>) -> Self {
+        let defaults = PathLimits::default();
+        PathLimits {
+            max_paths_to_enumerate: max_paths_to_enumerate
+                .unwrap_or(defaults.max_paths_to_enumerate)
+                .min(MAX_PATHS_TO_ENUMERATE_CEILING),
+            max_thresh_combinations: max_thresh_combinations
+                .unwrap_or(defaults.max_thresh_combinations)
+                .min(MAX_THRESH_COMBINATIONS_CEILING),
+        }
+    }
+}
+
+/// Marker appended to summarized paths that were not enumerated
+const NOT_ENUMERATED: &str = "not enumerated";
+
+/// Marker on a summarized thresh whose every k-of-n selection needs a signature
+const SIGNED_SUMMARY: &str = "each with a signature";
+
+/// Binomial coefficient C(n,k), saturating at u64::MAX
+fn binomial(n: usize, k: usize) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k) as u64;
+    let n = n as u64;
+    let mut result: u64 = 1;
+    for i in 0..k {
+        // result * (n - i) / (i + 1) stays integral at every step
+        result = match result.checked_mul(n - i) {
+            Some(product) => product / (i + 1),
+            None => return u64::MAX,
+        };
+    }
+    result
+}
+
+/// Whether enumerating k-of-n combinations would exceed the thresh combination cap
+fn exceeds_combination_cap(n: usize, k: usize, limits: &PathLimits) -> bool {
+    binomial(n, k) > limits.max_thresh_combinations
+}
+
+/// Single placeholder condition standing in for a thresh that was not enumerated
+/// `signed` marks a thresh that needs a signature whichever children are picked
+fn summarize_thresh_paths(k: usize, n: usize, signed: bool) -> String {
+    if signed {
+        format!("any {} of {} conditions ({} combinations, {}, {})", k, n, binomial(n, k), NOT_ENUMERATED, SIGNED_SUMMARY)
+    } else {
+        format!("any {} of {} conditions ({} combinations, {})", k, n, binomial(n, k), NOT_ENUMERATED)
+    }
+}
+
+/// Whether every k-of-n selection of a thresh includes a child that always needs a signature
+/// That holds when at least n-k+1 children need a key on every one of their paths
+fn thresh_always_signed<Pk: MiniscriptKey>(thresh: &Threshold<Arc<SemanticPolicy<Pk>>, 0>) -> bool {
+    let signed_children = thresh.iter()
+        .filter(|child| child.minimum_n_keys().is_some_and(|keys| keys > 0))
+        .count();
+    signed_children + thresh.k() > thresh.n()
+}
+
+/// Whether a path (conditions joined as in `spending_paths`) always needs a signature
+fn path_has_signature(path: &str) -> bool {
+    path.contains(" signs") || path.contains(SIGNED_SUMMARY)
+}
+
+/// Whether any thresh in the policy was summarized instead of enumerated
+pub fn paths_truncated<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>) -> bool {
+    paths_truncated_with_limits(policy, &PathLimits::default())
+}
+
+/// `paths_truncated` under caller-supplied enumeration caps
+pub fn paths_truncated_with_limits<Pk: MiniscriptKey>(policy: &SemanticPolicy<Pk>, limits: &PathLimits) -> bool {
+    match policy {
+        SemanticPolicy::Thresh(thresh) => {
+            let (k, n) = (thresh.k(), thresh.n());
+            (k != 1 && k != n && exceeds_combination_cap(n, k, limits))
+                || thresh.iter().any(|child| paths_truncated_with_limits(child.as_ref(), limits))
+        }
+        _ => false,
+    }
+}
+
 /// Generate k-of-n combinations (indices)
 fn generate_combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
     let mut result = Vec::new();
@@ -808,7 +1719,7 @@ const PREVIEW_PATHS_COUNT: usize = 3;
 /// Format a path with optional warning if no signature is required
 fn format_path_with_warning(conditions: &[String]) -> String {
     let path_str = conditions.join(" + ");
-    let has_signature = path_str.contains(" signs");
+    let has_signature = path_has_signature(&path_str);
     if has_signature {
         path_str
     } else {
@@ -821,7 +1732,15 @@ fn format_path_with_warning(conditions: &[String]) -> String {
 pub fn get_grouped_paths<Pk: MiniscriptKey + std::fmt::Display>(
     policy: &SemanticPolicy<Pk>,
 ) -> Vec<SpendingPathGroup> {
-    get_grouped_paths_recursive(policy, 1).groups
+    get_grouped_paths_with_limits(policy, &PathLimits::default())
+}
+
+/// `get_grouped_paths` under caller-supplied enumeration caps
+pub fn get_grouped_paths_with_limits<Pk: MiniscriptKey + std::fmt::Display>(
+    policy: &SemanticPolicy<Pk>,
+    limits: &PathLimits,
+) -> Vec<SpendingPathGroup> {
+    get_grouped_paths_recursive(policy, 1, limits).groups
 }
 
 /// Internal result type for recursive grouped path generation
@@ -834,6 +1753,7 @@ struct GroupedPathsResult {
 fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
     policy: &SemanticPolicy<Pk>,
     branch_number: usize,
+    limits: &PathLimits,
 ) -> GroupedPathsResult {
     match policy {
         SemanticPolicy::Unsatisfiable => GroupedPathsResult {
@@ -850,6 +1770,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                     paths: Some(vec!["(always true)".to_string()]),
                     preview_paths: None,
                     children: None,
+                    weight_percent: None,
                 }],
                 flat_paths: paths,
             }
@@ -864,6 +1785,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                     paths: Some(vec![format!("{} signs", pk)]),
                     preview_paths: None,
                     children: None,
+                    weight_percent: None,
                 }],
                 flat_paths: vec![path],
             }
@@ -884,6 +1806,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                     paths: Some(vec![condition.clone()]),
                     preview_paths: None,
                     children: None,
+                    weight_percent: None,
                 }],
                 flat_paths: vec![vec![condition]],
             }
@@ -904,6 +1827,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                     paths: Some(vec![condition.clone()]),
                     preview_paths: None,
                     children: None,
+                    weight_percent: None,
                 }],
                 flat_paths: vec![vec![condition]],
             }
@@ -920,6 +1844,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                     paths: Some(vec![condition.clone()]),
                     preview_paths: None,
                     children: None,
+                    weight_percent: None,
                 }],
                 flat_paths: vec![vec![condition]],
             }
@@ -936,6 +1861,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                     paths: Some(vec![condition.clone()]),
                     preview_paths: None,
                     children: None,
+                    weight_percent: None,
                 }],
                 flat_paths: vec![vec![condition]],
             }
@@ -952,6 +1878,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                     paths: Some(vec![condition.clone()]),
                     preview_paths: None,
                     children: None,
+                    weight_percent: None,
                 }],
                 flat_paths: vec![vec![condition]],
             }
@@ -968,6 +1895,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                     paths: Some(vec![condition.clone()]),
                     preview_paths: None,
                     children: None,
+                    weight_percent: None,
                 }],
                 flat_paths: vec![vec![condition]],
             }
@@ -983,7 +1911,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                 let mut all_flat_paths = Vec::new();
 
                 for (i, child) in children.iter().enumerate() {
-                    let child_result = get_grouped_paths_recursive(child.as_ref(), i + 1);
+                    let child_result = get_grouped_paths_recursive(child.as_ref(), i + 1, limits);
                     all_flat_paths.extend(child_result.flat_paths);
 
                     // Generate a smart label for this branch
@@ -1001,15 +1929,16 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                             paths: None,
                             preview_paths: None,
                             children: Some(child_result.groups),
+                            weight_percent: None,
                         });
                     } else if let Some(single_group) = child_result.groups.into_iter().next() {
                         // Single group from child - apply warning formatting to paths
                         // Check both for signature AND for existing warning to avoid duplicates
-                        let (paths, preview_paths) = if child_path_count <= MAX_PATHS_TO_ENUMERATE {
+                        let (paths, preview_paths) = if child_path_count <= limits.max_paths_to_enumerate {
                             // Apply warning to each path (if not already present)
                             let formatted_paths = single_group.paths.map(|ps| {
                                 ps.iter().map(|p| {
-                                    if p.contains(" signs") || p.contains("⚠️") {
+                                    if path_has_signature(p) || p.contains("⚠️") {
                                         p.clone()
                                     } else {
                                         format!("{} ⚠️ (no signature required)", p)
@@ -1021,7 +1950,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                             // Apply warning to preview paths (if not already present)
                             let formatted_preview = single_group.preview_paths.map(|ps| {
                                 ps.iter().map(|p| {
-                                    if p.contains(" signs") || p.contains("⚠️") {
+                                    if path_has_signature(p) || p.contains("⚠️") {
                                         p.clone()
                                     } else {
                                         format!("{} ⚠️ (no signature required)", p)
@@ -1037,6 +1966,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                             paths,
                             preview_paths,
                             children: None,
+                            weight_percent: None,
                         });
                     }
                 }
@@ -1046,10 +1976,26 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                     flat_paths: all_flat_paths,
                 }
             } else {
+                // THRESH with too many combinations: report the count only
+                if k != n && exceeds_combination_cap(n, k, limits) {
+                    return GroupedPathsResult {
+                        groups: vec![SpendingPathGroup {
+                            label: format!("Branch {}", branch_number),
+                            summary: Some(generate_thresh_summary::<Pk>(&children, k, n)),
+                            path_count: usize::try_from(count_all_paths(policy)).unwrap_or(usize::MAX),
+                            paths: None,
+                            preview_paths: None,
+                            children: None,
+                            weight_percent: None,
+                        }],
+                        flat_paths: vec![vec![summarize_thresh_paths(k, n, thresh_always_signed(thresh))]],
+                    };
+                }
+
                 // AND or THRESH: Combine into single group
                 let child_paths: Vec<Vec<Vec<String>>> = children
                     .iter()
-                    .map(|child| get_all_paths(child.as_ref()))
+                    .map(|child| get_all_paths_with_limits(child.as_ref(), limits))
                     .collect();
 
                 let flat_paths = if k == n {
@@ -1071,7 +2017,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
 
                 let path_count = flat_paths.len();
                 let summary = generate_thresh_summary::<Pk>(&children, k, n);
-                let (paths, preview_paths) = if path_count <= MAX_PATHS_TO_ENUMERATE {
+                let (paths, preview_paths) = if path_count <= limits.max_paths_to_enumerate {
                     (Some(flat_paths.iter().map(|p| format_path_with_warning(p)).collect()), None)
                 } else {
                     // Show first 3 paths as preview
@@ -1090,6 +2036,7 @@ fn get_grouped_paths_recursive<Pk: MiniscriptKey + std::fmt::Display>(
                         paths,
                         preview_paths,
                         children: None,
+                        weight_percent: None,
                     }],
                     flat_paths,
                 }
@@ -1119,6 +2066,7 @@ fn generate_branch_summary<Pk: MiniscriptKey + std::fmt::Display>(
             // Collect keys
             let mut keys: Vec<String> = Vec::new();
             let mut has_timelock = false;
+            let mut has_compound = false;
             let mut timelock_desc = String::new();
 
             for child in thresh.iter() {
@@ -1144,18 +2092,24 @@ fn generate_branch_summary<Pk: MiniscriptKey + std::fmt::Display>(
                             timelock_desc = format!("+ after {}", date);
                         }
                     }
-                    SemanticPolicy::Thresh(inner) => {
-                        // Nested threshold - collect its keys
-                        for inner_child in inner.iter() {
-                            if let SemanticPolicy::Key(pk) = inner_child.as_ref() {
-                                keys.push(pk.to_string());
-                            }
-                        }
+                    _ => {
+                        // Nested threshold or hashlock - summarise the children as written
+                        has_compound = true;
                     }
-                    _ => {}
                 }
             }
 
+            if has_compound {
+                let parts: Vec<String> = thresh.iter().map(|c| describe_policy_compact(c.as_ref())).collect();
+                return if k == n {
+                    Some(parts.join(" + "))
+                } else if k == 1 {
+                    Some(format!("any of {{{}}}", parts.join(", ")))
+                } else {
+                    Some(format!("{}-of-{} threshold: {{{}}}", k, n, parts.join(", ")))
+                };
+            }
+
             if keys.is_empty() {
                 return Some(format!("{}-of-{}", k, n));
             }
@@ -1230,7 +2184,50 @@ fn generate_thresh_summary<Pk: MiniscriptKey + std::fmt::Display>(
         };
         format!("{}-of-{} multisig: {}", k, n, keys_str)
     } else {
-        format!("{}-of-{} threshold", k, n)
+        // Mixed children - describe each subexpression instead of dropping it
+        let parts: Vec<String> = children.iter().map(|c| describe_policy_compact(c.as_ref())).collect();
+        format!("{}-of-{} threshold: {{{}}}", k, n, parts.join(", "))
+    }
+}
+
+/// Describe a semantic policy in one short line for use inside summaries
+fn describe_policy_compact<Pk: MiniscriptKey + std::fmt::Display>(
+    policy: &SemanticPolicy<Pk>,
+) -> String {
+    match policy {
+        SemanticPolicy::Unsatisfiable => "never".to_string(),
+        SemanticPolicy::Trivial => "always".to_string(),
+        SemanticPolicy::Key(pk) => pk.to_string(),
+        SemanticPolicy::After(t) => {
+            if t.is_block_height() {
+                format!("after block {}", t.to_consensus_u32())
+            } else {
+                format!("after {}", format_unix_timestamp(t.to_consensus_u32() as i64))
+            }
+        }
+        SemanticPolicy::Older(t) => {
+            if t.is_height_locked() {
+                format!("wait {} blocks", t.to_consensus_u32())
+            } else {
+                format!("wait {}", format_duration_seconds(t.to_consensus_u32()))
+            }
+        }
+        SemanticPolicy::Sha256(h) => format!("sha256({}...)", short_hash(&h.to_string())),
+        SemanticPolicy::Hash256(h) => format!("hash256({}...)", short_hash(&h.to_string())),
+        SemanticPolicy::Ripemd160(h) => format!("ripemd160({}...)", short_hash(&h.to_string())),
+        SemanticPolicy::Hash160(h) => format!("hash160({}...)", short_hash(&h.to_string())),
+        SemanticPolicy::Thresh(thresh) => {
+            let k = thresh.k();
+            let n = thresh.n();
+            let parts: Vec<String> = thresh.iter().map(|c| describe_policy_compact(c.as_ref())).collect();
+            if k == n {
+                format!("({})", parts.join(" + "))
+            } else if k == 1 {
+                format!("({})", parts.join(" or "))
+            } else {
+                format!("{}-of-{}({})", k, n, parts.join(", "))
+            }
+        }
     }
 }
 
@@ -1246,4 +2243,87 @@ mod tests {
         assert!(combos.contains(&vec![0, 2]));
         assert!(combos.contains(&vec![1, 2]));
     }
+
+    #[test]
+    fn test_binomial_matches_generated_combinations() {
+        for (n, k) in [(3, 2), (5, 0), (6, 3), (8, 8)] {
+            assert_eq!(binomial(n, k), generate_combinations(n, k).len() as u64);
+        }
+        assert_eq!(binomial(20, 10), 184_756);
+        assert_eq!(binomial(2, 3), 0);
+        assert!(exceeds_combination_cap(20, 10, &PathLimits::default()));
+        assert!(!exceeds_combination_cap(15, 3, &PathLimits::default()));
+    }
+
+    #[test]
+    fn test_analyze_andor_with_sigless_branch() {
+        let analysis = perform_miniscript_analysis::<Segwitv0>("andor(pk(A),pk(B),older(144))", &PathLimits::default()).unwrap();
+
+        let paths = analysis.spending_paths.unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0], "Path 1: A signs + B signs");
+
+        let security = analysis.security.unwrap();
+        assert!(!security.passes_sanity_check);
+        assert!(!security.requires_signature);
+        assert!(analysis.warnings.unwrap().iter().any(|w| w.contains("SANITY")));
+    }
+
+    #[test]
+    fn test_analysis_rejects_other_insane_miniscripts() {
+        assert!(perform_miniscript_analysis::<Segwitv0>("and_n(pk(A),older(144))", &PathLimits::default()).is_ok());
+        // Sigless without andor/and_n, and repeated keys, still fail to parse
+        assert!(perform_miniscript_analysis::<Segwitv0>("or_d(pk(A),older(144))", &PathLimits::default()).is_err());
+        assert!(perform_miniscript_analysis::<Segwitv0>("and_v(v:pk(A),pk(A))", &PathLimits::default()).is_err());
+        // An andor elsewhere in the script does not excuse a sigless path from another fragment
+        assert!(perform_miniscript_analysis::<Segwitv0>("or_d(andor(pk(A),pk(B),pk(C)),older(144))", &PathLimits::default()).is_err());
+        // Nor does it excuse a sane-check failure other than the sigless branch
+        assert!(perform_miniscript_analysis::<Segwitv0>("andor(pk(A),pk(B),pk(A))", &PathLimits::default()).is_err());
+    }
+
+    #[test]
+    fn test_hashlock_distinct_vs_repeated() {
+        let h1 = "0000000000000000000000000000000000000000000000000000000000000001";
+        let h2 = "0000000000000000000000000000000000000000000000000000000000000002";
+
+        let distinct = perform_policy_analysis(&format!("and(pk(A),and(sha256({}),sha256({})))", h1, h2)).unwrap();
+        let hashlocks = distinct.hashlocks.unwrap();
+        assert_eq!(hashlocks.sha256_count, 2);
+        assert_eq!(hashlocks.distinct_hashes.len(), 2);
+        assert!(hashlocks.redundant_hashes.is_empty());
+
+        let repeated = perform_policy_analysis(&format!("and(pk(A),and(sha256({}),sha256({})))", h1, h1)).unwrap();
+        let hashlocks = repeated.hashlocks.unwrap();
+        assert_eq!(hashlocks.sha256_count, 2);
+        assert_eq!(hashlocks.distinct_hashes, vec![format!("sha256({})", h1)]);
+        assert_eq!(hashlocks.redundant_hashes, vec![format!("sha256({})", h1)]);
+        assert!(repeated.warnings.unwrap().iter().any(|w| w.contains("Redundant hashlock")));
+
+        // Same hash in separate OR branches is not redundant
+        let branches = perform_policy_analysis(&format!("or(and(pk(A),sha256({})),and(pk(B),sha256({})))", h1, h1)).unwrap();
+        assert!(branches.hashlocks.unwrap().redundant_hashes.is_empty());
+    }
+
+    #[test]
+    fn test_hashlock_paths_capped_for_wide_and_of_ors() {
+        let h1 = "0000000000000000000000000000000000000000000000000000000000000001";
+
+        // 2^24 combined paths if the product were expanded in full
+        let mut policy = format!("and(sha256({}),sha256({}))", h1, h1);
+        for i in 0..24 {
+            policy = format!("and(or(pk(A{}),pk(B{})),{})", i, i, policy);
+        }
+        let semantic = policy.parse::<miniscript::policy::Concrete<String>>().unwrap().lift().unwrap();
+        let limits = PathLimits::default();
+        assert!(get_hashlock_paths(&semantic, &limits).len() as u64 <= limits.max_thresh_combinations);
+        assert_eq!(find_redundant_hashlocks(&semantic, &limits), vec![format!("sha256({})", h1)]);
+
+        // Distinct hashes on every branch make every combined path different; the cap still holds
+        let mut policy = format!("sha256({})", h1);
+        for i in 0..24 {
+            policy = format!("and(or(sha256({:064x}),sha256({:064x})),{})", 2 * i + 2, 2 * i + 3, policy);
+        }
+        let semantic = policy.parse::<miniscript::policy::Concrete<String>>().unwrap().lift().unwrap();
+        assert!(get_hashlock_paths(&semantic, &limits).len() as u64 <= limits.max_thresh_combinations);
+    }
 }
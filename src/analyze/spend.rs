@@ -0,0 +1,292 @@
+//! Cheapest spending path selection
+//!
+//! Plans a satisfaction for every enumerated spending path and prices it at a
+//! given fee rate. Taproot descriptors with a spendable internal key include the
+//! key-path spend, which the planner prefers whenever its key can sign.
+
+use std::str::FromStr;
+use bitcoin::absolute;
+use bitcoin::relative;
+use bitcoin::hashes::{hash160, ripemd160, sha256};
+use bitcoin::taproot::TapLeafHash;
+use miniscript::{DefiniteDescriptorKey, Descriptor, DescriptorPublicKey, Legacy, Miniscript, Segwitv0, Tap};
+use miniscript::{AbsLockTime, RelLockTime, hash256};
+use miniscript::plan::AssetProvider;
+use miniscript::policy::{Concrete, Liftable, semantic::Policy as SemanticPolicy};
+use serde::{Deserialize, Serialize};
+
+use crate::console_log;
+use crate::taproot::utils::{get_nums_descriptor_key, NUMS_POINT};
+use super::get_all_paths;
+
+/// Size of a Schnorr signature with the default sighash
+const SCHNORR_SIG_LEN: usize = 64;
+
+/// Largest standard ECDSA signature: 71 DER bytes (low-S) plus the sighash byte
+const MAX_ECDSA_SIG_LEN: usize = 72;
+
+/// The spending path with the lowest fee at a given fee rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheapestSpend {
+    /// 1-based path number, as in the analysis path list
+    pub path: usize,
+    /// Conditions required by the path
+    pub conditions: Vec<String>,
+    /// "key-path" or "script-path" for taproot, "script" otherwise
+    pub spend_type: String,
+    /// Weight of the scriptSig and witness in weight units
+    pub satisfaction_weight: u64,
+    /// Fee for the satisfaction data, rounded up to a whole satoshi
+    pub fee_sats: u64,
+}
+
+/// Result wrapper for the WASM cheapest spend endpoint
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CheapestSpendResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub cheapest: Option<CheapestSpend>,
+}
+
+/// Find the spending path whose satisfaction costs the least at `fee_rate_sat_vb`
+///
+/// The expression can be a descriptor, a miniscript or a policy; bare miniscripts and
+/// policies are wrapped for the context (taproot ones under the NUMS key, with the policy
+/// compiler extracting a key-path key when it can). Keys must be concrete so scripts can be planned.
+pub fn cheapest_spend(expression: &str, context: &str, fee_rate_sat_vb: f64) -> Result<CheapestSpend, String> {
+    if !fee_rate_sat_vb.is_finite() || fee_rate_sat_vb < 0.0 {
+        return Err(format!("Invalid fee rate: {}", fee_rate_sat_vb));
+    }
+
+    let descriptor = spend_descriptor(expression.trim(), context)?
+        .at_derivation_index(0)
+        .map_err(|e| format!("Failed to derive descriptor keys: {}", e))?;
+    let semantic = descriptor.lift()
+        .map_err(|e| format!("Failed to lift descriptor: {}", e))?;
+    let is_taproot = matches!(descriptor, Descriptor::Tr(_));
+
+    let mut cheapest: Option<CheapestSpend> = None;
+    for (i, conditions) in get_all_paths(&semantic).into_iter().enumerate() {
+        let provider = PathAssets { conditions: &conditions };
+        let Ok(plan) = descriptor.clone().plan(&provider) else {
+            continue;
+        };
+
+        let satisfaction_weight = plan.satisfaction_weight() as u64;
+        let spend_type = match (is_taproot, plan.witness_template().len()) {
+            (true, 1) => "key-path",
+            (true, _) => "script-path",
+            (false, _) => "script",
+        };
+        if cheapest.as_ref().is_some_and(|best| best.satisfaction_weight <= satisfaction_weight) {
+            continue;
+        }
+        cheapest = Some(CheapestSpend {
+            path: i + 1,
+            conditions,
+            spend_type: spend_type.to_string(),
+            satisfaction_weight,
+            fee_sats: (satisfaction_weight as f64 / 4.0 * fee_rate_sat_vb).ceil() as u64,
+        });
+    }
+
+    let cheapest = cheapest.ok_or("No spending path can be planned")?;
+    console_log!("Cheapest spend: path {} ({} WU, {} sats)", cheapest.path, cheapest.satisfaction_weight, cheapest.fee_sats);
+    Ok(cheapest)
+}
+
+/// Plan each path (given as `get_all_paths` conditions) and return its satisfaction weight
+/// Every entry is None when the expression cannot be turned into a descriptor with concrete keys
+pub(crate) fn path_witness_weights(expression: &str, context: &str, paths: &[Vec<String>]) -> Vec<Option<u64>> {
+    let descriptor = spend_descriptor(expression.trim(), context)
+        .and_then(|descriptor| descriptor.at_derivation_index(0).map_err(|e| e.to_string()));
+    let Ok(descriptor) = descriptor else {
+        return vec![None; paths.len()];
+    };
+
+    paths.iter()
+        .map(|conditions| {
+            let provider = PathAssets { conditions };
+            descriptor.clone().plan(&provider).ok().map(|plan| plan.satisfaction_weight() as u64)
+        })
+        .collect()
+}
+
+/// Composition of the worst-case satisfaction weight of a k-of-n multisig spend
+///
+/// `total_wu` is rust-miniscript's `max_weight_to_satisfy`. It equals
+/// `scale * (script_bytes + num_required * per_signature_bytes) + redeem_scale * control_or_redeem_bytes + overhead_wu`,
+/// where scriptSig bytes weigh 4 WU (P2SH, and the nested P2WSH redeem script) and witness bytes 1 WU
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightBreakdown {
+    /// Size of the script revealed at spend time (redeem, witness or leaf script)
+    pub script_bytes: usize,
+    /// Worst-case size of one signature, sighash byte included
+    pub per_signature_bytes: usize,
+    /// Signatures needed (k)
+    pub num_required: usize,
+    /// Taproot control block, or the P2WSH redeem script of a nested output; 0 otherwise
+    pub control_or_redeem_bytes: usize,
+    /// Length prefixes, stack item counts and the CHECKMULTISIG dummy element, in weight units
+    pub overhead_wu: u64,
+    /// Worst-case weight of the scriptSig and witness
+    pub total_wu: u64,
+}
+
+/// Result wrapper for the WASM weight breakdown endpoint
+#[derive(Serialize, Deserialize)]
+pub(crate) struct WeightBreakdownResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub breakdown: Option<WeightBreakdown>,
+}
+
+/// Break the worst-case spend weight of a k-of-n multisig into its parts
+///
+/// Accepts the same inputs as `cheapest_spend`. The script must require k signatures from n keys
+/// and nothing else (multi, sortedmulti, multi_a or a thresh of keys); taproot needs a single leaf.
+pub fn weight_breakdown(expression: &str, context: &str) -> Result<WeightBreakdown, String> {
+    use miniscript::descriptor::ShInner;
+
+    let descriptor = spend_descriptor(expression.trim(), context)?
+        .at_derivation_index(0)
+        .map_err(|e| format!("Failed to derive descriptor keys: {}", e))?;
+    let total_wu = descriptor.max_weight_to_satisfy()
+        .map_err(|e| format!("Cannot compute satisfaction weight: {}", e))?
+        .to_wu();
+
+    // (script bytes, semantic policy of the script, signature size, witness scale, redeem bytes, redeem scale)
+    let (script_bytes, policy, per_signature_bytes, scale, control_or_redeem_bytes, redeem_scale) = match &descriptor {
+        Descriptor::Sh(sh) => {
+            let script_bytes = descriptor.explicit_script().map_err(|e| e.to_string())?.len();
+            let policy = descriptor.lift().map_err(|e| format!("Failed to lift descriptor: {}", e))?;
+            match sh.as_inner() {
+                ShInner::Wsh(_) => (script_bytes, policy, MAX_ECDSA_SIG_LEN, 1, 34, 4),
+                ShInner::Ms(_) | ShInner::SortedMulti(_) => (script_bytes, policy, MAX_ECDSA_SIG_LEN, 4, 0, 4),
+                ShInner::Wpkh(_) => return Err("Weight breakdown needs a multisig script, not sh(wpkh)".to_string()),
+            }
+        }
+        Descriptor::Wsh(_) => {
+            let script_bytes = descriptor.explicit_script().map_err(|e| e.to_string())?.len();
+            let policy = descriptor.lift().map_err(|e| format!("Failed to lift descriptor: {}", e))?;
+            (script_bytes, policy, MAX_ECDSA_SIG_LEN, 1, 0, 1)
+        }
+        Descriptor::Tr(tr) => {
+            let leaves: Vec<_> = tr.iter_scripts().collect();
+            let [(depth, leaf)] = leaves.as_slice() else {
+                return Err(format!("Weight breakdown needs a taproot tree with exactly one leaf, found {}", leaves.len()));
+            };
+            let policy = leaf.lift().map_err(|e| format!("Failed to lift leaf: {}", e))?;
+            (leaf.script_size(), policy, SCHNORR_SIG_LEN + 1, 1, 33 + 32 * *depth as usize, 1)
+        }
+        _ => return Err("Weight breakdown needs a multisig script (sh, wsh, sh(wsh) or tr with one leaf)".to_string()),
+    };
+
+    let num_required = match &policy {
+        SemanticPolicy::Key(_) => 1,
+        SemanticPolicy::Thresh(thresh) if thresh.iter().all(|sub| matches!(**sub, SemanticPolicy::Key(_))) => thresh.k(),
+        _ => return Err("Weight breakdown needs a script that only requires k-of-n signatures".to_string()),
+    };
+
+    let components = scale * (script_bytes + num_required * per_signature_bytes) as u64
+        + redeem_scale * control_or_redeem_bytes as u64;
+    let overhead_wu = total_wu.checked_sub(components)
+        .ok_or_else(|| format!("Satisfaction weight {} WU is below its components ({} WU)", total_wu, components))?;
+
+    console_log!("Weight breakdown: {} WU ({} sigs of {} bytes, {} byte script)", total_wu, num_required, per_signature_bytes, script_bytes);
+    Ok(WeightBreakdown {
+        script_bytes,
+        per_signature_bytes,
+        num_required,
+        control_or_redeem_bytes,
+        overhead_wu,
+        total_wu,
+    })
+}
+
+// Turn the input into a descriptor: descriptors as-is, miniscripts wrapped, policies compiled
+fn spend_descriptor(expression: &str, context: &str) -> Result<Descriptor<DescriptorPublicKey>, String> {
+    if let Ok(descriptor) = Descriptor::<DescriptorPublicKey>::from_str(expression) {
+        return Ok(descriptor);
+    }
+
+    let context = context.to_lowercase();
+    let wrapped = match context.as_str() {
+        "legacy" => Miniscript::<DescriptorPublicKey, Legacy>::from_str(expression).ok()
+            .map(|ms| format!("sh({})", ms)),
+        "segwit" => Miniscript::<DescriptorPublicKey, Segwitv0>::from_str(expression).ok()
+            .map(|ms| format!("wsh({})", ms)),
+        "taproot" => Miniscript::<DescriptorPublicKey, Tap>::from_str(expression).ok()
+            .map(|ms| format!("tr({},{})", NUMS_POINT, ms)),
+        _ => return Err(format!("Unknown context: {}. Use legacy, segwit, or taproot.", context)),
+    };
+    if let Some(wrapped) = wrapped {
+        return Descriptor::from_str(&wrapped).map_err(|e| format!("Failed to build descriptor: {}", e));
+    }
+
+    let policy = Concrete::<DescriptorPublicKey>::from_str(expression)
+        .map_err(|e| format!("Expression is neither a descriptor, a miniscript nor a policy: {}", e))?;
+    match context.as_str() {
+        "legacy" => policy.compile::<Legacy>()
+            .map_err(|e| format!("Policy compilation failed: {}", e))
+            .and_then(|ms| Descriptor::new_sh(ms).map_err(|e| e.to_string())),
+        "segwit" => policy.compile::<Segwitv0>()
+            .map_err(|e| format!("Policy compilation failed: {}", e))
+            .and_then(|ms| Descriptor::new_wsh(ms).map_err(|e| e.to_string())),
+        _ => {
+            policy.compile_tr(Some(get_nums_descriptor_key())).map_err(|e| format!("Policy compilation failed: {}", e))
+        }
+    }
+}
+
+// Answers asset lookups from one path's condition list, worded as `get_all_paths` words them
+struct PathAssets<'a> {
+    conditions: &'a [String],
+}
+
+impl PathAssets<'_> {
+    fn allows(&self, atom: SemanticPolicy<DefiniteDescriptorKey>) -> bool {
+        get_all_paths(&atom).into_iter().flatten()
+            .all(|condition| self.conditions.contains(&condition))
+    }
+}
+
+impl AssetProvider<DefiniteDescriptorKey> for PathAssets<'_> {
+    fn provider_lookup_ecdsa_sig(&self, pk: &DefiniteDescriptorKey) -> bool {
+        self.allows(SemanticPolicy::Key(pk.clone()))
+    }
+
+    fn provider_lookup_tap_key_spend_sig(&self, pk: &DefiniteDescriptorKey) -> Option<usize> {
+        self.allows(SemanticPolicy::Key(pk.clone())).then_some(SCHNORR_SIG_LEN)
+    }
+
+    fn provider_lookup_tap_leaf_script_sig(&self, pk: &DefiniteDescriptorKey, _: &TapLeafHash) -> Option<usize> {
+        self.allows(SemanticPolicy::Key(pk.clone())).then_some(SCHNORR_SIG_LEN)
+    }
+
+    fn provider_lookup_sha256(&self, hash: &sha256::Hash) -> bool {
+        self.allows(SemanticPolicy::Sha256(*hash))
+    }
+
+    fn provider_lookup_hash256(&self, hash: &hash256::Hash) -> bool {
+        self.allows(SemanticPolicy::Hash256(*hash))
+    }
+
+    fn provider_lookup_ripemd160(&self, hash: &ripemd160::Hash) -> bool {
+        self.allows(SemanticPolicy::Ripemd160(*hash))
+    }
+
+    fn provider_lookup_hash160(&self, hash: &hash160::Hash) -> bool {
+        self.allows(SemanticPolicy::Hash160(*hash))
+    }
+
+    fn check_older(&self, lock: relative::LockTime) -> bool {
+        RelLockTime::try_from(lock.to_sequence())
+            .is_ok_and(|lock| self.allows(SemanticPolicy::Older(lock)))
+    }
+
+    fn check_after(&self, lock: absolute::LockTime) -> bool {
+        AbsLockTime::from_consensus(lock.to_consensus_u32())
+            .is_ok_and(|lock| self.allows(SemanticPolicy::After(lock)))
+    }
+}
@@ -1,10 +1,158 @@
 //! Debug information extraction for miniscript
 
-use miniscript::{Miniscript, MiniscriptKey, ScriptContext};
+use miniscript::{Miniscript, MiniscriptKey, ScriptContext, Terminal, Legacy, Segwitv0, Tap};
 use miniscript::descriptor::TapTree;
 use crate::types::{DebugInfo, TypeProperties, ExtendedProperties, LeafDebugInfo};
 use crate::console_log;
 
+/// Type-check a sub-expression on its own, e.g. `v:pk(A)`, without requiring a top-level B type
+///
+/// Keys may be placeholder names since only the type system is consulted.
+pub fn typecheck_fragment(fragment: &str, context: &str) -> Result<DebugInfo, String> {
+    let fragment = fragment.trim();
+    match context.to_lowercase().as_str() {
+        "legacy" => typecheck_fragment_in::<Legacy>(fragment),
+        "segwit" => typecheck_fragment_in::<Segwitv0>(fragment),
+        "taproot" => typecheck_fragment_in::<Tap>(fragment),
+        _ => Err(format!("Invalid context: {}. Use 'legacy', 'segwit', or 'taproot'", context)),
+    }
+}
+
+fn typecheck_fragment_in<Ctx: ScriptContext>(fragment: &str) -> Result<DebugInfo, String> {
+    use miniscript::expression::{FromTree, Tree};
+
+    let tree = Tree::from_str(fragment).map_err(|e| format!("Failed to parse fragment: {}", e))?;
+    let ms = <Miniscript<String, Ctx> as FromTree>::from_tree(&tree)
+        .map_err(|e| format!("Fragment does not type-check: {}", e))?;
+    extract_debug_info(&ms, true).ok_or_else(|| "Failed to extract type information".to_string())
+}
+
+/// Explain the first `and_b`/`or_b` whose arguments have the wrong types
+///
+/// rust-miniscript only reports "cannot accept children of types B and B"; this says
+/// which argument is wrong and which wrapper usually fixes it.
+pub fn boolean_combinator_hint(expression: &str, context: &str) -> Option<String> {
+    use miniscript::expression::Tree;
+
+    let tree = Tree::from_str(expression.trim()).ok()?;
+    match context.to_lowercase().as_str() {
+        "legacy" => boolean_combinator_misuse::<Legacy>(&tree),
+        "segwit" => boolean_combinator_misuse::<Segwitv0>(&tree),
+        "taproot" => boolean_combinator_misuse::<Tap>(&tree),
+        _ => None,
+    }
+}
+
+fn boolean_combinator_misuse<Ctx: ScriptContext>(tree: &miniscript::expression::Tree) -> Option<String> {
+    use miniscript::expression::FromTree;
+    use miniscript::miniscript::types::Base;
+
+    // Innermost misuse first, since an outer combinator fails whenever an inner one does
+    if let Some(hint) = tree.args.iter().find_map(boolean_combinator_misuse::<Ctx>) {
+        return Some(hint);
+    }
+    if !matches!(tree.name, "and_b" | "or_b") || tree.args.len() != 2 {
+        return None;
+    }
+
+    let first = <Miniscript<String, Ctx> as FromTree>::from_tree(&tree.args[0]).ok()?;
+    let second = <Miniscript<String, Ctx> as FromTree>::from_tree(&tree.args[1]).ok()?;
+    if first.ty.corr.base != Base::B {
+        return Some(format!(
+            "{} requires its first argument to be a B expression, but {} is {:?} (drop the v:/s:/a: wrapper)",
+            tree.name, first, first.ty.corr.base
+        ));
+    }
+    if second.ty.corr.base != Base::W {
+        return Some(format!(
+            "{} requires its second argument to be wrapped (e.g. s:pk or a:...), but {} is {:?}",
+            tree.name, second, second.ty.corr.base
+        ));
+    }
+    if tree.name == "or_b" && !(first.ty.corr.dissatisfiable && second.ty.corr.dissatisfiable) {
+        return Some("or_b requires both arguments to be dissatisfiable (d), e.g. pk or s:pk rather than v: or older".to_string());
+    }
+    None
+}
+
+/// Point to the fragments that make a miniscript malleable, with one explanation per source
+///
+/// A source is a malleable fragment whose children are all non-malleable, so the problem
+/// starts there rather than being inherited. Returns an empty list for non-malleable scripts.
+pub fn explain_malleability(expression: &str, context: &str) -> Result<Vec<String>, String> {
+    let expression = expression.trim();
+    match context.to_lowercase().as_str() {
+        "legacy" => malleability_sources::<Legacy>(expression),
+        "segwit" => malleability_sources::<Segwitv0>(expression),
+        "taproot" => malleability_sources::<Tap>(expression),
+        _ => Err(format!("Invalid context: {}. Use 'legacy', 'segwit', or 'taproot'", context)),
+    }
+}
+
+fn malleability_sources<Ctx: ScriptContext>(expression: &str) -> Result<Vec<String>, String> {
+    // The sanity check rejects malleable scripts, which are exactly the ones to explain
+    let ms = Miniscript::<String, Ctx>::from_str_insane(expression)
+        .map_err(|e| format!("Failed to parse miniscript: {}", e))?;
+    let mut sources = Vec::new();
+    collect_malleability_sources(&ms, &mut sources);
+    Ok(sources)
+}
+
+fn collect_malleability_sources<Ctx: ScriptContext>(ms: &Miniscript<String, Ctx>, sources: &mut Vec<String>) {
+    if ms.ty.mall.non_malleable {
+        return;
+    }
+    let children = ms.branches();
+    if children.iter().any(|child| !child.ty.mall.non_malleable) {
+        for child in children {
+            collect_malleability_sources(child, sources);
+        }
+        return;
+    }
+    sources.push(format!("{}: {}", ms, malleability_reason(ms)));
+}
+
+// Which non-malleability rule a fragment with non-malleable children breaks
+fn malleability_reason<Ctx: ScriptContext>(ms: &Miniscript<String, Ctx>) -> String {
+    use miniscript::miniscript::types::malleability::Dissat;
+
+    let unique_dissat = |child: &Miniscript<String, Ctx>| child.ty.mall.dissat == Dissat::Unique;
+    let no_signature = "neither branch requires a signature, so a third party can satisfy the branch that was not used";
+    match &ms.node {
+        Terminal::OrB(left, right) => {
+            if !unique_dissat(left) || !unique_dissat(right) {
+                let culprit = if unique_dissat(left) { right } else { left };
+                format!("or_b needs a unique dissatisfaction for both branches, but {} can be dissatisfied in more than one way", culprit)
+            } else {
+                no_signature.to_string()
+            }
+        }
+        Terminal::OrD(left, _) | Terminal::OrC(left, _) if !unique_dissat(left) => format!(
+            "the first branch {} can be dissatisfied in more than one way, so a third party can swap in another dissatisfaction",
+            left
+        ),
+        Terminal::AndOr(condition, _, _) if !unique_dissat(condition) => format!(
+            "the condition {} can be dissatisfied in more than one way, so a third party can switch to the else branch",
+            condition
+        ),
+        Terminal::OrD(..) | Terminal::OrC(..) | Terminal::OrI(..) => no_signature.to_string(),
+        Terminal::AndOr(..) => "no branch requires a signature, so a third party can choose which one is satisfied".to_string(),
+        Terminal::Thresh(thresh) => {
+            match thresh.iter().find(|sub| !unique_dissat(sub)) {
+                Some(sub) => format!(
+                    "thresh needs a unique dissatisfaction for every argument, but {} can be dissatisfied in more than one way",
+                    sub
+                ),
+                None => format!(
+                    "at least {} of the {} arguments must require a signature, or a third party can swap which ones are satisfied",
+                    thresh.n() - thresh.k(), thresh.n()
+                ),
+            }
+        }
+        _ => "a third party can change its satisfaction without a signature".to_string(),
+    }
+}
+
 /// Extract debug information from a miniscript
 pub fn extract_debug_info<Pk: MiniscriptKey, Ctx: ScriptContext>(
     ms: &Miniscript<Pk, Ctx>,
@@ -21,8 +169,8 @@ pub fn extract_debug_info<Pk: MiniscriptKey, Ctx: ScriptContext>(
     console_log!("=== VERBOSE MINISCRIPT DEBUG INFO ===");
     console_log!("{}", raw_output);
 
-    // Extract annotated expression from debug output
-    let annotated_expression = extract_annotated_expression(&raw_output);
+    // Walk the AST and annotate every node, including sugared wrappers
+    let annotated_expression = annotate_miniscript(ms);
 
     // Extract type properties
     let type_properties = extract_type_properties(ms);
@@ -42,15 +190,106 @@ pub fn extract_debug_info<Pk: MiniscriptKey, Ctx: ScriptContext>(
     })
 }
 
-/// Extract the annotated expression from debug output
-fn extract_annotated_expression(debug_output: &str) -> String {
-    // For now, return the miniscript string representation
-    // In a full implementation, we'd parse the debug output to extract
-    // the expression with type annotations like [B/onduesm]
-    debug_output.lines()
-        .take(20)  // Take first 20 lines as summary
-        .collect::<Vec<_>>()
-        .join("\n")
+/// Annotate a miniscript as an indented tree, one node per line with its type
+/// Wrappers are labeled with the type modification they apply
+fn annotate_miniscript<Pk: MiniscriptKey, Ctx: ScriptContext>(ms: &Miniscript<Pk, Ctx>) -> String {
+    let mut lines = Vec::new();
+    annotate_node(ms, 0, &mut lines);
+    lines.join("\n")
+}
+
+/// Append the annotation lines for a node and its children
+fn annotate_node<Pk: MiniscriptKey, Ctx: ScriptContext>(
+    ms: &Miniscript<Pk, Ctx>,
+    depth: usize,
+    lines: &mut Vec<String>,
+) {
+    let indent = "  ".repeat(depth);
+    let ty = type_code(&ms.ty);
+
+    // Wrappers: (label, type modification, wrapped child)
+    let wrapper: Option<(&str, &str, &Miniscript<Pk, Ctx>)> = match &ms.node {
+        Terminal::Alt(sub) => Some(("a:", "B→W, runs on the altstack (TOALTSTACK ... FROMALTSTACK)", sub)),
+        Terminal::Swap(sub) => Some(("s:", "Bo→W, swaps the top two stack items (SWAP ...)", sub)),
+        Terminal::Check(sub) => Some(("c:", "K→B, checks the signature (... CHECKSIG)", sub)),
+        Terminal::DupIf(sub) => Some(("d:", "Vz→Bonud, skipped when the input is zero (DUP IF ... ENDIF)", sub)),
+        Terminal::Verify(sub) => Some(("v:", "B→V, fails unless the result is true (... VERIFY)", sub)),
+        Terminal::NonZero(sub) => Some(("j:", "Bn→Bnd, skipped when the input is zero (SIZE 0NOTEQUAL IF ... ENDIF)", sub)),
+        Terminal::ZeroNotEqual(sub) => Some(("n:", "B→Bu, normalizes the result to 0/1 (... 0NOTEQUAL)", sub)),
+        Terminal::OrI(left, right) if matches!(left.node, Terminal::False) => {
+            Some(("l:", "B→Bd, dissatisfied by the left branch (IF 0 ELSE ... ENDIF)", right))
+        }
+        Terminal::OrI(left, right) if matches!(right.node, Terminal::False) => {
+            Some(("u:", "B→Bd, dissatisfied by the right branch (IF ... ELSE 0 ENDIF)", left))
+        }
+        Terminal::AndV(left, right) if matches!(right.node, Terminal::True) => {
+            Some(("t:", "V→Bu, pushes 1 after the verify (... 1)", left))
+        }
+        _ => None,
+    };
+
+    if let Some((label, note, sub)) = wrapper {
+        lines.push(format!("{}{} [{}] {}", indent, label, ty, note));
+        annotate_node(sub, depth + 1, lines);
+        return;
+    }
+
+    let (name, children): (String, Vec<&Miniscript<Pk, Ctx>>) = match &ms.node {
+        Terminal::AndV(l, r) => ("and_v".to_string(), vec![l, r]),
+        Terminal::AndB(l, r) => ("and_b".to_string(), vec![l, r]),
+        Terminal::AndOr(a, b, c) => ("andor".to_string(), vec![a, b, c]),
+        Terminal::OrB(l, r) => ("or_b".to_string(), vec![l, r]),
+        Terminal::OrD(l, r) => ("or_d".to_string(), vec![l, r]),
+        Terminal::OrC(l, r) => ("or_c".to_string(), vec![l, r]),
+        Terminal::OrI(l, r) => ("or_i".to_string(), vec![l, r]),
+        Terminal::Thresh(thresh) => (
+            format!("thresh({})", thresh.k()),
+            thresh.iter().map(|sub| sub.as_ref()).collect(),
+        ),
+        leaf => (leaf.to_string(), Vec::new()),
+    };
+
+    lines.push(format!("{}{} [{}]", indent, name, ty));
+    for child in children {
+        annotate_node(child, depth + 1, lines);
+    }
+}
+
+/// Format a type in the same [B/onduesm] notation rust-miniscript uses for Debug
+fn type_code(ty: &miniscript::miniscript::types::Type) -> String {
+    use miniscript::miniscript::types::{Base, Dissat, Input};
+
+    let mut code = String::from(match ty.corr.base {
+        Base::B => "B/",
+        Base::K => "K/",
+        Base::V => "V/",
+        Base::W => "W/",
+    });
+    code.push_str(match ty.corr.input {
+        Input::Zero => "z",
+        Input::One => "o",
+        Input::OneNonZero => "on",
+        Input::Any => "",
+        Input::AnyNonZero => "n",
+    });
+    if ty.corr.dissatisfiable {
+        code.push('d');
+    }
+    if ty.corr.unit {
+        code.push('u');
+    }
+    code.push_str(match ty.mall.dissat {
+        Dissat::None => "f",
+        Dissat::Unique => "e",
+        Dissat::Unknown => "",
+    });
+    if ty.mall.safe {
+        code.push('s');
+    }
+    if ty.mall.non_malleable {
+        code.push('m');
+    }
+    code
 }
 
 /// Extract type properties from miniscript
@@ -7,30 +7,486 @@ use crate::compile::options::{CompileOptions, InputType, CompileContext};
 use crate::types::CompilationResult;
 use crate::console_log;
 use bitcoin::Network;
-use crate::parse::helpers::{detect_network, needs_descriptor_processing, is_descriptor_wrapper};
+use miniscript::{Descriptor, DescriptorPublicKey};
+use crate::parse::helpers::{canonical_expression, detect_network, needs_descriptor_processing, is_descriptor_wrapper, strip_descriptor_checksum, strip_invisible, strip_whitespace, with_fresh_checksum};
 use crate::descriptors::parser::parse_descriptors;
-use crate::validation;
+use crate::validation::{self, COMPRESSED_KEY, XONLY_KEY};
+use std::collections::HashMap;
 
 // Unified compilation entry point
 pub fn compile_unified(expression: &str, options: CompileOptions) -> Result<CompilationResult, String> {
     console_log!("=== UNIFIED COMPILE ===");
+    let mut timer = StageTimer::new(options.timing);
+    validation::check_expression_length(
+        expression,
+        options.max_expression_length.unwrap_or(validation::MAX_EXPRESSION_LENGTH),
+    )?;
+    validation::check_non_ascii(expression)?;
+    let original_expression = strip_invisible(expression).trim().to_string();
+    let mut expression = strip_whitespace(expression)?;
+    validation::validate_arguments(&expression)?;
+    // A pasted descriptor may carry its checksum; verify and drop it so it never reaches the parsers
+    let (body, had_checksum) = strip_descriptor_checksum(&expression)?;
+    expression = body.to_string();
+    if crate::address::is_combo_descriptor(&expression) {
+        return Err(crate::address::COMBO_SINGLE_OUTPUT_ERROR.to_string());
+    }
+    if expression.starts_with("rawtr(") {
+        return Err("rawtr() takes a final taproot output key and has no script tree; use tr() to compile a script".to_string());
+    }
+    let mut key_warnings = Vec::new();
+    if options.auto_convert_keys {
+        let (converted, warning) = auto_convert_keys(&expression, options.context)?;
+        expression = converted;
+        key_warnings.extend(warning);
+    }
+    validation::check_mixed_key_types(&expression)?;
+    validation::check_multisig_keys(&expression)?;
+    let expression = expression.as_str();
     console_log!("Expression: {}", expression);
     console_log!("Options: input_type={:?}, context={}, mode={}, network={:?}",
         options.input_type, options.context.as_str(), options.mode.as_str(), options.network());
 
-    match options.input_type {
-        InputType::Policy => compile_policy_unified(expression, options),
-        InputType::Miniscript => compile_miniscript_unified(expression, options),
+    let context = options.context;
+    let network = options.network();
+    let user_internal_key = options.nums_key.clone().or_else(|| match options.input_type {
+        InputType::Policy => crate::compile::policy::split_tr_policy(expression).map(|(key, _)| key.to_string()),
+        InputType::Miniscript => None,
+    });
+    let guard_context = match options.input_type {
+        InputType::Policy => "policy",
+        InputType::Miniscript => context.as_str(),
+    };
+    let degenerate_warning = crate::analyze::degenerate_spend_warning(expression, guard_context);
+    timer.stage("preprocess");
+
+    let declared_type = match options.input_type {
+        InputType::Policy => "policy",
+        InputType::Miniscript => "miniscript",
+    };
+    let compiled = match options.input_type {
+        InputType::Policy => compile_policy_unified(expression, options, &mut timer),
+        InputType::Miniscript => compile_miniscript_unified(expression, options, &mut timer),
+    };
+
+    // A policy in the miniscript field (or the reverse) gets a pointer instead of a parser error
+    let failed = compiled.as_ref().map_or(true, |result| !result.success);
+    let mismatch = if failed {
+        validation::input_type_mismatch_hint(expression, declared_type, context.as_str())
+    } else {
+        None
+    };
+    let mut result = match (compiled, mismatch) {
+        (Ok(mut result), Some(hint)) => {
+            result.error = Some(hint);
+            result
+        }
+        (Err(_), Some(hint)) => return Err(hint),
+        (compiled, None) => compiled?,
+    };
+
+    // Typecheck failures inside and_b/or_b get a note on which argument needs which wrapper
+    if failed && declared_type == "miniscript" {
+        if let Some(hint) = crate::compile::debug::boolean_combinator_hint(expression, context.as_str()) {
+            let error = result.error.take().unwrap_or_default();
+            result.error = Some(format!("{}\nHint: {}", error, hint));
+        }
+    }
+
+    if result.success {
+        if had_checksum {
+            result.compiled_miniscript = result.compiled_miniscript.map(|compiled| match compiled.strip_prefix("Valid descriptor: ") {
+                Some(descriptor) => format!("Valid descriptor: {}", with_fresh_checksum(descriptor)),
+                None => compiled,
+            });
+        }
+        result.output_descriptor_type = Some(output_descriptor_type(expression, context, result.compiled_miniscript.as_deref()));
+        result.output_summary = crate::analyze::shape_summary(expression, guard_context);
+        if context == CompileContext::Taproot {
+            result.internal_key_source = taproot_internal_key_source(result.compiled_miniscript.as_deref(), user_internal_key.as_deref());
+        }
+        result.script_pubkey_hex = result.script.as_deref().and_then(|script| wrapped_script_pubkey(script, context));
+    }
+
+    // Every descriptor-derived field below comes from this one parse of the compiled output
+    let descriptor = if result.success {
+        result.compiled_miniscript.as_deref().and_then(|compiled| parse_compiled_descriptor(compiled, context))
+    } else {
+        None
+    };
+    if let Some(descriptor) = &descriptor {
+        result.descriptor_info = Some(compiled_descriptor_info(descriptor));
+        result.tree_braces = compiled_tree_braces(descriptor);
+        if declared_type == "policy" && context == CompileContext::Taproot {
+            result.tap_leaf_miniscripts = compiled_tap_leaf_miniscripts(descriptor);
+        }
+        result.receive_change_addresses = receive_change_addresses(descriptor, network);
+        if let Some((script_pubkey, witness_script, vbytes)) = spend_size_breakdown(descriptor) {
+            result.script_pubkey_bytes = Some(script_pubkey);
+            result.witness_script_bytes = witness_script;
+            result.worst_case_spend_vbytes = vbytes;
+        }
+        if context == CompileContext::Segwit {
+            if let Some((witness_script_hash, redeem_script_hash)) = segwit_script_hashes(descriptor) {
+                result.witness_script_hash = Some(witness_script_hash);
+                result.redeem_script_hash = redeem_script_hash;
+            }
+        }
+    }
+
+    if !key_warnings.is_empty() {
+        result.warnings.get_or_insert_with(Vec::new).extend(key_warnings);
+    }
+
+    if result.success {
+        let mut advisories = validation::timelock_advisories(expression);
+        if context == CompileContext::Segwit {
+            if let Some(compiled) = result.compiled_miniscript.as_deref() {
+                advisories.extend(validation::segwit_standardness_warnings(compiled));
+            }
+        }
+        if let Some(Descriptor::Tr(tr)) = &descriptor {
+            advisories.extend(validation::tr_standardness_warnings(tr));
+        }
+        if !advisories.is_empty() {
+            result.warnings.get_or_insert_with(Vec::new).extend(advisories);
+        }
+    }
+
+    // Never hand out a script anyone can spend or nobody ever can
+    if let Some(warning) = degenerate_warning.filter(|_| result.success) {
+        result.success = false;
+        result.address = None;
+        result.error = Some(warning);
+    }
+
+    if let Some(wildcards) = count_descriptor_wildcards(expression) {
+        result.is_ranged = Some(wildcards > 0);
+        result.derivation_wildcards = Some(wildcards);
+    }
+
+    result.original_expression = Some(original_expression);
+    result.normalized_expression = canonical_expression(expression, declared_type == "policy", context.as_str());
+
+    timer.stage("post_process");
+    result.timings = timer.finish();
+    Ok(result)
+}
+
+// Elapsed milliseconds per compile stage, only measured when timing is requested
+pub(crate) struct StageTimer {
+    enabled: bool,
+    started: f64,
+    last: f64,
+    stages: HashMap<String, f64>,
+}
+
+impl StageTimer {
+    pub(crate) fn new(enabled: bool) -> Self {
+        let now = if enabled { crate::utils::now_ms() } else { 0.0 };
+        StageTimer { enabled, started: now, last: now, stages: HashMap::new() }
+    }
+
+    // Record the time since the previous stage under `name`
+    // Stages: preprocess, expand_descriptors, compile, address, post_process
+    pub(crate) fn stage(&mut self, name: &str) {
+        if self.enabled {
+            let now = crate::utils::now_ms();
+            self.stages.insert(name.to_string(), now - self.last);
+            self.last = now;
+        }
+    }
+
+    fn finish(mut self) -> Option<HashMap<String, f64>> {
+        if !self.enabled {
+            return None;
+        }
+        self.stages.insert("total".to_string(), self.last - self.started);
+        Some(self.stages)
+    }
+}
+
+// Output script bytes, witness script bytes (P2WSH and nested P2WSH) and the input's worst-case vbytes
+// The input size is the unsatisfied TxIn plus max_weight_to_satisfy, as rust-miniscript defines it
+fn spend_size_breakdown(descriptor: &Descriptor<DescriptorPublicKey>) -> Option<(usize, Option<usize>, Option<u64>)> {
+    use miniscript::descriptor::ShInner;
+
+    let descriptor = descriptor.at_derivation_index(0).ok()?;
+    let witness_script = match &descriptor {
+        Descriptor::Wsh(_) => descriptor.explicit_script().ok(),
+        Descriptor::Sh(sh) if matches!(sh.as_inner(), ShInner::Wsh(_)) => descriptor.explicit_script().ok(),
+        _ => None,
+    };
+    let unsatisfied = bitcoin::TxIn::default();
+    let base_weight = match descriptor {
+        Descriptor::Sh(ref sh) if matches!(sh.as_inner(), ShInner::Ms(_)) => unsatisfied.legacy_weight(),
+        Descriptor::Bare(_) | Descriptor::Pkh(_) => unsatisfied.legacy_weight(),
+        _ => unsatisfied.segwit_weight(),
+    };
+    let vbytes = descriptor.max_weight_to_satisfy().ok()
+        .map(|weight| (base_weight + weight).to_vbytes_ceil());
+
+    Some((descriptor.script_pubkey().len(), witness_script.map(|script| script.len()), vbytes))
+}
+
+// sha256 of the witness script (P2WSH and nested P2WSH), plus hash160 of the redeem script when nested
+// These are the hashes embedded in the output, so they can be checked against an explorer
+fn segwit_script_hashes(descriptor: &Descriptor<DescriptorPublicKey>) -> Option<(String, Option<String>)> {
+    use bitcoin::hashes::Hash;
+    use miniscript::descriptor::ShInner;
+
+    let descriptor = descriptor.at_derivation_index(0).ok()?;
+    let nested = match &descriptor {
+        Descriptor::Wsh(_) => false,
+        Descriptor::Sh(sh) if matches!(sh.as_inner(), ShInner::Wsh(_)) => true,
+        _ => return None,
+    };
+    let witness_script = descriptor.explicit_script().ok()?;
+    let witness_script_hash = witness_script.wscript_hash();
+    let redeem_script_hash = nested.then(|| {
+        let redeem_script = bitcoin::ScriptBuf::new_p2wsh(&witness_script_hash);
+        hex::encode(redeem_script.script_hash().to_byte_array())
+    });
+    Some((hex::encode(witness_script_hash.to_byte_array()), redeem_script_hash))
+}
+
+// Friendly output type name from an explicit descriptor wrapper, or the context otherwise
+// For taproot the internal key of the compiled tr() decides between key-only, script-only and key+script
+fn output_descriptor_type(expression: &str, context: CompileContext, compiled: Option<&str>) -> String {
+    let trimmed = expression.trim();
+    let wrapped = [
+        ("sh(wsh(", "P2SH-P2WSH"),
+        ("sh(wpkh(", "P2SH-P2WPKH"),
+        ("wsh(", "P2WSH"),
+        ("wpkh(", "P2WPKH"),
+        ("pkh(", "P2PKH"),
+        ("sh(", "P2SH"),
+    ];
+    if let Some((_, name)) = wrapped.iter().find(|(prefix, _)| trimmed.starts_with(prefix)) {
+        return name.to_string();
+    }
+
+    match context {
+        CompileContext::Legacy => "P2SH".to_string(),
+        CompileContext::Segwit => "P2WSH".to_string(),
+        CompileContext::Taproot => {
+            let tr = compiled.filter(|c| c.starts_with("tr(")).unwrap_or(trimmed);
+            let inner = tr.strip_prefix("tr(").unwrap_or(tr);
+            let inner = inner.split('#').next().unwrap_or(inner);
+            match inner.split_once(',') {
+                None => "P2TR (key-only)".to_string(),
+                Some((internal_key, _)) if internal_key == crate::taproot::utils::NUMS_POINT => "P2TR (script-only)".to_string(),
+                Some(_) if tree_derived_internal_key(tr) => "P2TR (script-only)".to_string(),
+                Some(_) => "P2TR (key+script)".to_string(),
+            }
+        }
+    }
+}
+
+// Summarize the structure of the parsed compiled output
+fn compiled_descriptor_info(descriptor: &Descriptor<DescriptorPublicKey>) -> crate::types::CompiledDescriptorInfo {
+    use miniscript::descriptor::ShInner;
+
+    let (kind, internal_key, leaf_count) = match descriptor {
+        Descriptor::Bare(_) => ("bare", None, 0),
+        Descriptor::Pkh(_) => ("pkh", None, 0),
+        Descriptor::Wpkh(_) => ("wpkh", None, 0),
+        Descriptor::Sh(sh) => match sh.as_inner() {
+            ShInner::Wsh(_) => ("sh-wsh", None, 0),
+            ShInner::Wpkh(_) => ("sh-wpkh", None, 0),
+            _ => ("sh", None, 0),
+        },
+        Descriptor::Wsh(_) => ("wsh", None, 0),
+        Descriptor::Tr(tr) => ("tr", Some(tr.internal_key().to_string()), tr.iter_scripts().count()),
+    };
+
+    crate::types::CompiledDescriptorInfo {
+        kind: kind.to_string(),
+        internal_key,
+        has_tree: leaf_count > 0,
+        leaf_count,
+        is_ranged: descriptor.has_wildcard(),
     }
 }
 
+// Parse the compiled output as a descriptor, wrapping a bare miniscript for the context
+// Bare miniscripts are placed in the context's default wrapper; single-leaf taproot sits under NUMS
+fn parse_compiled_descriptor(compiled: &str, context: CompileContext) -> Option<Descriptor<DescriptorPublicKey>> {
+    let compiled = compiled.strip_prefix("Valid descriptor: ").unwrap_or(compiled);
+    let compiled = compiled.split("|LEAF_ASM:").next().unwrap_or(compiled).trim();
+    let is_descriptor = ["tr(", "wsh(", "sh(", "wpkh(", "pkh("].iter().any(|prefix| compiled.starts_with(prefix));
+    let descriptor = if is_descriptor {
+        compiled.to_string()
+    } else {
+        match context {
+            CompileContext::Legacy => format!("sh({})", compiled),
+            CompileContext::Segwit => format!("wsh({})", compiled),
+            CompileContext::Taproot => format!("tr({},{})", crate::taproot::utils::NUMS_POINT, compiled),
+        }
+    };
+    descriptor.parse().ok()
+}
+
+// The brace-grouped script tree of a compiled tr() descriptor with more than one leaf
+// TapTree's Display walks the tree itself, so the grouping is exactly the tree that was built
+fn compiled_tree_braces(descriptor: &Descriptor<DescriptorPublicKey>) -> Option<String> {
+    use miniscript::descriptor::TapTree;
+
+    let Descriptor::Tr(tr) = descriptor else {
+        return None;
+    };
+    match tr.tap_tree() {
+        Some(tree @ TapTree::Tree { .. }) => Some(tree.to_string()),
+        _ => None,
+    }
+}
+
+// The miniscript of each leaf of a compiled tr() descriptor, in tree order (None without a script tree)
+fn compiled_tap_leaf_miniscripts(descriptor: &Descriptor<DescriptorPublicKey>) -> Option<Vec<String>> {
+    let Descriptor::Tr(tr) = descriptor else {
+        return None;
+    };
+    let leaves: Vec<String> = tr.iter_scripts().map(|(_depth, ms)| ms.to_string()).collect();
+    (!leaves.is_empty()).then_some(leaves)
+}
+
+// First receive (branch 0) and change (branch 1) addresses of a compiled `<0;1>` multipath descriptor
+fn receive_change_addresses(descriptor: &Descriptor<DescriptorPublicKey>, network: Network) -> Option<crate::types::ReceiveChangeAddresses> {
+    if !descriptor.is_multipath() {
+        return None;
+    }
+
+    let branches = descriptor.clone().into_single_descriptors().ok()?;
+    let first_address = |branch: usize| -> Option<String> {
+        let derived = branches.get(branch)?.at_derivation_index(0).ok()?;
+        derived.address(network).ok().map(|address| address.to_string())
+    };
+    Some(crate::types::ReceiveChangeAddresses {
+        receive: first_address(0)?,
+        change: first_address(1)?,
+    })
+}
+
+// Where the internal key of a compiled tr() descriptor came from: the NUMS point,
+// a NUMS key derived from the tree, the key supplied in the options, or a key
+// extracted from the expression
+fn taproot_internal_key_source(compiled: Option<&str>, user_key: Option<&str>) -> Option<String> {
+    let inner = compiled?.strip_prefix("tr(")?;
+    let internal_key = inner.split([',', ')']).next()?;
+    let source = if internal_key == crate::taproot::utils::NUMS_POINT {
+        "NUMS"
+    } else if tree_derived_internal_key(compiled?) {
+        "NUMS-derived"
+    } else if user_key == Some(internal_key) {
+        "user-provided"
+    } else {
+        "extracted"
+    };
+    Some(source.to_string())
+}
+
+// Whether a tr() descriptor uses the unspendable internal key derived from its own merkle root
+fn tree_derived_internal_key(descriptor: &str) -> bool {
+    match descriptor.parse::<Descriptor<bitcoin::XOnlyPublicKey>>() {
+        Ok(Descriptor::Tr(tr)) => {
+            *tr.internal_key() != crate::taproot::utils::get_nums_point()
+                && crate::taproot::utils::is_unspendable_internal_key(&tr)
+        }
+        _ => false,
+    }
+}
+
+// Opt-in key type conversion: compressed keys lose their 02/03 prefix for Taproot
+// The reverse is refused since an x-only key does not say which parity prefix it had
+fn auto_convert_keys(expression: &str, context: CompileContext) -> Result<(String, Option<String>), String> {
+    if context == CompileContext::Taproot {
+        let count = COMPRESSED_KEY.find_iter(expression).count();
+        if count == 0 {
+            return Ok((expression.to_string(), None));
+        }
+        let converted = COMPRESSED_KEY.replace_all(expression, "$1").into_owned();
+        let warning = format!("⚠️ Converted {} compressed key(s) to x-only form for Taproot by dropping the 02/03 prefix", count);
+        return Ok((converted, Some(warning)));
+    }
+
+    let xonly = XONLY_KEY.captures_iter(expression)
+        .find(|caps| caps.get(1).is_none())
+        .and_then(|caps| caps.get(2));
+    if let Some(key) = xonly {
+        return Err(format!(
+            "Cannot convert x-only key {} to a compressed key for {} context: the 02/03 parity prefix is ambiguous. Please provide the compressed key.",
+            key.as_str(), context.as_str()
+        ));
+    }
+    Ok((expression.to_string(), None))
+}
+
+// Output script committing to a compiled redeem (P2SH) or witness (P2WSH) script
+// None for Taproot, where `script` already is the output script, and for non-hex placeholders
+fn wrapped_script_pubkey(script_hex: &str, context: CompileContext) -> Option<String> {
+    let script = bitcoin::ScriptBuf::from_bytes(hex::decode(script_hex).ok()?);
+    let script_pubkey = match context {
+        CompileContext::Legacy => bitcoin::ScriptBuf::new_p2sh(&script.script_hash()),
+        CompileContext::Segwit => bitcoin::ScriptBuf::new_p2wsh(&script.wscript_hash()),
+        CompileContext::Taproot => return None,
+    };
+    Some(script_pubkey.to_hex_string())
+}
+
+// Count `*` wildcards when the expression uses descriptor keys (None for plain keys)
+fn count_descriptor_wildcards(expression: &str) -> Option<usize> {
+    let descriptors = parse_descriptors(expression).ok()?;
+    if descriptors.is_empty() {
+        return None;
+    }
+    Some(expression.matches('*').count())
+}
+
+// Compile several expressions with the same options, preserving order
+// A failure in one expression is reported in its own result and does not affect the others
+pub fn compile_batch(expressions: &[String], options: &CompileOptions) -> Vec<CompilationResult> {
+    console_log!("=== BATCH COMPILE ({} expressions) ===", expressions.len());
+
+    expressions.iter()
+        .map(|expression| {
+            compile_unified(expression, options.clone()).unwrap_or_else(CompilationResult::failure)
+        })
+        .collect()
+}
+
+// Compile an expression and compare its address with an expected one, returning (matches, actual address)
+// Full descriptors the compiler does not accept, such as a key-only tr(KEY), are derived directly
+pub fn compile_and_assert(expression: &str, options: CompileOptions, expected_address: &str) -> Result<(bool, String), String> {
+    let network = options.network_str.clone();
+    let result = compile_unified(expression, options)?;
+
+    let actual = match result.address {
+        Some(address) if result.success => address,
+        _ => match crate::address::script_pubkey_at_index(expression, 0, &network) {
+            Ok(derived) => derived.address
+                .ok_or_else(|| "Descriptor has no address form".to_string())?,
+            Err(_) => return Err(result.error.unwrap_or_else(|| "No address generated".to_string())),
+        },
+    };
+
+    console_log!("Address assertion: expected={} actual={}", expected_address.trim(), actual);
+    Ok((actual == expected_address.trim(), actual))
+}
+
 // Compile policy with unified options
-fn compile_policy_unified(policy: &str, options: CompileOptions) -> Result<CompilationResult, String> {
+fn compile_policy_unified(policy: &str, options: CompileOptions, timer: &mut StageTimer) -> Result<CompilationResult, String> {
     let context_str = options.context.as_str();
     let mode_str = options.mode.as_str();
-    match crate::compile::policy::compile_policy_to_miniscript_with_mode(policy, context_str, mode_str) {
+    let compiled = crate::compile::policy::compile_policy_to_miniscript_with_mode(policy, context_str, mode_str);
+    timer.stage("compile");
+    match compiled {
         Ok((script, script_asm, address, script_size, ms_type, compiled_miniscript,
             max_satisfaction_size, max_weight_to_satisfy, sanity_check, is_non_malleable)) => {
+            let warnings = if options.context == CompileContext::Taproot {
+                taproot_descriptor_warnings(Some(&compiled_miniscript))
+            } else {
+                None
+            };
             Ok(CompilationResult {
                 success: true,
                 error: None,
@@ -46,29 +502,33 @@ fn compile_policy_unified(policy: &str, options: CompileOptions) -> Result<Compi
                 is_non_malleable,
                 debug_info: None,
                 debug_info_leaves: None,
+                warnings,
+                is_ranged: None,
+                derivation_wildcards: None,
+                output_descriptor_type: None,
+                output_summary: None,
+                internal_key_source: None,
+                script_pubkey_hex: None,
+                timings: None,
+                descriptor_info: None,
+                tree_braces: None,
+                receive_change_addresses: None,
+                script_pubkey_bytes: None,
+                witness_script_bytes: None,
+                worst_case_spend_vbytes: None,
+                original_expression: None,
+                normalized_expression: None,
+                tap_leaf_miniscripts: None,
+                witness_script_hash: None,
+                redeem_script_hash: None,
             })
         },
-        Err(e) => Ok(CompilationResult {
-            success: false,
-            error: Some(e),
-            script: None,
-            script_asm: None,
-            address: None,
-            script_size: None,
-            miniscript_type: None,
-            compiled_miniscript: None,
-            max_satisfaction_size: None,
-            max_weight_to_satisfy: None,
-            sanity_check: None,
-            is_non_malleable: None,
-            debug_info: None,
-            debug_info_leaves: None,
-        })
+        Err(e) => Ok(CompilationResult::failure(e))
     }
 }
 
 // Compile miniscript with unified options
-fn compile_miniscript_unified(expression: &str, options: CompileOptions) -> Result<CompilationResult, String> {
+fn compile_miniscript_unified(expression: &str, options: CompileOptions, timer: &mut StageTimer) -> Result<CompilationResult, String> {
     let context_str = options.context.as_str();
 
     if options.context == CompileContext::Taproot {
@@ -76,9 +536,15 @@ fn compile_miniscript_unified(expression: &str, options: CompileOptions) -> Resu
         let nums_key = options.nums_key.clone().unwrap_or_else(|| crate::taproot::utils::NUMS_POINT.to_string());
         let network = options.network();
 
-        match compile_taproot_with_mode_network_debug(expression, mode_str, &nums_key, network, options.verbose_debug) {
+        match compile_taproot_with_mode_network_debug(expression, mode_str, &nums_key, network, options.verbose_debug, timer) {
             Ok((script, script_asm, address, script_size, ms_type,
                 max_satisfaction_size, max_weight_to_satisfy, sanity_check, is_non_malleable, normalized_miniscript, debug_info, debug_info_leaves)) => {
+                let mut warnings = taproot_descriptor_warnings(normalized_miniscript.as_deref());
+                if options.compare_taproot_modes {
+                    if let Some(note) = taproot_mode_difference_note(expression, mode_str, &nums_key, network, address.as_deref()) {
+                        warnings.get_or_insert_with(Vec::new).push(note);
+                    }
+                }
                 Ok(CompilationResult {
                     success: true,
                     error: None,
@@ -94,28 +560,32 @@ fn compile_miniscript_unified(expression: &str, options: CompileOptions) -> Resu
                     is_non_malleable,
                     debug_info,
                     debug_info_leaves,
+                    warnings,
+                    is_ranged: None,
+                    derivation_wildcards: None,
+                    output_descriptor_type: None,
+                    output_summary: None,
+                    internal_key_source: None,
+                    script_pubkey_hex: None,
+                    timings: None,
+                    descriptor_info: None,
+                    tree_braces: None,
+                    receive_change_addresses: None,
+                    script_pubkey_bytes: None,
+                    witness_script_bytes: None,
+                    worst_case_spend_vbytes: None,
+                    original_expression: None,
+                    normalized_expression: None,
+                    tap_leaf_miniscripts: None,
+                    witness_script_hash: None,
+                    redeem_script_hash: None,
                 })
             },
-            Err(e) => Ok(CompilationResult {
-                success: false,
-                error: Some(e),
-                script: None,
-                script_asm: None,
-                address: None,
-                script_size: None,
-                miniscript_type: None,
-                compiled_miniscript: None,
-                max_satisfaction_size: None,
-                max_weight_to_satisfy: None,
-                sanity_check: None,
-                is_non_malleable: None,
-                debug_info: None,
-                debug_info_leaves: None,
-            })
+            Err(e) => Ok(CompilationResult::failure(e))
         }
     } else {
         // For non-taproot contexts, use direct compilation
-        match compile_non_taproot_context_debug(expression, context_str, options.verbose_debug) {
+        match compile_non_taproot_context_debug(expression, context_str, options.verbose_debug, timer) {
             Ok((script, script_asm, address, script_size, ms_type,
                 max_satisfaction_size, max_weight_to_satisfy, sanity_check, is_non_malleable, normalized_miniscript, debug_info)) => {
                 Ok(CompilationResult {
@@ -133,28 +603,62 @@ fn compile_miniscript_unified(expression: &str, options: CompileOptions) -> Resu
                     is_non_malleable,
                     debug_info,
                     debug_info_leaves: None,
+                    warnings: None,
+                    is_ranged: None,
+                    derivation_wildcards: None,
+                    output_descriptor_type: None,
+                    output_summary: None,
+                    internal_key_source: None,
+                    script_pubkey_hex: None,
+                    timings: None,
+                    descriptor_info: None,
+                    tree_braces: None,
+                    receive_change_addresses: None,
+                    script_pubkey_bytes: None,
+                    witness_script_bytes: None,
+                    worst_case_spend_vbytes: None,
+                    original_expression: None,
+                    normalized_expression: None,
+                    tap_leaf_miniscripts: None,
+                    witness_script_hash: None,
+                    redeem_script_hash: None,
                 })
             },
-            Err(e) => Ok(CompilationResult {
-                success: false,
-                error: Some(e),
-                script: None,
-                script_asm: None,
-                address: None,
-                script_size: None,
-                miniscript_type: None,
-                compiled_miniscript: None,
-                max_satisfaction_size: None,
-                max_weight_to_satisfy: None,
-                sanity_check: None,
-                is_non_malleable: None,
-                debug_info: None,
-                debug_info_leaves: None,
-            })
+            Err(e) => Ok(CompilationResult::failure(e))
         }
     }
 }
 
+// Collect the internal-key reuse, tree size and leaf limit warnings for a compiled tr() descriptor
+fn taproot_descriptor_warnings(descriptor: Option<&str>) -> Option<Vec<String>> {
+    let descriptor = descriptor?;
+    let descriptor = descriptor.split("|LEAF_ASM:").next().unwrap_or(descriptor);
+    let mut warnings: Vec<String> = crate::taproot::utils::internal_key_reuse_warning(descriptor).into_iter().collect();
+    warnings.extend(crate::taproot::utils::tap_tree_warnings(descriptor));
+    (!warnings.is_empty()).then_some(warnings)
+}
+
+// Compile the expression under every other taproot mode and note when the address differs
+fn taproot_mode_difference_note(
+    expression: &str,
+    selected_mode: &str,
+    nums_key: &str,
+    network: Network,
+    selected_address: Option<&str>,
+) -> Option<String> {
+    let differs = ["multi-leaf", "single-leaf", "script-path", "script-path-unique"].iter()
+        .filter(|mode| **mode != selected_mode)
+        .filter_map(|mode| compile_taproot_with_mode(expression, mode, nums_key, network).ok())
+        .filter(|response| response.success)
+        .any(|response| response.address.as_deref() != selected_address);
+
+    if differs {
+        Some(format!("this policy's address differs across taproot modes — you selected {}", selected_mode))
+    } else {
+        None
+    }
+}
+
 // Taproot compilation with mode and network support
 fn compile_taproot_with_mode_network(
     expression: &str,
@@ -162,7 +666,7 @@ fn compile_taproot_with_mode_network(
     nums_key: &str,
     network: Network
 ) -> Result<(String, String, Option<String>, usize, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>, Option<String>), String> {
-    compile_taproot_with_mode_network_debug(expression, mode, nums_key, network, false).map(|(a,b,c,d,e,f,g,h,i,j,_,_)| (a,b,c,d,e,f,g,h,i,j))
+    compile_taproot_with_mode_network_debug(expression, mode, nums_key, network, false, &mut StageTimer::new(false)).map(|(a,b,c,d,e,f,g,h,i,j,_,_)| (a,b,c,d,e,f,g,h,i,j))
 }
 
 // Taproot compilation with mode, network and debug support
@@ -171,14 +675,17 @@ fn compile_taproot_with_mode_network_debug(
     mode: &str,
     nums_key: &str,
     network: Network,
-    verbose_debug: bool
+    verbose_debug: bool,
+    timer: &mut StageTimer,
 ) -> Result<(String, String, Option<String>, usize, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>, Option<String>, Option<crate::types::DebugInfo>, Option<Vec<crate::types::LeafDebugInfo>>), String> {
     console_log!("=== COMPILE_TAPROOT_WITH_MODE_NETWORK ===\nExpression: {}\nMode: {}\nNetwork: {:?}", expression, mode, network);
 
     let mut response = compile_taproot_with_mode(expression, mode, nums_key, network)?;
+    timer.stage("compile");
     console_log!("DEBUG: response.compiled_miniscript after compile_taproot_with_mode: {:?}", response.compiled_miniscript);
 
-    if network != Network::Bitcoin {
+    // The unique internal key is only known to the compiled descriptor, which already used this network
+    if network != Network::Bitcoin && mode != "script-path-unique" {
         console_log!("Regenerating taproot address for different network: {:?}", network);
 
         if let Some(ref _script_hex) = response.script {
@@ -201,6 +708,7 @@ fn compile_taproot_with_mode_network_debug(
                 console_log!("Successfully regenerated address for network: {}", response.address.as_ref().unwrap());
             }
         }
+        timer.stage("address");
     }
 
     // Generate debug info if verbose mode enabled (only if not already populated)
@@ -241,6 +749,9 @@ fn compile_taproot_with_mode(
 ) -> Result<crate::compile::types::CompileResponse, String> {
     console_log!("=== COMPILE_TAPROOT_WITH_MODE ===\nExpression: {}\nMode: {}\nNetwork: {:?}", expression, mode, network);
 
+    validation::validate_timelocks(expression)?;
+    validation::validate_multi_a(expression, "taproot")?;
+
     match mode {
         "multi-leaf" => {
             console_log!("Using multi-leaf compilation");
@@ -254,6 +765,10 @@ fn compile_taproot_with_mode(
             console_log!("Using script-path compilation");
             crate::compile::modes::compile_taproot_script_path(expression, nums_key, network, true)
         },
+        "script-path-unique" => {
+            console_log!("Using script-path compilation with a tree-derived internal key");
+            crate::compile::modes::compile_taproot_script_path_unique(expression, network, true)
+        },
         "default" | _ => {
             console_log!("Using default taproot compilation with multi-leaf detection");
             crate::compile::modes::compile_taproot_multi_leaf(expression, network, true)
@@ -266,14 +781,15 @@ pub(crate) fn compile_non_taproot_context(
     expression: &str,
     context: &str
 ) -> Result<(String, String, Option<String>, usize, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>, Option<String>), String> {
-    compile_non_taproot_context_debug(expression, context, false).map(|(a,b,c,d,e,f,g,h,i,j,_)| (a,b,c,d,e,f,g,h,i,j))
+    compile_non_taproot_context_debug(expression, context, false, &mut StageTimer::new(false)).map(|(a,b,c,d,e,f,g,h,i,j,_)| (a,b,c,d,e,f,g,h,i,j))
 }
 
 // Non-taproot context compilation with debug support
 pub(crate) fn compile_non_taproot_context_debug(
     expression: &str,
     context: &str,
-    verbose_debug: bool
+    verbose_debug: bool,
+    timer: &mut StageTimer,
 ) -> Result<(String, String, Option<String>, usize, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>, Option<String>, Option<crate::types::DebugInfo>), String> {
     console_log!("=== COMPILE_NON_TAPROOT_CONTEXT ===\nExpression: {}\nContext: {}", expression, context);
 
@@ -282,16 +798,21 @@ pub(crate) fn compile_non_taproot_context_debug(
     }
 
     let trimmed = expression.trim();
+    validation::validate_timelocks(trimmed)?;
+    validation::validate_multi_a(trimmed, context)?;
     let network = detect_network(trimmed);
 
     let processed_expr = if needs_descriptor_processing(trimmed) {
-        process_expression_descriptors(trimmed)?
+        let processed = process_expression_descriptors(trimmed)?;
+        timer.stage("expand_descriptors");
+        processed
     } else {
         trimmed.to_string()
     };
 
     if is_descriptor_wrapper(&processed_expr) {
         let desc_result = compile_descriptor(&processed_expr, context)?;
+        timer.stage("compile");
         // Add debug info placeholder for descriptors
         let debug_info = if verbose_debug {
             crate::compile::debug::extract_descriptor_debug_info::<bitcoin::PublicKey>(&processed_expr, true)
@@ -302,11 +823,12 @@ pub(crate) fn compile_non_taproot_context_debug(
     }
 
     let result = match context {
-        "legacy" => crate::compile::miniscript::compile_legacy_miniscript_with_debug(&processed_expr, network, verbose_debug),
-        "segwit" => crate::compile::miniscript::compile_segwit_miniscript_with_debug(&processed_expr, network, verbose_debug),
+        "legacy" => crate::compile::miniscript::compile_legacy_miniscript_timed(&processed_expr, network, verbose_debug, timer),
+        "segwit" => crate::compile::miniscript::compile_segwit_miniscript_timed(&processed_expr, network, verbose_debug, timer),
         "taproot" => crate::compile::miniscript::compile_taproot_miniscript_with_debug(&processed_expr, network, verbose_debug),
         _ => Err(format!("Invalid context: {}. Use 'legacy', 'segwit', or 'taproot'", context))
     }?;
+    timer.stage(if context == "taproot" { "compile" } else { "address" });
 
     // Extract debug output from result (it's the 11th element, index 10)
     let actual_debug_output = if verbose_debug {
@@ -1,14 +1,15 @@
 //! Miniscript implementation
 
-use miniscript::{Miniscript, Tap, Segwitv0, Legacy, Descriptor};
+use miniscript::{Miniscript, Tap, Legacy, Descriptor};
 use miniscript::descriptor::TapTree;
-use bitcoin::{PublicKey, XOnlyPublicKey, Network, Address, secp256k1::Secp256k1, taproot::TaprootBuilder};
-use std::str::FromStr;
+use bitcoin::{PublicKey, XOnlyPublicKey, Network, Address, taproot::TaprootBuilder};
+use crate::utils::secp;
 use std::sync::Arc;
 use crate::console_log;
-use crate::taproot::utils::get_taproot_nums_point;
+use crate::taproot::utils::get_nums_point;
 use crate::taproot::utils::NUMS_POINT;
 use crate::descriptors::compiler::compile_parsed_descriptor;
+use crate::compile::engine::StageTimer;
 
 
 /// Compile Legacy context miniscript
@@ -17,6 +18,11 @@ pub fn compile_legacy_miniscript(expression: &str, network: Network) -> Result<(
 }
 
 pub fn compile_legacy_miniscript_with_debug(expression: &str, network: Network, verbose_debug: bool) -> Result<(String, String, Option<String>, usize, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>, Option<String>, Option<String>), String> {
+    compile_legacy_miniscript_timed(expression, network, verbose_debug, &mut StageTimer::new(false))
+}
+
+// Legacy compile that closes the "compile" stage before deriving the address
+pub(crate) fn compile_legacy_miniscript_timed(expression: &str, network: Network, verbose_debug: bool, timer: &mut StageTimer) -> Result<(String, String, Option<String>, usize, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>, Option<String>, Option<String>), String> {
     match expression.parse::<Miniscript<PublicKey, Legacy>>() {
         Ok(ms) => {
             // Capture debug info if verbose mode enabled
@@ -43,11 +49,14 @@ pub fn compile_legacy_miniscript_with_debug(expression: &str, network: Network,
             
             console_log!("Legacy max_weight_to_satisfy: {} WU", max_weight.to_wu());
             
-            let max_satisfaction_size = Some((max_weight.to_wu() as f64 / 4.0) as usize);
+            // Legacy has no witness discount: the satisfaction lives in the scriptSig, so report
+            // its byte size directly. max_weight_to_satisfy is scriptSig size * 4 (plus redeemScript push).
+            let max_satisfaction_size = ms.max_satisfaction_size().ok();
             let max_weight_to_satisfy = Some(max_weight.to_wu());
             let sanity_check = ms.sanity_check().is_ok();
             let is_non_malleable = ms.is_non_malleable();
             
+            timer.stage("compile");
             let address = Address::p2sh(&script, network).ok().map(|a| a.to_string());
             
             Ok((
@@ -81,7 +90,13 @@ pub fn compile_segwit_miniscript(expression: &str, network: Network) -> Result<(
 }
 
 pub fn compile_segwit_miniscript_with_debug(expression: &str, network: Network, verbose_debug: bool) -> Result<(String, String, Option<String>, usize, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>, Option<String>, Option<String>), String> {
-    match expression.parse::<Miniscript<PublicKey, Segwitv0>>() {
+    compile_segwit_miniscript_timed(expression, network, verbose_debug, &mut StageTimer::new(false))
+}
+
+// Segwit compile that closes the "compile" stage before deriving the address
+pub(crate) fn compile_segwit_miniscript_timed(expression: &str, network: Network, verbose_debug: bool, timer: &mut StageTimer) -> Result<(String, String, Option<String>, usize, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>, Option<String>, Option<String>), String> {
+    // Scripts over the relay limits are still consensus-valid; they compile and get a standardness warning
+    match crate::validation::parse_segwit_relaxed::<PublicKey>(expression) {
         Ok(ms) => {
             // Capture debug info if verbose mode enabled
             let debug_output = if verbose_debug {
@@ -112,6 +127,7 @@ pub fn compile_segwit_miniscript_with_debug(expression: &str, network: Network,
             let sanity_check = ms.sanity_check().is_ok();
             let is_non_malleable = ms.is_non_malleable();
             
+            timer.stage("compile");
             let address = Some(Address::p2wsh(&script, network).to_string());
             
             Ok((
@@ -163,20 +179,16 @@ pub fn compile_taproot_miniscript_raw(expression: &str) -> Result<(String, Strin
             
             // For single-leaf mode, we still generate the same taproot address
             // but show the raw miniscript script in HEX/ASM
-            let nums_point_str = NUMS_POINT;
-            let nums_key = match XOnlyPublicKey::from_str(nums_point_str) {
-                Ok(key) => key,
-                Err(_) => return Err("Failed to parse NUMS point".to_string())
-            };
+            let nums_key = get_nums_point();
             
             // Create taproot address (same as multi-leaf for address consistency)
-            let secp = Secp256k1::verification_only();
+            let secp = secp();
             match TaprootBuilder::new().add_leaf(0, script.clone()) {
                 Ok(builder) => {
-                    match builder.finalize(&secp, nums_key) {
+                    match builder.finalize(secp, nums_key) {
                         Ok(spend_info) => {
                             let output_key = spend_info.output_key();
-                            let address = Address::p2tr(&secp, output_key.to_x_only_public_key(), None, network);
+                            let address = Address::p2tr(secp, output_key.to_x_only_public_key(), None, network);
                             
                             // Calculate weight info based on raw script
                             let max_satisfaction_size = ms.max_satisfaction_size().ok();
@@ -241,7 +253,8 @@ pub fn compile_taproot_miniscript_multiline(expression: &str, internal_key: Opti
                     key.to_string()
                 },
                 None => {
-                    let extracted = crate::keys::extract_internal_key_from_expression(expression);
+                    // Use the normalized form so c:pk_k(A) resolves the same key as pk(A)
+                    let extracted = crate::keys::extract_internal_key_from_expression(&normalized_miniscript);
                     console_log!("DEBUG MULTILINE: Extracted internal key from expression: {}", extracted);
                     extracted
                 }
@@ -249,7 +262,7 @@ pub fn compile_taproot_miniscript_multiline(expression: &str, internal_key: Opti
             
             let internal_key = if internal_key_name == NUMS_POINT {
                 console_log!("DEBUG MULTILINE: Using NUMS point as internal key");
-                get_taproot_nums_point()
+                get_nums_point()
             } else if let Ok(key_bytes) = hex::decode(&internal_key_name) {
                 console_log!("DEBUG MULTILINE: Trying to decode hex key: {} (length: {})", internal_key_name, key_bytes.len());
                 if key_bytes.len() == 32 {
@@ -258,26 +271,26 @@ pub fn compile_taproot_miniscript_multiline(expression: &str, internal_key: Opti
                         xonly_key
                     } else {
                         console_log!("DEBUG MULTILINE: Failed to create XOnlyPublicKey from slice, using NUMS");
-                        get_taproot_nums_point()
+                        get_nums_point()
                     }
                 } else {
                     console_log!("DEBUG MULTILINE: Key bytes length is not 32 ({}), using NUMS", key_bytes.len());
-                    get_taproot_nums_point()
+                    get_nums_point()
                 }
             } else {
                 console_log!("DEBUG MULTILINE: Failed to decode hex key: {}, using NUMS", internal_key_name);
-                get_taproot_nums_point()
+                get_nums_point()
             };
             
             // Create TapTree with the miniscript
-            let secp = Secp256k1::verification_only();
+            let secp = secp();
             match TaprootBuilder::new().add_leaf(0, ms.encode()) {
                 Ok(builder) => {
-                    match builder.finalize(&secp, internal_key) {
+                    match builder.finalize(secp, internal_key) {
                         Ok(spend_info) => {
                             // Get the output key for address
                             let output_key = spend_info.output_key();
-                            let address = Address::p2tr(&secp, output_key.to_x_only_public_key(), None, network);
+                            let address = Address::p2tr(secp, output_key.to_x_only_public_key(), None, network);
                             
                             // Build the scriptPubKey (OP_1 + 32-byte key)
                             let script_pubkey = address.script_pubkey();
@@ -366,7 +379,8 @@ pub fn compile_taproot_miniscript_with_debug(expression: &str, network: Network,
             console_log!("After OR transformation: {}", transformed_miniscript);
             
             // Extract internal key name and resolve to actual key
-            let internal_key_name = crate::keys::extract_internal_key_from_expression(expression);
+            // Use the normalized form so c:pk_k(A) resolves the same key as pk(A)
+            let internal_key_name = crate::keys::extract_internal_key_from_expression(&normalized_miniscript);
             console_log!("Extracted internal key name: {}", internal_key_name);
             
             // If we transformed an OR pattern, create a new tr() descriptor with tree notation
@@ -436,7 +450,7 @@ pub fn compile_taproot_miniscript_with_debug(expression: &str, network: Network,
                     // Resolve internal key name to actual XOnlyPublicKey
                     let internal_key = if internal_key_name == NUMS_POINT {
                         console_log!("DEBUG: Using NUMS point as internal key");
-                        get_taproot_nums_point()
+                        get_nums_point()
                     } else if let Ok(key_bytes) = hex::decode(&internal_key_name) {
                         console_log!("DEBUG: Trying to decode hex key: {} (length: {})", internal_key_name, key_bytes.len());
                         if key_bytes.len() == 32 {
@@ -445,15 +459,15 @@ pub fn compile_taproot_miniscript_with_debug(expression: &str, network: Network,
                                 xonly_key
                             } else {
                                 console_log!("DEBUG: Failed to create XOnlyPublicKey from slice, using NUMS");
-                                get_taproot_nums_point()
+                                get_nums_point()
                             }
                         } else {
                             console_log!("DEBUG: Key bytes length is not 32 ({}), using NUMS", key_bytes.len());
-                            get_taproot_nums_point()
+                            get_nums_point()
                         }
                     } else {
                         console_log!("DEBUG: Failed to decode hex key: {}, using NUMS", internal_key_name);
-                        get_taproot_nums_point()
+                        get_nums_point()
                     };
                     
                     // Create descriptor using new_tr method
@@ -521,7 +535,7 @@ pub fn compile_taproot_miniscript_with_debug(expression: &str, network: Network,
                     let is_non_malleable = ms.is_non_malleable();
                     
                     // Build descriptor string with resolved internal key name  
-                    let descriptor_string = format!("tr({},{})", internal_key_name, expression);
+                    let descriptor_string = format!("tr({},{})", internal_key_name, normalized_miniscript);
                     console_log!("Generated Taproot descriptor: {}", descriptor_string);
                     console_log!("Generated Taproot script hex: {}", script_hex);
                     console_log!("Generated Taproot address: {:?}", address);
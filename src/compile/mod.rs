@@ -12,4 +12,5 @@ pub mod engine;
 pub mod options;
 pub mod utils;
 pub mod debug;
+pub mod simplify;
 
@@ -44,7 +44,8 @@ pub fn compile_taproot_multi_leaf(expression: &str, network: Network, verbose: b
             let max_weight_to_satisfy = max_satisfaction_size.map(|s| s as u64);
 
             // Extract internal key from expression (INSTEAD OF NUMS)
-            let internal_key_str = crate::keys::extract_internal_key_from_expression(expression);
+            // Use the normalized form so c:pk_k(A) resolves the same key as pk(A)
+            let internal_key_str = crate::keys::extract_internal_key_from_expression(&normalized_miniscript);
             console_log!("DEBUG DESCRIPTOR KEYPATH: Extracted internal key: {}", internal_key_str);
 
             // Parse internal key (INSTEAD OF NUMS)
@@ -226,10 +227,7 @@ pub fn compile_taproot_single_leaf(expression: &str, nums_key: &str, network: Ne
             let max_weight_to_satisfy = max_satisfaction_size.map(|s| s as u64);
             
             // Parse NUMS key
-            let nums_xonly_key = match XOnlyPublicKey::from_str(nums_key) {
-                Ok(key) => key,
-                Err(_) => return Err(format!("Failed to parse NUMS key: {}", nums_key))
-            };
+            let nums_xonly_key = crate::taproot::utils::parse_nums_key(nums_key)?;
             
             console_log!("DEBUG DESCRIPTOR SIMPLIFIED: Using NUMS key: {}", nums_xonly_key);
             
@@ -332,10 +330,7 @@ pub fn compile_taproot_script_path(expression: &str, nums_key: &str, network: Ne
             let max_weight_to_satisfy = max_satisfaction_size.map(|s| s as u64);
             
             // Parse NUMS key
-            let nums_xonly_key = match XOnlyPublicKey::from_str(nums_key) {
-                Ok(key) => key,
-                Err(_) => return Err(format!("Failed to parse NUMS key: {}", nums_key))
-            };
+            let nums_xonly_key = crate::taproot::utils::parse_nums_key(nums_key)?;
             
             console_log!("DEBUG DESCRIPTOR: Using NUMS key: {}", nums_xonly_key);
             
@@ -472,3 +467,23 @@ pub fn compile_taproot_script_path(expression: &str, nums_key: &str, network: Ne
         }
     }
 }
+
+/// Compile taproot script-path mode with an unspendable internal key unique to the script tree
+pub fn compile_taproot_script_path_unique(expression: &str, network: Network, verbose: bool) -> Result<CompileResponse, String> {
+    console_log!("=== COMPILE_TAPROOT_SCRIPT_PATH_UNIQUE ===");
+
+    // Compile against NUMS first to learn the tree, then recompile with the key derived from it
+    let nums_response = compile_taproot_script_path(expression, crate::taproot::utils::NUMS_POINT, network, false)?;
+    let Some(compiled) = nums_response.compiled_miniscript.as_deref().filter(|_| nums_response.success) else {
+        return Ok(nums_response);
+    };
+    let tree = match Descriptor::<XOnlyPublicKey>::from_str(compiled) {
+        Ok(Descriptor::Tr(tr)) => tr.tap_tree().clone().ok_or("Script-path compilation produced no script tree")?,
+        Ok(_) => return Err("Script-path compilation did not produce a tr() descriptor".to_string()),
+        Err(e) => return Err(format!("Failed to parse compiled descriptor: {}", e)),
+    };
+
+    let internal_key = crate::taproot::utils::unspendable_key_for_tree(&tree)?;
+    console_log!("Derived unique unspendable internal key: {}", internal_key);
+    compile_taproot_script_path(expression, &internal_key.to_string(), network, verbose)
+}
@@ -23,6 +23,18 @@ pub struct CompileOptions {
     // Enable verbose debug output
     #[serde(default)]
     pub verbose_debug: bool,
+    // Compare the taproot address across modes and note when it differs
+    #[serde(default)]
+    pub compare_taproot_modes: bool,
+    // Convert compressed keys to x-only for Taproot instead of rejecting them
+    #[serde(default)]
+    pub auto_convert_keys: bool,
+    // Override for the maximum accepted expression length (defaults to MAX_EXPRESSION_LENGTH)
+    #[serde(default)]
+    pub max_expression_length: Option<usize>,
+    // Record elapsed milliseconds per compile stage
+    #[serde(default)]
+    pub timing: bool,
 }
 
 fn default_network_string() -> String {
@@ -50,6 +62,10 @@ impl Default for CompileOptions {
             network_str: "bitcoin".to_string(),
             nums_key: None,
             verbose_debug: false,
+            compare_taproot_modes: false,
+            auto_convert_keys: false,
+            max_expression_length: None,
+            timing: false,
         }
     }
 }
@@ -102,6 +118,9 @@ pub enum CompileMode {
     MultiLeaf,
     #[serde(alias = "ScriptPath")]
     ScriptPath,
+    // Script-only with an unspendable internal key derived from the merkle root
+    #[serde(alias = "ScriptPathUnique")]
+    ScriptPathUnique,
 }
 
 impl CompileMode {
@@ -112,6 +131,7 @@ impl CompileMode {
             "single-leaf" => Ok(CompileMode::SingleLeaf),
             "multi-leaf" => Ok(CompileMode::MultiLeaf),
             "script-path" => Ok(CompileMode::ScriptPath),
+            "script-path-unique" => Ok(CompileMode::ScriptPathUnique),
             _ => Err(format!("Invalid mode: {}", s))
         }
     }
@@ -123,6 +143,7 @@ impl CompileMode {
             CompileMode::SingleLeaf => "single-leaf",
             CompileMode::MultiLeaf => "multi-leaf",
             CompileMode::ScriptPath => "script-path",
+            CompileMode::ScriptPathUnique => "script-path-unique",
         }
     }
 }
@@ -144,6 +165,10 @@ impl CompileOptions {
             network_str,
             nums_key: None,
             verbose_debug: false,
+            compare_taproot_modes: false,
+            auto_convert_keys: false,
+            max_expression_length: None,
+            timing: false,
         })
     }
 
@@ -163,6 +188,10 @@ impl CompileOptions {
             network_str,
             nums_key,
             verbose_debug: false,
+            compare_taproot_modes: false,
+            auto_convert_keys: false,
+            max_expression_length: None,
+            timing: false,
         })
     }
 }
\ No newline at end of file
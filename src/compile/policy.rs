@@ -2,13 +2,23 @@
 
 use miniscript::{Miniscript, Tap, Segwitv0, Legacy, policy::Concrete, Descriptor};
 use bitcoin::{PublicKey, XOnlyPublicKey, Network};
-use std::str::FromStr;
 use crate::console_log;
 use miniscript::descriptor::DescriptorPublicKey;
 use crate::descriptors::parser::parse_descriptors;
 use crate::descriptors::utils::replace_descriptors_with_keys;
 use crate::translators::{DescriptorKeyTranslator, XOnlyDescriptorKeyTranslator};
 use crate::taproot::utils::NUMS_POINT;
+use crate::compile::types::{AutoContextChoice, ContextCandidate, OptimalMiniscript};
+use crate::validation::{COMPRESSED_KEY, POLICY_KEY_ARG, XONLY_KEY};
+
+/// Error for a policy whose keys are all x-only, compiled outside taproot
+pub const XONLY_POLICY_NEEDS_TAPROOT: &str = "This policy uses x-only keys and can only be compiled for Taproot";
+
+/// Whether every pk() key in a policy is a bare x-only key (and there is at least one)
+pub(crate) fn uses_only_xonly_keys(policy: &str) -> bool {
+    let mut keys = POLICY_KEY_ARG.captures_iter(policy).map(|c| c[1].trim().to_string()).peekable();
+    keys.peek().is_some() && keys.all(|key| key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit()))
+}
 
 /// Compile policy to miniscript
 pub fn compile_policy_to_miniscript(policy: &str, context: &str) -> Result<(String, String, Option<String>, usize, String, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>), String> {
@@ -21,13 +31,21 @@ pub fn compile_policy_to_miniscript_with_mode(policy: &str, context: &str, mode:
         return Err("Empty policy - please enter a policy expression".to_string());
     }
 
-    let trimmed = policy.trim();
+    let stripped = crate::parse::helpers::strip_whitespace(policy)?;
+    let trimmed = stripped.as_str();
+
+    crate::validation::validate_timelocks(trimmed)?;
+    if split_tr_policy(trimmed).is_some() && context != "taproot" {
+        return Err("tr(KEY, policy) sets a taproot internal key; select the taproot context".to_string());
+    }
     
     // Check for incompatible key types based on context
     if context != "taproot" {
+        if uses_only_xonly_keys(trimmed) {
+            return Err(XONLY_POLICY_NEEDS_TAPROOT.to_string());
+        }
         // Check for x-only keys (64 hex chars) in non-taproot contexts
-        let xonly_key_regex = regex::Regex::new(r"\b[a-fA-F0-9]{64}\b").unwrap();
-        if xonly_key_regex.is_match(trimmed) {
+        if XONLY_KEY.is_match(trimmed) {
             // Check if it's not an xpub/tpub, descriptor, or SHA256 hash
             if !trimmed.contains("xpub") && !trimmed.contains("tpub") && !trimmed.contains("[") && !trimmed.contains("sha256(") {
                 return Err(format!(
@@ -38,8 +56,7 @@ pub fn compile_policy_to_miniscript_with_mode(policy: &str, context: &str, mode:
         }
     } else {
         // Check for compressed keys (66 hex chars starting with 02/03) in taproot context
-        let compressed_key_regex = regex::Regex::new(r"\b(02|03)[a-fA-F0-9]{64}\b").unwrap();
-        if compressed_key_regex.is_match(trimmed) {
+        if COMPRESSED_KEY.is_match(trimmed) {
             // Check if it's not part of a descriptor
             if !trimmed.contains("xpub") && !trimmed.contains("tpub") && !trimmed.contains("[") {
                 return Err("Taproot context requires x-only keys (64 characters). Found compressed key (66 characters starting with 02/03). Please check your compile context selection.".to_string());
@@ -54,6 +71,10 @@ pub fn compile_policy_to_miniscript_with_mode(policy: &str, context: &str, mode:
         Network::Bitcoin
     };
     
+    if let Some((key, inner)) = split_tr_policy(trimmed) {
+        return compile_taproot_policy_with_internal_key(key, inner, network);
+    }
+
     console_log!("Processing policy directly: {}", trimmed);
     
     // Check if policy contains descriptor keys
@@ -331,7 +352,6 @@ pub fn compile_taproot_policy_xonly_with_mode(
     network: Network,
     mode: &str
 ) -> Result<(String, String, Option<String>, usize, String, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>), String> {
-    use miniscript::descriptor::TapTree;
     
     console_log!("compile_taproot_policy_xonly_with_mode called with mode: {}", mode);
     
@@ -341,83 +361,138 @@ pub fn compile_taproot_policy_xonly_with_mode(
             console_log!("Using single-leaf compilation mode");
             compile_taproot_policy_xonly_single_leaf(policy, network)
         },
-        "script-path" | "multi-leaf" => {
+        "script-path" | "script-path-unique" | "multi-leaf" => {
             // Script-path mode (NUMS point) or Key+Script mode
             console_log!("Using {} compilation mode", mode);
             
-            // Parse and compile policy (keys are treated as strings)
-            let compiled: Miniscript<XOnlyPublicKey, Tap> = policy.compile::<Tap>()
-                .map_err(|e| format!("Failed to compile policy: {}", e))?;
-
-            // Collect keys
-            let mut keys: Vec<String> = policy.keys().into_iter().map(|k| k.to_string()).collect();
-            keys.sort(); 
-            keys.dedup();
-            console_log!("Keys in policy: {:?}", keys);
-
-            // Special handling for the specific case: or(pk(A), or(pk(B), pk(C)))
-            // Should become: {{pk(A), pk(B)}, pk(C)}
-            let tree = if let Concrete::Or(branches) = &policy {
-                if branches.len() == 2 {
-                    let (_, first) = &branches[0];
-                    let (_, second) = &branches[1];
+            let tree = policy_tap_tree(&policy)?;
+            crate::taproot::utils::validate_tap_tree(&tree)?;
+
+            // Determine internal key based on mode
+            let internal_key = if mode == "script-path" {
+                // BIP341 NUMS internal key (script-only pattern)
+                let nums = crate::taproot::utils::get_nums_point();
+                console_log!("Using NUMS point as internal key for script-only mode");
+                nums
+            } else if mode == "script-path-unique" {
+                // Unspendable internal key unique to this tree
+                let unique = crate::taproot::utils::unspendable_key_for_tree(&tree)?;
+                console_log!("Using tree-derived unspendable internal key: {}", unique);
+                unique
+            } else {
+                // Key+Script mode: use first key from policy as internal key
+                let chosen_xonly = policy.keys()
+                    .into_iter()
+                    .next()
+                    .ok_or("Policy contains no keys")?;
+                console_log!("Using policy key as internal key for key+script mode: {}", chosen_xonly);
+                *chosen_xonly
+            };
+            
+            taproot_policy_descriptor(internal_key, tree, network)
+        },
+        _ => {
+            Err(format!("Unknown taproot compilation mode: {}", mode))
+        }
+    }
+}
+
+/// Split a `tr(KEY,POLICY)` policy into its internal key and inner policy
+pub(crate) fn split_tr_policy(policy: &str) -> Option<(&str, &str)> {
+    let body = policy.strip_prefix("tr(")?.strip_suffix(')')?;
+    let mut depth = 0usize;
+    for (i, c) in body.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => return Some((&body[..i], &body[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parse a taproot policy with x-only keys, translating descriptor keys when present
+fn parse_xonly_policy(policy: &str) -> Result<Concrete<XOnlyPublicKey>, String> {
+    if let Ok(xonly_policy) = policy.parse::<Concrete<XOnlyPublicKey>>() {
+        return Ok(xonly_policy);
+    }
+    let descriptor_policy = policy.parse::<Concrete<DescriptorPublicKey>>()
+        .map_err(|e| format!("Invalid policy: {}", e))?;
+    descriptor_policy.translate_pk(&mut XOnlyDescriptorKeyTranslator::new())
+        .map_err(|_| "Failed to translate descriptor keys to x-only keys for Taproot".to_string())
+}
+
+/// Compile `tr(KEY,POLICY)`: KEY becomes the internal key (the key path) and the whole
+/// inner policy is compiled into the script tree, never moved to the key path
+fn compile_taproot_policy_with_internal_key(
+    key: &str,
+    inner: &str,
+    network: Network
+) -> Result<(String, String, Option<String>, usize, String, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>), String> {
+    let Concrete::Key(internal_key) = parse_xonly_policy(&format!("pk({})", key))? else {
+        return Err(format!("Invalid internal key: {}", key));
+    };
+    let policy = parse_xonly_policy(inner)?;
+    console_log!("Compiling policy under explicit internal key {}: {}", internal_key, inner);
+
+    let tree = policy_tap_tree(&policy)?;
+    crate::taproot::utils::validate_tap_tree(&tree)?;
+    taproot_policy_descriptor(internal_key, tree, network)
+}
+
+/// Build the script tree for a taproot policy: one leaf per top-level `or` branch
+/// (with or(pk(A),or(pk(B),pk(C))) grouped as {pk(A),{pk(B),pk(C)}}), else a single leaf
+fn policy_tap_tree(policy: &Concrete<XOnlyPublicKey>) -> Result<miniscript::descriptor::TapTree<XOnlyPublicKey>, String> {
+    use miniscript::descriptor::TapTree;
+
+    // Parse and compile policy (keys are treated as strings)
+    let compiled: Miniscript<XOnlyPublicKey, Tap> = policy.compile::<Tap>()
+        .map_err(|e| format!("Failed to compile policy: {}", e))?;
+
+    // Collect keys
+    let mut keys: Vec<String> = policy.keys().into_iter().map(|k| k.to_string()).collect();
+    keys.sort(); 
+    keys.dedup();
+    console_log!("Keys in policy: {:?}", keys);
+
+    // Special handling for the specific case: or(pk(A), or(pk(B), pk(C)))
+    // Should become: {{pk(A), pk(B)}, pk(C)}
+    let tree = if let Concrete::Or(branches) = &policy {
+        if branches.len() == 2 {
+            let (_, first) = &branches[0];
+            let (_, second) = &branches[1];
+            
+            // Check if this is the pattern: or(pk(A), or(pk(B), pk(C)))
+            if let Concrete::Or(nested_branches) = &**second {
+                if nested_branches.len() == 2 {
+                    let (_, nested_first) = &nested_branches[0];
+                    let (_, nested_second) = &nested_branches[1];
                     
-                    // Check if this is the pattern: or(pk(A), or(pk(B), pk(C)))
-                    if let Concrete::Or(nested_branches) = &**second {
-                        if nested_branches.len() == 2 {
-                            let (_, nested_first) = &nested_branches[0];
-                            let (_, nested_second) = &nested_branches[1];
-                            
-                            // Check if all are pk() nodes
-                            if let (Concrete::Key(_), Concrete::Key(_), Concrete::Key(_)) = (&**first, &**nested_first, &**nested_second) {
-                                // Special case: keep first key separate, group second and third keys together
-                                let first_pk = (**first).clone();
-                                let second_pk = (**nested_first).clone();
-                                let third_pk = (**nested_second).clone();
-                                
-                                // Create TapTree structure: {pk(A), {pk(B), pk(C)}}
-                                // Left branch: pk(A) - single leaf
-                                // Right branch: {pk(B), pk(C)} - two separate leaves
-                                let first_ms: Miniscript<XOnlyPublicKey, Tap> = first_pk.compile::<Tap>()
-                                    .map_err(|e| format!("Failed to compile first pk: {:?}", e))?;
-                                let second_ms: Miniscript<XOnlyPublicKey, Tap> = second_pk.compile::<Tap>()
-                                    .map_err(|e| format!("Failed to compile second pk: {:?}", e))?;
-                                let third_ms: Miniscript<XOnlyPublicKey, Tap> = third_pk.compile::<Tap>()
-                                    .map_err(|e| format!("Failed to compile third pk: {:?}", e))?;
-                                
-                                let left_branch = TapTree::Leaf(first_ms.into());
-                                let right_branch = TapTree::combine(
-                                    TapTree::Leaf(second_ms.into()),
-                                    TapTree::Leaf(third_ms.into())
-                                );
-                                
-                                TapTree::combine(left_branch, right_branch)
-                            } else {
-                                // Not the special pattern, use default behavior
-                                let mut leaves: Vec<TapTree<XOnlyPublicKey>> = Vec::new();
-                                for (_, sub) in branches {
-                                    let ms: Miniscript<XOnlyPublicKey, Tap> = (**sub).compile::<Tap>()
-                                        .map_err(|e| format!("Failed to compile sub-policy: {:?}", e))?;
-                                    leaves.push(TapTree::Leaf(ms.into()));
-                                }
-                                leaves
-                                    .into_iter()
-                                    .reduce(|acc, t| TapTree::combine(acc, t))
-                                    .unwrap_or_else(|| TapTree::Leaf(compiled.into()))
-                            }
-                        } else {
-                            // Not the special pattern, use default behavior
-                            let mut leaves: Vec<TapTree<XOnlyPublicKey>> = Vec::new();
-                            for (_, sub) in branches {
-                                let ms: Miniscript<XOnlyPublicKey, Tap> = (**sub).compile::<Tap>()
-                                    .map_err(|e| format!("Failed to compile sub-policy: {:?}", e))?;
-                                leaves.push(TapTree::Leaf(ms.into()));
-                            }
-                            leaves
-                                .into_iter()
-                                .reduce(|acc, t| TapTree::combine(acc, t))
-                                .unwrap_or_else(|| TapTree::Leaf(compiled.into()))
-                        }
+                    // Check if all are pk() nodes
+                    if let (Concrete::Key(_), Concrete::Key(_), Concrete::Key(_)) = (&**first, &**nested_first, &**nested_second) {
+                        // Special case: keep first key separate, group second and third keys together
+                        let first_pk = (**first).clone();
+                        let second_pk = (**nested_first).clone();
+                        let third_pk = (**nested_second).clone();
+                        
+                        // Create TapTree structure: {pk(A), {pk(B), pk(C)}}
+                        // Left branch: pk(A) - single leaf
+                        // Right branch: {pk(B), pk(C)} - two separate leaves
+                        let first_ms: Miniscript<XOnlyPublicKey, Tap> = first_pk.compile::<Tap>()
+                            .map_err(|e| format!("Failed to compile first pk: {:?}", e))?;
+                        let second_ms: Miniscript<XOnlyPublicKey, Tap> = second_pk.compile::<Tap>()
+                            .map_err(|e| format!("Failed to compile second pk: {:?}", e))?;
+                        let third_ms: Miniscript<XOnlyPublicKey, Tap> = third_pk.compile::<Tap>()
+                            .map_err(|e| format!("Failed to compile third pk: {:?}", e))?;
+                        
+                        let left_branch = TapTree::Leaf(first_ms.into());
+                        let right_branch = TapTree::combine(
+                            TapTree::Leaf(second_ms.into()),
+                            TapTree::Leaf(third_ms.into())
+                        );
+                        
+                        TapTree::combine(left_branch, right_branch)
                     } else {
                         // Not the special pattern, use default behavior
                         let mut leaves: Vec<TapTree<XOnlyPublicKey>> = Vec::new();
@@ -445,72 +520,84 @@ pub fn compile_taproot_policy_xonly_with_mode(
                         .unwrap_or_else(|| TapTree::Leaf(compiled.into()))
                 }
             } else {
-                // Single policy, not an OR
-                TapTree::Leaf(compiled.into())
-            };
-            
-            // Determine internal key based on mode
-            let internal_key = if mode == "script-path" {
-                // BIP341 NUMS internal key (script-only pattern)
-                let nums = XOnlyPublicKey::from_str(
-                    NUMS_POINT
-                ).map_err(|e| format!("Failed to parse NUMS point: {}", e))?;
-                console_log!("Using NUMS point as internal key for script-only mode");
-                nums
-            } else {
-                // Key+Script mode: use first key from policy as internal key
-                let chosen_xonly = policy.keys()
+                // Not the special pattern, use default behavior
+                let mut leaves: Vec<TapTree<XOnlyPublicKey>> = Vec::new();
+                for (_, sub) in branches {
+                    let ms: Miniscript<XOnlyPublicKey, Tap> = (**sub).compile::<Tap>()
+                        .map_err(|e| format!("Failed to compile sub-policy: {:?}", e))?;
+                    leaves.push(TapTree::Leaf(ms.into()));
+                }
+                leaves
                     .into_iter()
-                    .next()
-                    .ok_or("Policy contains no keys")?;
-                console_log!("Using policy key as internal key for key+script mode: {}", chosen_xonly);
-                *chosen_xonly
-            };
-            
-            // Create the descriptor
-            let descriptor = Descriptor::<XOnlyPublicKey>::new_tr(internal_key, Some(tree))
-                .map_err(|e| format!("Failed to create taproot descriptor: {}", e))?;
-            
-            console_log!("Created taproot descriptor: {}", descriptor);
-            
-            // Get the output script (scriptPubKey)
-            let script = descriptor.script_pubkey();
-            let script_hex = hex::encode(script.as_bytes());
-            let script_asm = script.to_asm_string();
-            
-            // Generate address from descriptor
-            let address = descriptor.address(network)
-                .map(|addr| addr.to_string())
-                .ok();
-            
-            // Get script size
-            let script_size = script.len();
-            
-            // For display, we'll show the descriptor
-            let compiled_miniscript_display = descriptor.to_string();
-            
-            // Get max satisfaction weight if available
-            let max_weight_to_satisfy = descriptor.max_weight_to_satisfy()
-                .ok()
-                .and_then(|w| w.to_wu().try_into().ok());
-            
-            Ok((
-                script_hex,
-                script_asm,
-                address,
-                script_size,
-                "Taproot".to_string(),
-                compiled_miniscript_display,
-                None, // max_satisfaction_size not needed for taproot
-                max_weight_to_satisfy,
-                Some(true), // sanity_check - assume true for valid compilation
-                Some(true), // is_non_malleable - taproot is non-malleable
-            ))
-        },
-        _ => {
-            Err(format!("Unknown taproot compilation mode: {}", mode))
+                    .reduce(|acc, t| TapTree::combine(acc, t))
+                    .unwrap_or_else(|| TapTree::Leaf(compiled.into()))
+            }
+        } else {
+            // Not the special pattern, use default behavior
+            let mut leaves: Vec<TapTree<XOnlyPublicKey>> = Vec::new();
+            for (_, sub) in branches {
+                let ms: Miniscript<XOnlyPublicKey, Tap> = (**sub).compile::<Tap>()
+                    .map_err(|e| format!("Failed to compile sub-policy: {:?}", e))?;
+                leaves.push(TapTree::Leaf(ms.into()));
+            }
+            leaves
+                .into_iter()
+                .reduce(|acc, t| TapTree::combine(acc, t))
+                .unwrap_or_else(|| TapTree::Leaf(compiled.into()))
         }
-    }
+    } else {
+        // Single policy, not an OR
+        TapTree::Leaf(compiled.into())
+    };
+
+    Ok(tree)
+}
+
+/// Build the tr() descriptor for a compiled tree and report it in the policy compile tuple
+fn taproot_policy_descriptor(
+    internal_key: XOnlyPublicKey,
+    tree: miniscript::descriptor::TapTree<XOnlyPublicKey>,
+    network: Network
+) -> Result<(String, String, Option<String>, usize, String, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>), String> {
+    // Create the descriptor
+    let descriptor = Descriptor::<XOnlyPublicKey>::new_tr(internal_key, Some(tree))
+        .map_err(|e| format!("Failed to create taproot descriptor: {}", e))?;
+    
+    console_log!("Created taproot descriptor: {}", descriptor);
+    
+    // Get the output script (scriptPubKey)
+    let script = descriptor.script_pubkey();
+    let script_hex = hex::encode(script.as_bytes());
+    let script_asm = script.to_asm_string();
+    
+    // Generate address from descriptor
+    let address = descriptor.address(network)
+        .map(|addr| addr.to_string())
+        .ok();
+    
+    // Get script size
+    let script_size = script.len();
+    
+    // For display, we'll show the descriptor
+    let compiled_miniscript_display = descriptor.to_string();
+    
+    // Get max satisfaction weight if available
+    let max_weight_to_satisfy = descriptor.max_weight_to_satisfy()
+        .ok()
+        .and_then(|w| w.to_wu().try_into().ok());
+    
+    Ok((
+        script_hex,
+        script_asm,
+        address,
+        script_size,
+        "Taproot".to_string(),
+        compiled_miniscript_display,
+        None, // max_satisfaction_size not needed for taproot
+        max_weight_to_satisfy,
+        Some(true), // sanity_check - assume true for valid compilation
+        Some(true), // is_non_malleable - taproot is non-malleable
+    ))
 }
 
 /// Original single-leaf taproot compilation method for XOnlyPublicKey
@@ -630,10 +717,7 @@ pub fn compile_taproot_script_path_descriptor(expression: &str, nums_key: &str,
             let max_weight_to_satisfy = max_satisfaction_size.map(|s| s as u64);
             
             // Parse NUMS key
-            let nums_xonly_key = match XOnlyPublicKey::from_str(nums_key) {
-                Ok(key) => key,
-                Err(_) => return Err(format!("Failed to parse NUMS key: {}", nums_key))
-            };
+            let nums_xonly_key = crate::taproot::utils::parse_nums_key(nums_key)?;
             
             console_log!("DEBUG DESCRIPTOR: Using NUMS key: {}", nums_xonly_key);
             
@@ -739,3 +823,133 @@ pub fn compile_taproot_script_path_descriptor(expression: &str, nums_key: &str,
         Err(e) => Err(format!("Miniscript parsing failed: {}", e))
     }
 }
+
+/// Compile a policy in every context its keys allow and pick the cheapest by worst-case weight
+/// x-only keys → Taproot only; compressed keys → Legacy or Segwit v0; descriptor keys → any context
+pub fn compile_auto_context(policy: &str) -> Result<AutoContextChoice, String> {
+    let trimmed = policy.trim();
+    if trimmed.is_empty() {
+        return Err("Empty policy - please enter a policy expression".to_string());
+    }
+
+    let parsed: Concrete<String> = trimmed.parse()
+        .map_err(|e| format!("Policy parsing failed: {}", e))?;
+
+    let mut has_xonly = false;
+    let mut has_compressed = false;
+    let mut has_descriptor = false;
+    for key in parsed.keys() {
+        let is_hex = key.chars().all(|c| c.is_ascii_hexdigit());
+        if key.contains("xpub") || key.contains("tpub") || key.contains('[') {
+            has_descriptor = true;
+        } else if is_hex && key.len() == 64 {
+            has_xonly = true;
+        } else if is_hex && key.len() == 66 && (key.starts_with("02") || key.starts_with("03")) {
+            has_compressed = true;
+        } else {
+            return Err(format!("Cannot determine key type for '{}'. Use hex public keys or descriptor keys.", key));
+        }
+    }
+
+    let mut reasoning = Vec::new();
+    let contexts: Vec<&str> = match (has_xonly, has_compressed) {
+        (true, true) => return Err("Policy mixes x-only and compressed keys; no single context accepts both".to_string()),
+        (true, false) => {
+            reasoning.push("x-only keys found: only Taproot accepts them".to_string());
+            vec!["taproot"]
+        }
+        (false, true) => {
+            reasoning.push("compressed keys found: comparing Legacy and Segwit v0".to_string());
+            vec!["legacy", "segwit"]
+        }
+        (false, false) if has_descriptor => {
+            reasoning.push("only descriptor keys found: comparing all contexts".to_string());
+            vec!["legacy", "segwit", "taproot"]
+        }
+        (false, false) => return Err("Policy contains no keys to determine a context from".to_string()),
+    };
+
+    let mut candidates = Vec::new();
+    let mut best = None;
+    for context in contexts {
+        match compile_policy_to_miniscript(trimmed, context) {
+            Ok(result) => {
+                let weight = result.7;
+                candidates.push(ContextCandidate { context: context.to_string(), max_weight_to_satisfy: weight, error: None });
+                let better = match &best {
+                    None => true,
+                    Some((_, current_weight, _)) => weight.unwrap_or(u64::MAX) < *current_weight,
+                };
+                if better {
+                    best = Some((context, weight.unwrap_or(u64::MAX), result));
+                }
+            }
+            Err(e) => candidates.push(ContextCandidate { context: context.to_string(), max_weight_to_satisfy: None, error: Some(e) }),
+        }
+    }
+
+    let (context, _, result) = best.ok_or_else(|| "Policy did not compile in any candidate context".to_string())?;
+    for candidate in &candidates {
+        match (&candidate.error, candidate.max_weight_to_satisfy) {
+            (Some(e), _) => reasoning.push(format!("{}: failed ({})", candidate.context, e)),
+            (None, Some(w)) => reasoning.push(format!("{}: {} WU worst-case", candidate.context, w)),
+            (None, None) => reasoning.push(format!("{}: weight unknown", candidate.context)),
+        }
+    }
+    reasoning.push(format!("selected {} as the smallest worst-case weight", context));
+
+    Ok(AutoContextChoice {
+        context: context.to_string(),
+        reasoning,
+        script: result.0,
+        address: result.2,
+        compiled_miniscript: result.5,
+        max_weight_to_satisfy: result.7,
+        candidates,
+    })
+}
+
+/// Compile a policy and report the miniscript the compiler chose with its stats
+///
+/// Keys are kept as written, so placeholder names work; the weight is that of the
+/// miniscript wrapped for the context, with taproot using a single leaf under NUMS.
+pub fn policy_to_optimal_miniscript(policy: &str, context: &str) -> Result<OptimalMiniscript, String> {
+    use miniscript::descriptor::TapTree;
+    use std::sync::Arc;
+
+    let policy: Concrete<String> = policy.trim().parse()
+        .map_err(|e| format!("Policy parsing failed: {}", e))?;
+
+    fn stats<Ctx: miniscript::ScriptContext>(ms: &Miniscript<String, Ctx>) -> (String, usize, usize) {
+        (ms.to_string(), ms.script_size(), ms.ext.ops.count)
+    }
+
+    let ((miniscript, script_size, ops), descriptor) = match context.to_lowercase().as_str() {
+        "legacy" => {
+            let ms = policy.compile::<Legacy>().map_err(|e| format!("Policy compilation failed: {}", e))?;
+            (stats(&ms), Descriptor::new_sh(ms))
+        }
+        "segwit" => {
+            let ms = policy.compile::<Segwitv0>().map_err(|e| format!("Policy compilation failed: {}", e))?;
+            (stats(&ms), Descriptor::new_wsh(ms))
+        }
+        "taproot" => {
+            let ms = policy.compile::<Tap>().map_err(|e| format!("Policy compilation failed: {}", e))?;
+            (stats(&ms), Descriptor::new_tr(NUMS_POINT.to_string(), Some(TapTree::Leaf(Arc::new(ms)))))
+        }
+        _ => return Err(format!("Unknown context: {}. Use legacy, segwit, or taproot.", context)),
+    };
+
+    let max_sat_weight = descriptor.ok()
+        .and_then(|descriptor| descriptor.max_weight_to_satisfy().ok())
+        .map(|weight| weight.to_wu());
+    console_log!("Policy compiled to {} ({} bytes, {} ops)", miniscript, script_size, ops);
+
+    Ok(OptimalMiniscript {
+        miniscript,
+        script_size,
+        ops,
+        max_sat_weight,
+        chosen_over: Vec::new(),
+    })
+}
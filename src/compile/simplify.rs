@@ -0,0 +1,153 @@
+//! Policy simplification before compilation
+
+use std::str::FromStr;
+use std::sync::Arc;
+use miniscript::Threshold;
+use miniscript::policy::Concrete;
+use crate::console_log;
+
+/// Flatten nested same-operator `and`/`or` and drop identical branches
+///
+/// Concrete policies only allow two-argument `and`/`or`, so a flattened
+/// group of three or more becomes `thresh(n,...)` (all-of) or `thresh(1,...)`
+/// (any-of). `or` branches with probability weights are kept nested so the
+/// weights still mean what they did; duplicate `or` branches add up their weights.
+/// Returns the simplified policy and a description of each change applied.
+pub fn simplify_policy(policy: &str) -> Result<(String, Vec<String>), String> {
+    let parsed = Concrete::<String>::from_str(policy.trim())
+        .map_err(|e| format!("Policy parsing failed: {}", e))?;
+
+    let mut applied = Vec::new();
+    let simplified = simplify_node(&parsed, &mut applied);
+    console_log!("Simplified policy with {} changes: {}", applied.len(), simplified);
+    Ok((simplified.to_string(), applied))
+}
+
+fn simplify_node(node: &Concrete<String>, applied: &mut Vec<String>) -> Concrete<String> {
+    match node {
+        Concrete::And(subs) => {
+            let mut flat: Vec<Arc<Concrete<String>>> = Vec::new();
+            for sub in subs {
+                let sub = simplify_node(sub, applied);
+                match all_of(&sub) {
+                    Some(inner) => {
+                        applied.push(format!("Flattened nested and: {}", sub));
+                        flat.extend(inner);
+                    }
+                    None => flat.push(Arc::new(sub)),
+                }
+            }
+            dedup_branches(&mut flat, "and", applied);
+            match flat.len() {
+                1 => flat[0].as_ref().clone(),
+                2 => Concrete::And(flat),
+                _ => Concrete::Thresh(Threshold::and_n(flat)),
+            }
+        }
+        Concrete::Or(branches) => {
+            let mut flat: Vec<(usize, Arc<Concrete<String>>)> = Vec::new();
+            let unweighted = branches.iter().all(|(weight, _)| *weight == 1);
+            for (weight, sub) in branches {
+                let sub = simplify_node(sub, applied);
+                match any_of(&sub).filter(|_| unweighted) {
+                    Some(inner) => {
+                        applied.push(format!("Flattened nested or: {}", sub));
+                        flat.extend(inner.into_iter().map(|branch| (1, branch)));
+                    }
+                    None => flat.push((*weight, Arc::new(sub))),
+                }
+            }
+
+            let mut merged: Vec<(usize, Arc<Concrete<String>>)> = Vec::new();
+            for (weight, branch) in flat {
+                match merged.iter_mut().find(|(_, existing)| existing == &branch) {
+                    Some((existing_weight, _)) => {
+                        applied.push(format!("Removed duplicate or branch: {}", branch));
+                        *existing_weight += weight;
+                    }
+                    None => merged.push((weight, branch)),
+                }
+            }
+
+            match merged.len() {
+                1 => merged[0].1.as_ref().clone(),
+                2 => Concrete::Or(merged),
+                _ if merged.iter().all(|(weight, _)| *weight == 1) => {
+                    Concrete::Thresh(Threshold::or_n(merged.into_iter().map(|(_, branch)| branch).collect()))
+                }
+                _ => nest_weighted_or(merged),
+            }
+        }
+        Concrete::Thresh(thresh) => {
+            let subs = thresh.iter().map(|sub| Arc::new(simplify_node(sub, applied))).collect();
+            Concrete::Thresh(Threshold::new(thresh.k(), subs).expect("same k and n as a valid threshold"))
+        }
+        other => other.clone(),
+    }
+}
+
+// Two-argument `or` chain that keeps each branch's share of the total weight
+fn nest_weighted_or(mut branches: Vec<(usize, Arc<Concrete<String>>)>) -> Concrete<String> {
+    let (last_weight, last) = branches.pop().expect("at least two branches");
+    let (_, nested) = branches.into_iter().rev().fold((last_weight, last), |(rest_weight, rest), (weight, branch)| {
+        (weight + rest_weight, Arc::new(Concrete::Or(vec![(weight, branch), (rest_weight, rest)])))
+    });
+    nested.as_ref().clone()
+}
+
+// Branches of an all-of node: a two-argument `and` or a `thresh(n,...)` with k == n
+fn all_of(node: &Concrete<String>) -> Option<Vec<Arc<Concrete<String>>>> {
+    match node {
+        Concrete::And(subs) => Some(subs.clone()),
+        Concrete::Thresh(thresh) if thresh.k() == thresh.n() && thresh.n() > 1 => Some(thresh.data().to_vec()),
+        _ => None,
+    }
+}
+
+// Branches of an unweighted any-of node: `or` with unit weights or `thresh(1,...)`
+fn any_of(node: &Concrete<String>) -> Option<Vec<Arc<Concrete<String>>>> {
+    match node {
+        Concrete::Or(branches) if branches.iter().all(|(weight, _)| *weight == 1) => {
+            Some(branches.iter().map(|(_, branch)| branch.clone()).collect())
+        }
+        Concrete::Thresh(thresh) if thresh.k() == 1 && thresh.n() > 1 => Some(thresh.data().to_vec()),
+        _ => None,
+    }
+}
+
+// Keep the first of each group of identical branches
+fn dedup_branches(branches: &mut Vec<Arc<Concrete<String>>>, operator: &str, applied: &mut Vec<String>) {
+    let mut kept: Vec<Arc<Concrete<String>>> = Vec::new();
+    for branch in branches.drain(..) {
+        if kept.contains(&branch) {
+            applied.push(format!("Removed duplicate {} branch: {}", operator, branch));
+        } else {
+            kept.push(branch);
+        }
+    }
+    *branches = kept;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pk(key: &str) -> Arc<Concrete<String>> {
+        Arc::new(Concrete::Key(key.to_string()))
+    }
+
+    #[test]
+    fn test_weighted_or_keeps_weights_after_merging() {
+        // or(9@pk(A),1@pk(B),1@pk(C),1@pk(A))
+        let node = Concrete::Or(vec![(9, pk("A")), (1, pk("B")), (1, pk("C")), (1, pk("A"))]);
+        let mut applied = Vec::new();
+        let simplified = simplify_node(&node, &mut applied);
+        assert_eq!(simplified.to_string(), "or(10@pk(A),2@or(1@pk(B),1@pk(C)))");
+        assert_eq!(applied, vec!["Removed duplicate or branch: pk(A)"]);
+        assert!(simplified.to_string().parse::<Concrete<String>>().is_ok());
+
+        // Merged duplicates in a flattened unweighted or keep their combined weight
+        let (simplified, _) = simplify_policy("or(pk(A),or(pk(B),or(pk(C),pk(A))))").unwrap();
+        assert_eq!(simplified, "or(2@pk(A),2@or(1@pk(B),1@pk(C)))");
+    }
+}
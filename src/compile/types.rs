@@ -54,3 +54,73 @@ pub struct CompileResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub debug_info_leaves: Option<Vec<crate::types::LeafDebugInfo>>,
 }
+
+/// Outcome of compiling a policy in one candidate context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextCandidate {
+    pub context: String,
+    pub max_weight_to_satisfy: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Context picked by automatic context selection, with the reasoning behind it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoContextChoice {
+    pub context: String,
+    pub reasoning: Vec<String>,
+    pub script: String,
+    pub address: Option<String>,
+    pub compiled_miniscript: String,
+    pub max_weight_to_satisfy: Option<u64>,
+    pub candidates: Vec<ContextCandidate>,
+}
+
+/// Result wrapper for the WASM auto-context endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoContextResult {
+    pub success: bool,
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub choice: Option<AutoContextChoice>,
+}
+
+/// Result wrapper for the WASM policy simplification endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimplifyPolicyResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub policy: Option<String>,
+    /// One entry per flattening or deduplication applied
+    pub simplifications: Option<Vec<String>>,
+}
+
+/// Miniscript the policy compiler chose, with its size and cost figures
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimalMiniscript {
+    pub miniscript: String,
+    pub script_size: usize,
+    /// Non-push opcodes in the script
+    pub ops: usize,
+    /// Worst-case satisfaction weight of the wrapped output (sh, wsh, or tr under NUMS)
+    pub max_sat_weight: Option<u64>,
+    /// Alternative forms the compiler passed over
+    pub chosen_over: Vec<String>,
+}
+
+/// Result wrapper for the WASM optimal miniscript endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimalMiniscriptResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub result: Option<OptimalMiniscript>,
+}
+
+/// Result wrapper for the WASM malleability explanation endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MalleabilityResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub non_malleable: Option<bool>,
+    /// One entry per fragment that introduces malleability, with the reason
+    pub sources: Option<Vec<String>>,
+}
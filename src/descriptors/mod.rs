@@ -5,6 +5,7 @@ pub mod parser;
 pub mod processor;
 pub mod utils;
 pub mod compiler;
+pub mod template;
 
 #[cfg(test)]
 mod tests;
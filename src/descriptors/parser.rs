@@ -4,6 +4,14 @@ use regex::Regex;
 use std::collections::HashMap;
 use crate::descriptors::types::{DescriptorPatterns, ParsedDescriptor};
 use crate::console_log;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    // Built once; every parse_descriptors call shares the same compiled patterns
+    static ref DESCRIPTOR_PATTERNS: Result<DescriptorPatterns, String> = create_descriptor_regex_patterns();
+    // Extended public key whose final wildcard step is hardened (`*h` / `*'`)
+    static ref HARDENED_WILDCARD: Regex = Regex::new(r"[xyzt]pub[A-Za-z0-9]+(?:/[0-9<>;hH']+)*/\*[hH']").unwrap();
+}
 
 /// Create regex patterns for descriptor parsing
 pub fn create_descriptor_regex_patterns() -> Result<DescriptorPatterns, String> {
@@ -42,17 +50,37 @@ pub fn create_descriptor_regex_patterns() -> Result<DescriptorPatterns, String>
     })
 }
 
+/// Shared descriptor regex patterns, compiled on first use
+pub fn descriptor_regex_patterns() -> Result<&'static DescriptorPatterns, String> {
+    DESCRIPTOR_PATTERNS.as_ref().map_err(|e| e.clone())
+}
+
+/// Error for a `*h`/`*'` wildcard on an extended public key
+pub const HARDENED_WILDCARD_ERROR: &str = "Hardened wildcard (*h) cannot be derived from a public key — use an unhardened wildcard or provide an xprv";
+
+/// Reject extended public keys whose wildcard step is hardened
+///
+/// The wildcard patterns below would otherwise match `xpub/0/*` and silently
+/// drop the trailing hardened marker.
+pub(crate) fn reject_hardened_wildcard(expression: &str) -> Result<(), String> {
+    if HARDENED_WILDCARD.is_match(expression) {
+        return Err(HARDENED_WILDCARD_ERROR.to_string());
+    }
+    Ok(())
+}
+
 /// Parse descriptors from an expression
 pub fn parse_descriptors(expression: &str) -> Result<HashMap<String, ParsedDescriptor>, String> {
     let mut descriptors = HashMap::new();
     
     console_log!("Parsing descriptors from expression of length: {}", expression.len());
+    reject_hardened_wildcard(expression)?;
     
     // Create regex patterns for different descriptor formats
-    let patterns = create_descriptor_regex_patterns()?;
+    let patterns = descriptor_regex_patterns()?;
     
     // Process each pattern type
-    crate::descriptors::processor::process_comprehensive_descriptors(expression, &patterns, &mut descriptors)?;
+    crate::descriptors::processor::process_comprehensive_descriptors(expression, patterns, &mut descriptors)?;
     
     console_log!("Found {} descriptors total", descriptors.len());
     Ok(descriptors)
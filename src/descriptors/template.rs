@@ -0,0 +1,60 @@
+//! Descriptor templates: a policy over placeholder names plus a per-signer key map
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use miniscript::{Descriptor, DescriptorPublicKey, Legacy, Segwitv0};
+use miniscript::policy::Concrete;
+use crate::console_log;
+use crate::descriptors::types::TemplateKey;
+use crate::translators::NamedKeyTranslator;
+
+/// Compile a policy template whose keys are placeholder names into a ranged descriptor
+///
+/// Every name becomes `[origin]xpub/<0;1>/*`, so the result covers both the
+/// receive and change chains. The returned descriptor carries its checksum.
+pub fn compile_descriptor_template(template: &str, keys: &[TemplateKey], context: &str) -> Result<String, String> {
+    console_log!("Compiling descriptor template with {} keys: {}", keys.len(), template);
+
+    let policy = Concrete::<String>::from_str(template.trim())
+        .map_err(|e| format!("Template parsing failed: {}", e))?;
+
+    let mut key_map = HashMap::new();
+    for key in keys {
+        if key_map.contains_key(&key.name) {
+            return Err(format!("Duplicate key map entry for '{}'", key.name));
+        }
+        key_map.insert(key.name.clone(), template_descriptor_key(key)?);
+    }
+
+    let policy = policy.translate_pk(&mut NamedKeyTranslator::new(&key_map))
+        .map_err(|e| format!("Template key substitution failed: {:?}", e))?;
+
+    let descriptor: Descriptor<DescriptorPublicKey> = match context.to_lowercase().as_str() {
+        "legacy" => {
+            let ms = policy.compile::<Legacy>().map_err(|e| format!("Policy compilation failed: {}", e))?;
+            Descriptor::new_sh(ms).map_err(|e| format!("Descriptor construction failed: {}", e))?
+        }
+        "segwit" => {
+            let ms = policy.compile::<Segwitv0>().map_err(|e| format!("Policy compilation failed: {}", e))?;
+            Descriptor::new_wsh(ms).map_err(|e| format!("Descriptor construction failed: {}", e))?
+        }
+        "taproot" => policy.compile_tr(None).map_err(|e| format!("Policy compilation failed: {}", e))?,
+        _ => return Err(format!("Invalid context: {}. Use 'legacy', 'segwit', or 'taproot'", context)),
+    };
+
+    Ok(descriptor.to_string())
+}
+
+// `[origin]xpub/<0;1>/*` for one key map entry
+fn template_descriptor_key(key: &TemplateKey) -> Result<DescriptorPublicKey, String> {
+    let origin = key.origin.as_deref()
+        .map(|origin| origin.trim().trim_start_matches('[').trim_end_matches(']'))
+        .filter(|origin| !origin.is_empty());
+    let key_str = match origin {
+        Some(origin) => format!("[{}]{}/<0;1>/*", origin, key.xpub.trim()),
+        None => format!("{}/<0;1>/*", key.xpub.trim()),
+    };
+
+    DescriptorPublicKey::from_str(&key_str)
+        .map_err(|e| format!("Invalid key for '{}': {}", key.name, e))
+}
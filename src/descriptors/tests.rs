@@ -363,4 +363,222 @@ mod tests {
         let keys: Vec<&str> = key_regex.find_iter(&result).map(|m| m.as_str()).collect();
         assert!(!keys.is_empty(), "Should contain derived public keys");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_shared_secp_context_derivation_matches_per_call_context() {
+        use bitcoin::bip32::ChildNumber;
+        use bitcoin::secp256k1::Secp256k1;
+        use bitcoin::{Address, CompressedPublicKey, Network};
+        use std::str::FromStr;
+
+        let descriptors = parse_descriptors(COMPLEX_DESCRIPTOR_WILDCARD).unwrap();
+        let parsed_descriptor = descriptors.iter()
+            .find(|(_, parsed_desc)| parsed_desc.info.is_wildcard)
+            .map(|(_, parsed_desc)| parsed_desc)
+            .expect("Should find wildcard descriptor");
+
+        let start = std::time::Instant::now();
+        for index in 0..100u32 {
+            // Shared context path
+            let expanded = expand_descriptor(parsed_descriptor, index).unwrap();
+
+            // Old approach: fresh context for every derivation
+            let secp = Secp256k1::verification_only();
+            let path = [
+                ChildNumber::from_normal_idx(parsed_descriptor.info.child_paths[0]).unwrap(),
+                ChildNumber::from_normal_idx(index).unwrap(),
+            ];
+            let expected = parsed_descriptor.info.xpub.derive_pub(&secp, &path).unwrap().public_key;
+
+            assert_eq!(expanded, expected.to_string(), "Derived key mismatch at index {}", index);
+
+            let address = Address::p2wpkh(&CompressedPublicKey::from_str(&expanded).unwrap(), Network::Bitcoin);
+            let expected_address = Address::p2wpkh(&CompressedPublicKey(expected), Network::Bitcoin);
+            assert_eq!(address, expected_address, "Derived address mismatch at index {}", index);
+        }
+        println!("Derived 100 addresses in {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn test_preview_descriptor_keys_matches_sequential_derivation() {
+        use crate::descriptors::utils::preview_descriptor_keys;
+        use bitcoin::bip32::{ChildNumber, Xpub};
+        use bitcoin::secp256k1::Secp256k1;
+        use std::str::FromStr;
+
+        let tpub = "tpubDDEe6Dc3LW1JEUzExDRZ3XBzcAzYxMTfVU5KojsTwXoJ4st6LzqgbFZ1HhDBdTptjXH9MwgdYG4K7MNJBfQktc6AoS8WeAWFDHwDTu99bZa";
+        let expression = format!("pk([C8FE8D4F/48h/1h/123h/2h]{}/0/*)", tpub);
+
+        let previews = preview_descriptor_keys(&expression, 3).unwrap();
+        assert_eq!(previews.len(), 1, "Overlapping partial matches should be collapsed");
+
+        let xpub = Xpub::from_str(tpub).unwrap();
+        let secp = Secp256k1::verification_only();
+        let expected: Vec<String> = (0..3)
+            .map(|i| {
+                let path = [ChildNumber::from_normal_idx(0).unwrap(), ChildNumber::from_normal_idx(i).unwrap()];
+                xpub.derive_pub(&secp, &path).unwrap().public_key.to_string()
+            })
+            .collect();
+
+        assert_eq!(previews[0].fingerprint, xpub.fingerprint().to_string());
+        assert_eq!(previews[0].keys, expected);
+
+        let capped = preview_descriptor_keys(&expression, 1000).unwrap();
+        assert_eq!(capped[0].keys.len(), crate::descriptors::utils::MAX_PREVIEW_KEYS as usize);
+    }
+
+    #[test]
+    fn test_parse_derivation_path_hardened_notations() {
+        use crate::descriptors::utils::parse_derivation_path;
+
+        let tpub = "tpubDDEe6Dc3LW1JEUzExDRZ3XBzcAzYxMTfVU5KojsTwXoJ4st6LzqgbFZ1HhDBdTptjXH9MwgdYG4K7MNJBfQktc6AoS8WeAWFDHwDTu99bZa";
+        let apostrophe = format!("pk([C8FE8D4F/84'/0'/0']{}/0/*)", tpub);
+        let h_marker = format!("pk([C8FE8D4F/84h/0h/0h]{}/0/*)", tpub);
+
+        let find_full = |expression: &str| {
+            parse_descriptors(expression).unwrap()
+                .into_iter()
+                .find(|(descriptor_str, _)| descriptor_str.starts_with('['))
+                .expect("Should find the descriptor with key origin")
+        };
+        let (apostrophe_str, apostrophe_desc) = find_full(&apostrophe);
+        let (h_str, h_desc) = find_full(&h_marker);
+
+        assert_eq!(apostrophe_desc.info.derivation_path, h_desc.info.derivation_path);
+        assert_eq!(apostrophe_desc.info.derivation_path.to_string(), "84'/0'/0'");
+        // The preserved descriptor keeps the notation it was written in
+        assert!(apostrophe_str.contains("84'/0'/0'"));
+        assert!(h_str.contains("84h/0h/0h"));
+
+        let invalid = parse_derivation_path("84x/0h").unwrap_err();
+        assert!(invalid.contains("84x"), "{}", invalid);
+        let mixed = parse_derivation_path("84'/0h/0h").unwrap_err();
+        assert!(mixed.contains("mixes"), "{}", mixed);
+    }
+
+    #[test]
+    fn test_hardened_wildcard_is_rejected() {
+        use crate::descriptors::parser::HARDENED_WILDCARD_ERROR;
+
+        for wildcard in ["*h", "*'"] {
+            let expression = COMPLEX_DESCRIPTOR_WILDCARD.replace("/0/*)", &format!("/0/{})", wildcard));
+            let err = parse_descriptors(&expression).unwrap_err();
+            assert_eq!(err, "Hardened wildcard (*h) cannot be derived from a public key — use an unhardened wildcard or provide an xprv");
+            assert_eq!(err, HARDENED_WILDCARD_ERROR);
+        }
+
+        // Hardened steps in the key origin are fine
+        assert!(parse_descriptors(COMPLEX_DESCRIPTOR_WILDCARD).is_ok());
+    }
+
+    #[test]
+    fn test_descriptor_patterns_are_compiled_once() {
+        use crate::descriptors::parser::{create_descriptor_regex_patterns, descriptor_regex_patterns};
+
+        // Every call hands out the same compiled patterns
+        let shared = descriptor_regex_patterns().unwrap();
+        assert!(std::ptr::eq(shared, descriptor_regex_patterns().unwrap()));
+
+        // ...which match exactly like freshly built ones
+        let fresh = create_descriptor_regex_patterns().unwrap();
+        for expression in [COMPLEX_DESCRIPTOR_FIXED, COMPLEX_DESCRIPTOR_WILDCARD] {
+            assert_eq!(shared.full_fixed_double.is_match(expression), fresh.full_fixed_double.is_match(expression));
+            assert_eq!(shared.full_fixed_wildcard.is_match(expression), fresh.full_fixed_wildcard.is_match(expression));
+            assert_eq!(shared.bare_fixed_single.is_match(expression), fresh.bare_fixed_single.is_match(expression));
+        }
+
+        // Repeated parses and key extraction give identical results
+        let first = parse_descriptors(COMPLEX_DESCRIPTOR_WILDCARD).unwrap();
+        let second = parse_descriptors(COMPLEX_DESCRIPTOR_WILDCARD).unwrap();
+        assert_eq!(first.len(), second.len());
+        for (key, parsed) in &first {
+            assert_eq!(parsed.info.child_paths, second[key].info.child_paths);
+        }
+        let expression = "and_v(v:pk(f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9),pk(key2))";
+        for _ in 0..2 {
+            assert_eq!(extract_internal_key_from_expression(expression), "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9");
+            assert!(extract_xonly_key_from_miniscript(expression).is_some());
+        }
+    }
+
+    #[test]
+    fn test_compile_descriptor_template_multisig() {
+        use crate::descriptors::template::compile_descriptor_template;
+        use crate::descriptors::types::TemplateKey;
+        use bitcoin::bip32::{Xpriv, Xpub};
+        use miniscript::{Descriptor, DescriptorPublicKey};
+        use std::str::FromStr;
+
+        let alice_xpub = "xpub6Ctf53JHVC5K4JHwatPdJyXjzADFQt7pazJdQ4rc7j1chsQW6KcJUHFDbBn6e5mvGDEnFhFBCkX383uvzq14Y9Ado5qn5Y7qBiXi5DtVBda";
+        let secp = bitcoin::secp256k1::Secp256k1::new();
+        let bob_xpub = Xpub::from_priv(&secp, &Xpriv::new_master(bitcoin::Network::Bitcoin, &[7u8; 32]).unwrap()).to_string();
+        let keys = vec![
+            TemplateKey { name: "Alice".to_string(), xpub: alice_xpub.to_string(), origin: Some("C8FE8D4F/48h/1h/123h/2h".to_string()) },
+            TemplateKey { name: "Bob".to_string(), xpub: bob_xpub.clone(), origin: Some("[d34db33f/48'/0'/0'/2']".to_string()) },
+        ];
+
+        let descriptor = compile_descriptor_template("thresh(2,pk(Alice),pk(Bob))", &keys, "segwit").unwrap();
+        // The compiler picks and_v(v:pk,pk) as the cheapest 2-of-2
+        assert!(descriptor.starts_with("wsh(and_v(v:pk("), "{}", descriptor);
+        assert!(descriptor.contains(&format!("[c8fe8d4f/48'/1'/123'/2']{}/<0;1>/*", alice_xpub)), "{}", descriptor);
+        assert!(descriptor.contains(&format!("[d34db33f/48'/0'/0'/2']{}/<0;1>/*", bob_xpub)), "{}", descriptor);
+
+        // The checksum is present and valid: parsing rejects a wrong one
+        let (body, checksum) = descriptor.split_once('#').unwrap();
+        assert_eq!(checksum.len(), 8);
+        let parsed = Descriptor::<DescriptorPublicKey>::from_str(&descriptor).unwrap();
+        assert!(parsed.is_multipath());
+        assert!(Descriptor::<DescriptorPublicKey>::from_str(&format!("{}#qqqqqqqq", body)).is_err());
+
+        // Every placeholder needs an entry
+        let err = compile_descriptor_template("thresh(2,pk(Alice),pk(Carol))", &keys, "segwit").unwrap_err();
+        assert!(err.contains("No key provided for 'Carol'"), "{}", err);
+    }
+
+    #[test]
+    fn test_descriptor_id_ignores_formatting() {
+        use crate::descriptors::utils::descriptor_id;
+        use miniscript::descriptor::checksum::desc_checksum;
+
+        let tpub = "tpubDDEe6Dc3LW1JEUzExDRZ3XBzcAzYxMTfVU5KojsTwXoJ4st6LzqgbFZ1HhDBdTptjXH9MwgdYG4K7MNJBfQktc6AoS8WeAWFDHwDTu99bZa";
+        let descriptor = format!("wsh(pk([c8fe8d4f/48'/1'/123'/2']{}/0/*))", tpub);
+        let id = descriptor_id(&descriptor).unwrap();
+        assert_eq!(id.len(), 16);
+
+        // h markers, upper-case fingerprint, line breaks and a checksum do not change the id
+        let reformatted = format!("wsh(\n  pk([C8FE8D4F/48h/1h/123h/2h]{}/0/*)\n)", tpub);
+        assert_eq!(descriptor_id(&reformatted).unwrap(), id);
+        let checksummed = format!("{}#{}", descriptor, desc_checksum(&descriptor).unwrap());
+        assert_eq!(descriptor_id(&checksummed).unwrap(), id);
+
+        // A different derivation branch is a different descriptor
+        let change = format!("wsh(pk([c8fe8d4f/48'/1'/123'/2']{}/1/*))", tpub);
+        assert_ne!(descriptor_id(&change).unwrap(), id);
+        assert!(descriptor_id("wsh(pk(A))").is_err());
+    }
+
+    #[test]
+    fn test_expand_descriptor_rejects_hardened_range_index() {
+        use crate::descriptors::utils::MAX_UNHARDENED_INDEX;
+
+        let descriptors = parse_descriptors(COMPLEX_DESCRIPTOR_WILDCARD).unwrap();
+        let wildcard = descriptors.values().find(|parsed| parsed.info.is_wildcard).unwrap();
+
+        // 2^31 - 1 is the last unhardened index
+        assert!(expand_descriptor(wildcard, MAX_UNHARDENED_INDEX).is_ok());
+        assert_eq!(
+            expand_descriptor(wildcard, 1 << 31).unwrap_err(),
+            "Derivation index 2147483648 exceeds the maximum unhardened index (2147483647)"
+        );
+
+        // A fixed path component in the hardened range gets the same message
+        let fixed = COMPLEX_DESCRIPTOR_FIXED.replace("/0/0)", "/2147483648/0)");
+        let descriptors = parse_descriptors(&fixed).unwrap();
+        let parsed = descriptors.values().next().unwrap();
+        assert_eq!(
+            expand_descriptor(parsed, 0).unwrap_err(),
+            "Derivation index 2147483648 exceeds the maximum unhardened index (2147483647)"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Type definitions
 
 use regex::Regex;
+use serde::{Serialize, Deserialize};
 
 // Re-export descriptor types from the main types module
 pub use crate::types::{DescriptorInfo, ParsedDescriptor};
@@ -25,3 +26,21 @@ pub struct DescriptorPatterns {
     pub bare_fixed_single: Regex,        // xpub/0
     pub bare_fixed_double: Regex,        // xpub/0/0
 }
+
+/// One signer of a descriptor template: the placeholder name and its key with origin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateKey {
+    pub name: String,
+    pub xpub: String,
+    /// Key origin as `fingerprint/path`, with or without the surrounding brackets
+    #[serde(default)]
+    pub origin: Option<String>,
+}
+
+/// Result wrapper for the WASM descriptor template endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescriptorTemplateResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub descriptor: Option<String>,
+}
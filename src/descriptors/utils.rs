@@ -1,12 +1,15 @@
 //! Utility functions
 
 use bitcoin::bip32::{Xpub, DerivationPath, Fingerprint, ChildNumber};
-use bitcoin::secp256k1::Secp256k1;
+use crate::utils::secp;
 use bitcoin::XOnlyPublicKey;
 use std::str::FromStr;
 use std::collections::HashMap;
 use crate::descriptors::types::ParsedDescriptor;
+use crate::descriptors::parser::{parse_descriptors, reject_hardened_wildcard};
+use crate::types::DescriptorKeyPreview;
 use crate::console_log;
+use crate::parse::helpers::{canonical_descriptor, strip_whitespace};
 
 /// Parse fingerprint from hex string
 pub(crate) fn parse_fingerprint(hex_str: &str) -> Result<Fingerprint, String> {
@@ -23,9 +26,35 @@ pub(crate) fn parse_fingerprint(hex_str: &str) -> Result<Fingerprint, String> {
 }
 
 /// Parse derivation path from string
+/// Hardened steps may use either `h` or `'`, but a single path must not mix the two
 pub(crate) fn parse_derivation_path(path_str: &str) -> Result<DerivationPath, String> {
-    let normalized_path = path_str.replace("h", "'");
-    
+    let mut uses_h = false;
+    let mut uses_apostrophe = false;
+
+    for step in path_str.split('/').filter(|step| !step.is_empty() && *step != "m") {
+        let (index, marker) = match step.strip_suffix('h') {
+            Some(index) => (index, Some('h')),
+            None => match step.strip_suffix('\'') {
+                Some(index) => (index, Some('\'')),
+                None => (step, None),
+            },
+        };
+        if index.is_empty() || !index.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid derivation path: step '{}' must be a number optionally followed by h or '", step));
+        }
+        match marker {
+            Some('h') => uses_h = true,
+            Some(_) => uses_apostrophe = true,
+            None => {}
+        }
+    }
+
+    if uses_h && uses_apostrophe {
+        return Err(format!("Invalid derivation path: '{}' mixes h and ' hardened markers", path_str));
+    }
+
+    let normalized_path = path_str.replace('h', "'");
+
     DerivationPath::from_str(&normalized_path)
         .map_err(|e| format!("Invalid derivation path: {}", e))
 }
@@ -49,14 +78,26 @@ pub(crate) fn parse_child_paths(range_str: Option<&str>) -> Result<Vec<u32>, Str
     }
 }
 
+/// Largest unhardened BIP32 child index; indices from 2^31 up are hardened
+pub const MAX_UNHARDENED_INDEX: u32 = (1 << 31) - 1;
+
+/// Unhardened child number for a derivation index, rejecting the hardened range with a clear message
+pub(crate) fn normal_child(index: u32) -> Result<ChildNumber, String> {
+    if index > MAX_UNHARDENED_INDEX {
+        return Err(format!("Derivation index {} exceeds the maximum unhardened index ({})", index, MAX_UNHARDENED_INDEX));
+    }
+    ChildNumber::from_normal_idx(index).map_err(|e| format!("Invalid child number: {}", e))
+}
+
 /// Expand a descriptor at a specific child index
 pub fn expand_descriptor(descriptor: &ParsedDescriptor, child_index: u32) -> Result<String, String> {
-    let secp = Secp256k1::verification_only();
+    let secp = secp();
     
     console_log!("Expanding descriptor: {}", descriptor.original);
     console_log!("Xpub: {}", descriptor.info.xpub);
     console_log!("Child paths: {:?}", descriptor.info.child_paths);
     console_log!("Is wildcard: {}", descriptor.info.is_wildcard);
+    reject_hardened_wildcard(&descriptor.original)?;
     
     // Handle different derivation patterns comprehensively
     let final_xpub = if !descriptor.info.is_wildcard {
@@ -69,24 +110,21 @@ pub fn expand_descriptor(descriptor: &ParsedDescriptor, child_index: u32) -> Res
             },
             1 => {
                 // Single fixed derivation: xpub/0
-                let child = ChildNumber::from_normal_idx(descriptor.info.child_paths[0])
-                    .map_err(|e| format!("Invalid child number: {}", e))?;
+                let child = normal_child(descriptor.info.child_paths[0])?;
 
                 console_log!("Single derivation: {}", descriptor.info.child_paths[0]);
                 descriptor.info.xpub
-                    .derive_pub(&secp, &[child])
+                    .derive_pub(secp, &[child])
                     .map_err(|e| format!("Single key derivation failed: {}", e))?
             },
             2 => {
                 // Double fixed derivation: xpub/0/1
-                let first_child = ChildNumber::from_normal_idx(descriptor.info.child_paths[0])
-                    .map_err(|e| format!("Invalid first child number: {}", e))?;
-                let second_child = ChildNumber::from_normal_idx(descriptor.info.child_paths[1])
-                    .map_err(|e| format!("Invalid second child number: {}", e))?;
+                let first_child = normal_child(descriptor.info.child_paths[0])?;
+                let second_child = normal_child(descriptor.info.child_paths[1])?;
 
                 console_log!("Double derivation: {}/{}", descriptor.info.child_paths[0], descriptor.info.child_paths[1]);
                 descriptor.info.xpub
-                    .derive_pub(&secp, &[first_child, second_child])
+                    .derive_pub(secp, &[first_child, second_child])
                     .map_err(|e| format!("Double key derivation failed: {}", e))?
             },
             _ => return Err("Unsupported fixed derivation path length".to_string()),
@@ -96,49 +134,42 @@ pub fn expand_descriptor(descriptor: &ParsedDescriptor, child_index: u32) -> Res
         match descriptor.info.child_paths.len() {
             0 => {
                 // Single wildcard: xpub/* or xpub/*/*
-                let child = ChildNumber::from_normal_idx(child_index)
-                    .map_err(|e| format!("Invalid child index: {}", e))?;
+                let child = normal_child(child_index)?;
 
                 console_log!("Single wildcard derivation: {}", child_index);
                 descriptor.info.xpub
-                    .derive_pub(&secp, &[child])
+                    .derive_pub(secp, &[child])
                     .map_err(|e| format!("Single wildcard derivation failed: {}", e))?
             },
             1 => {
                 // Fixed wildcard: xpub/0/*
-                let first_child = ChildNumber::from_normal_idx(descriptor.info.child_paths[0])
-                    .map_err(|e| format!("Invalid first child number: {}", e))?;
-                let second_child = ChildNumber::from_normal_idx(child_index)
-                    .map_err(|e| format!("Invalid child index: {}", e))?;
+                let first_child = normal_child(descriptor.info.child_paths[0])?;
+                let second_child = normal_child(child_index)?;
 
                 console_log!("Fixed wildcard derivation: {}/{}", descriptor.info.child_paths[0], child_index);
                 descriptor.info.xpub
-                    .derive_pub(&secp, &[first_child, second_child])
+                    .derive_pub(secp, &[first_child, second_child])
                     .map_err(|e| format!("Fixed wildcard derivation failed: {}", e))?
             },
             2 => {
                 // Wildcard fixed: xpub/*/0 or double wildcard: xpub/*/*
                 if descriptor.info.child_paths[0] == u32::MAX {
                     // Wildcard fixed: xpub/*/0
-                    let first_child = ChildNumber::from_normal_idx(child_index)
-                        .map_err(|e| format!("Invalid child index: {}", e))?;
-                    let second_child = ChildNumber::from_normal_idx(descriptor.info.child_paths[1])
-                        .map_err(|e| format!("Invalid second child number: {}", e))?;
+                    let first_child = normal_child(child_index)?;
+                    let second_child = normal_child(descriptor.info.child_paths[1])?;
 
                     console_log!("Wildcard fixed derivation: {}/{}", child_index, descriptor.info.child_paths[1]);
                     descriptor.info.xpub
-                        .derive_pub(&secp, &[first_child, second_child])
+                        .derive_pub(secp, &[first_child, second_child])
                         .map_err(|e| format!("Wildcard fixed derivation failed: {}", e))?
                 } else {
                     // Double wildcard: xpub/*/*
-                    let first_child = ChildNumber::from_normal_idx(child_index)
-                        .map_err(|e| format!("Invalid child index: {}", e))?;
-                    let second_child = ChildNumber::from_normal_idx(child_index)
-                        .map_err(|e| format!("Invalid child index: {}", e))?;
+                    let first_child = normal_child(child_index)?;
+                    let second_child = normal_child(child_index)?;
 
                     console_log!("Double wildcard derivation: {}/{}", child_index, child_index);
                     descriptor.info.xpub
-                        .derive_pub(&secp, &[first_child, second_child])
+                        .derive_pub(secp, &[first_child, second_child])
                         .map_err(|e| format!("Double wildcard derivation failed: {}", e))?
                 }
             },
@@ -153,6 +184,50 @@ pub fn expand_descriptor(descriptor: &ParsedDescriptor, child_index: u32) -> Res
     Ok(hex_key)
 }
 
+/// Maximum number of child keys derived per descriptor by `preview_descriptor_keys`
+pub const MAX_PREVIEW_KEYS: u32 = 20;
+
+/// Preview the first `count` derived keys for every HD descriptor in an expression
+pub fn preview_descriptor_keys(expression: &str, count: u32) -> Result<Vec<DescriptorKeyPreview>, String> {
+    let descriptors = parse_descriptors(expression)?;
+    let count = count.clamp(1, MAX_PREVIEW_KEYS);
+
+    // Drop partial matches that are contained in a longer descriptor and keep expression order
+    let mut ordered: Vec<_> = descriptors.iter()
+        .filter(|(descriptor_str, _)| !descriptors.keys()
+            .any(|other| other.len() > descriptor_str.len() && other.contains(descriptor_str.as_str())))
+        .collect();
+    ordered.sort_by_key(|(descriptor_str, _)| expression.find(descriptor_str.as_str()).unwrap_or(usize::MAX));
+
+    ordered.into_iter()
+        .map(|(descriptor_str, parsed)| {
+            let keys = (0..count)
+                .map(|index| expand_descriptor(parsed, index))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(DescriptorKeyPreview {
+                descriptor: descriptor_str.clone(),
+                fingerprint: parsed.info.xpub.fingerprint().to_string(),
+                keys,
+            })
+        })
+        .collect()
+}
+
+/// Stable id for tracking a descriptor across sessions
+///
+/// The first 8 bytes (16 hex characters) of the sha256 of the canonical descriptor with
+/// its checksum, so whitespace, `h`/`'` hardened markers and an omitted checksum do not
+/// change the id.
+pub fn descriptor_id(descriptor: &str) -> Result<String, String> {
+    use bitcoin::hashes::{sha256, Hash};
+    use miniscript::descriptor::checksum::desc_checksum;
+
+    let canonical = canonical_descriptor(&strip_whitespace(descriptor)?)?;
+    let checksum = desc_checksum(&canonical).map_err(|e| format!("Invalid descriptor: {}", e))?;
+    let digest = sha256::Hash::hash(format!("{}#{}", canonical, checksum).as_bytes());
+    Ok(hex::encode(&digest.to_byte_array()[..8]))
+}
+
 /// Replace descriptors in expression with concrete keys
 pub fn replace_descriptors_with_keys(expression: &str, descriptors: &HashMap<String, ParsedDescriptor>) -> Result<String, String> {
     let mut result = expression.to_string();
@@ -180,7 +255,7 @@ pub fn replace_descriptors_with_keys(expression: &str, descriptors: &HashMap<Str
 
 /// Expand a descriptor at a specific child index returning x-only key for Taproot
 pub fn expand_descriptor_xonly(descriptor: &ParsedDescriptor, child_index: u32) -> Result<String, String> {
-    let secp = Secp256k1::verification_only();
+    let secp = secp();
 
     console_log!("Expanding descriptor for x-only key: {}", descriptor.original);
     console_log!("Xpub: {}", descriptor.info.xpub);
@@ -198,24 +273,21 @@ pub fn expand_descriptor_xonly(descriptor: &ParsedDescriptor, child_index: u32)
             },
             1 => {
                 // Single fixed derivation: xpub/0
-                let child = ChildNumber::from_normal_idx(descriptor.info.child_paths[0])
-                    .map_err(|e| format!("Invalid child number: {}", e))?;
+                let child = normal_child(descriptor.info.child_paths[0])?;
 
                 console_log!("Single derivation: {}", descriptor.info.child_paths[0]);
                 descriptor.info.xpub
-                    .derive_pub(&secp, &[child])
+                    .derive_pub(secp, &[child])
                     .map_err(|e| format!("Single key derivation failed: {}", e))?
             },
             2 => {
                 // Double fixed derivation: xpub/0/1
-                let first_child = ChildNumber::from_normal_idx(descriptor.info.child_paths[0])
-                    .map_err(|e| format!("Invalid first child number: {}", e))?;
-                let second_child = ChildNumber::from_normal_idx(descriptor.info.child_paths[1])
-                    .map_err(|e| format!("Invalid second child number: {}", e))?;
+                let first_child = normal_child(descriptor.info.child_paths[0])?;
+                let second_child = normal_child(descriptor.info.child_paths[1])?;
 
                 console_log!("Double derivation: {}/{}", descriptor.info.child_paths[0], descriptor.info.child_paths[1]);
                 descriptor.info.xpub
-                    .derive_pub(&secp, &[first_child, second_child])
+                    .derive_pub(secp, &[first_child, second_child])
                     .map_err(|e| format!("Double key derivation failed: {}", e))?
             },
             _ => return Err("Unsupported fixed derivation path length".to_string()),
@@ -225,49 +297,42 @@ pub fn expand_descriptor_xonly(descriptor: &ParsedDescriptor, child_index: u32)
         match descriptor.info.child_paths.len() {
             0 => {
                 // Single wildcard: xpub/* or xpub/*/*
-                let child = ChildNumber::from_normal_idx(child_index)
-                    .map_err(|e| format!("Invalid child index: {}", e))?;
+                let child = normal_child(child_index)?;
 
                 console_log!("Single wildcard derivation: {}", child_index);
                 descriptor.info.xpub
-                    .derive_pub(&secp, &[child])
+                    .derive_pub(secp, &[child])
                     .map_err(|e| format!("Single wildcard derivation failed: {}", e))?
             },
             1 => {
                 // Fixed wildcard: xpub/0/*
-                let first_child = ChildNumber::from_normal_idx(descriptor.info.child_paths[0])
-                    .map_err(|e| format!("Invalid first child number: {}", e))?;
-                let second_child = ChildNumber::from_normal_idx(child_index)
-                    .map_err(|e| format!("Invalid child index: {}", e))?;
+                let first_child = normal_child(descriptor.info.child_paths[0])?;
+                let second_child = normal_child(child_index)?;
 
                 console_log!("Fixed wildcard derivation: {}/{}", descriptor.info.child_paths[0], child_index);
                 descriptor.info.xpub
-                    .derive_pub(&secp, &[first_child, second_child])
+                    .derive_pub(secp, &[first_child, second_child])
                     .map_err(|e| format!("Fixed wildcard derivation failed: {}", e))?
             },
             2 => {
                 // Wildcard fixed: xpub/*/0 or double wildcard: xpub/*/*
                 if descriptor.info.child_paths[0] == u32::MAX {
                     // Wildcard fixed: xpub/*/0
-                    let first_child = ChildNumber::from_normal_idx(child_index)
-                        .map_err(|e| format!("Invalid child index: {}", e))?;
-                    let second_child = ChildNumber::from_normal_idx(descriptor.info.child_paths[1])
-                        .map_err(|e| format!("Invalid second child number: {}", e))?;
+                    let first_child = normal_child(child_index)?;
+                    let second_child = normal_child(descriptor.info.child_paths[1])?;
 
                     console_log!("Wildcard fixed derivation: {}/{}", child_index, descriptor.info.child_paths[1]);
                     descriptor.info.xpub
-                        .derive_pub(&secp, &[first_child, second_child])
+                        .derive_pub(secp, &[first_child, second_child])
                         .map_err(|e| format!("Wildcard fixed derivation failed: {}", e))?
                 } else {
                     // Double wildcard: xpub/*/*
-                    let first_child = ChildNumber::from_normal_idx(child_index)
-                        .map_err(|e| format!("Invalid child index: {}", e))?;
-                    let second_child = ChildNumber::from_normal_idx(child_index)
-                        .map_err(|e| format!("Invalid child index: {}", e))?;
+                    let first_child = normal_child(child_index)?;
+                    let second_child = normal_child(child_index)?;
 
                     console_log!("Double wildcard derivation: {}/{}", child_index, child_index);
                     descriptor.info.xpub
-                        .derive_pub(&secp, &[first_child, second_child])
+                        .derive_pub(secp, &[first_child, second_child])
                         .map_err(|e| format!("Double wildcard derivation failed: {}", e))?
                 }
             },
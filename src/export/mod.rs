@@ -4,6 +4,7 @@
 //! - Bitcoin Core `importdescriptors` JSON format
 //! - Generic descriptor format (Sparrow/Liana compatible)
 //! - Developer comprehensive JSON
+//! - Per-wallet export profiles (Bitcoin Core / Sparrow / Liana / generic)
 
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::JsValue;
@@ -11,7 +12,7 @@ use wasm_bindgen::JsValue;
 use crate::console_log;
 use crate::compile::options::{CompileOptions, InputType, CompileContext, CompileMode};
 use crate::compile::engine::compile_unified;
-use crate::address::{generate_address, AddressInput};
+use bitcoin::{Address, Network, Script, ScriptBuf};
 use crate::types::CompilationResult;
 
 /// Get current ISO 8601 timestamp from JavaScript
@@ -39,6 +40,13 @@ pub struct ExportOptions {
     /// HD descriptor range end (default: 100)
     #[serde(default)]
     pub range_end: Option<u32>,
+    /// Append the BIP 380 `#checksum` to exported descriptors (default: true)
+    #[serde(default = "default_include_checksum")]
+    pub include_checksum: bool,
+}
+
+fn default_include_checksum() -> bool {
+    true
 }
 
 impl Default for ExportOptions {
@@ -49,6 +57,7 @@ impl Default for ExportOptions {
             include_satisfaction_paths: true,
             range_start: Some(0),
             range_end: Some(100),
+            include_checksum: true,
         }
     }
 }
@@ -174,6 +183,17 @@ pub struct BitcoinCoreExport {
     pub importdescriptors: Vec<BitcoinCoreDescriptor>,
 }
 
+/// Export profile envelope: the selected profile and its wallet-specific payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProfileResult {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub profile: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+}
+
 /// Generate Bitcoin Core importdescriptors format
 pub fn export_for_bitcoin_core(descriptor: &str, options_js: JsValue) -> JsValue {
     console_log!("Exporting for Bitcoin Core: {}", descriptor);
@@ -188,7 +208,7 @@ pub fn export_for_bitcoin_core(descriptor: &str, options_js: JsValue) -> JsValue
     let has_wildcard = descriptor.contains("/*");
 
     let bitcoin_core_desc = BitcoinCoreDescriptor {
-        desc: add_checksum(descriptor),
+        desc: finish_descriptor(descriptor, options.include_checksum),
         timestamp: "now".to_string(),
         range: if has_wildcard { Some([range_start, range_end]) } else { None },
         watchonly: true,
@@ -214,38 +234,8 @@ pub fn export_comprehensive(
     let options: ExportOptions = serde_wasm_bindgen::from_value(options_js)
         .unwrap_or_default();
 
-    // Determine input type
-    let input_type_enum = if input_type == "policy" {
-        InputType::Policy
-    } else {
-        InputType::Miniscript
-    };
-
-    // Parse context
-    let compile_context = match context.to_lowercase().as_str() {
-        "legacy" => CompileContext::Legacy,
-        "segwit" => CompileContext::Segwit,
-        "taproot" | "taproot-multi" | "taproot-keypath" => CompileContext::Taproot,
-        _ => CompileContext::Segwit,
-    };
-
-    // Determine mode for taproot
-    let compile_mode = match context.to_lowercase().as_str() {
-        "taproot-multi" => CompileMode::MultiLeaf,
-        "taproot-keypath" => CompileMode::ScriptPath,
-        "taproot" => CompileMode::SingleLeaf,
-        _ => CompileMode::Default,
-    };
-
     // Create compile options
-    let compile_options = CompileOptions {
-        input_type: input_type_enum,
-        context: compile_context.clone(),
-        mode: compile_mode,
-        network_str: "testnet".to_string(),
-        nums_key: None,
-        verbose_debug: false,
-    };
+    let compile_options = export_compile_options(context, input_type);
 
     // Compile the expression
     let compilation_result = match compile_unified(expression, compile_options) {
@@ -299,7 +289,7 @@ pub fn export_comprehensive(
 
     // Build compilation info
     let compilation = CompilationExport {
-        descriptor: build_descriptor(&compilation_result, context),
+        descriptor: build_descriptor(&compilation_result, context, options.include_checksum),
         miniscript: compilation_result.compiled_miniscript.clone(),
         script_hex: compilation_result.script.clone().unwrap_or_default(),
         script_asm: compilation_result.script_asm.clone().unwrap_or_default(),
@@ -336,7 +326,7 @@ pub fn export_comprehensive(
     });
 
     // Build Bitcoin Core export
-    let bitcoin_core = build_bitcoin_core_export(&compilation_result, context);
+    let bitcoin_core = build_bitcoin_core_export(&compilation_result, context, options.include_checksum);
 
     let result = ExportResult {
         success: true,
@@ -419,8 +409,17 @@ fn add_checksum(desc: &str) -> String {
     }
 }
 
+/// Add the checksum, or strip one the compiler already attached when checksums are off
+fn finish_descriptor(desc: &str, include_checksum: bool) -> String {
+    if include_checksum {
+        add_checksum(desc)
+    } else {
+        desc.split('#').next().unwrap_or(desc).to_string()
+    }
+}
+
 /// Build descriptor string from compilation result
-fn build_descriptor(result: &CompilationResult, context: &str) -> Option<String> {
+fn build_descriptor(result: &CompilationResult, context: &str, include_checksum: bool) -> Option<String> {
     let miniscript = result.compiled_miniscript.as_ref()?;
 
     // Strip |LEAF_ASM: suffix if present (taproot single-leaf format)
@@ -444,7 +443,7 @@ fn build_descriptor(result: &CompilationResult, context: &str) -> Option<String>
         _ => return None,
     };
 
-    Some(add_checksum(&desc))
+    Some(finish_descriptor(&desc, include_checksum))
 }
 
 /// Generate addresses for all requested networks
@@ -453,15 +452,6 @@ fn generate_network_addresses(
     context: &str,
     include_all: bool
 ) -> NetworkAddresses {
-    let script_hex = result.script.clone().unwrap_or_default();
-
-    let script_type = match context.to_lowercase().as_str() {
-        "legacy" => "Legacy",
-        "segwit" => "Segwit v0",
-        "taproot" | "taproot-multi" | "taproot-keypath" => "Taproot",
-        _ => "Segwit v0",
-    };
-
     // For Taproot, we use the address from compilation result
     if context.contains("taproot") {
         let address = result.address.clone();
@@ -473,30 +463,38 @@ fn generate_network_addresses(
         };
     }
 
-    // For Legacy/Segwit, generate addresses for each network
-    let generate_for_network = |network: &str| -> Option<String> {
-        let input = AddressInput {
-            script_or_miniscript: script_hex.clone(),
-            script_type: script_type.to_string(),
-            network: network.to_string(),
-            internal_key: None,
-            use_single_leaf: None,
-        };
+    // For Legacy/Segwit the script is network-independent, so decode it once
+    // and only redo the final address encoding per network
+    let script = result.script.as_deref()
+        .and_then(|script_hex| hex::decode(script_hex).ok())
+        .map(ScriptBuf::from_bytes);
+    match script {
+        Some(script) => addresses_for_script(&script, context.eq_ignore_ascii_case("legacy"), include_all),
+        None => NetworkAddresses { mainnet: None, testnet: None, signet: None, regtest: None },
+    }
+}
 
-        generate_address(input).ok().map(|r| r.address)
+/// P2SH (legacy) or P2WSH (segwit) addresses of one script on each network
+fn addresses_for_script(script: &Script, legacy: bool, include_all: bool) -> NetworkAddresses {
+    let address_for = |network: Network| -> Option<String> {
+        if legacy {
+            Address::p2sh(script, network).ok().map(|address| address.to_string())
+        } else {
+            Some(Address::p2wsh(script, network).to_string())
+        }
     };
 
     NetworkAddresses {
-        mainnet: generate_for_network("mainnet"),
-        testnet: generate_for_network("testnet"),
-        signet: if include_all { generate_for_network("signet") } else { None },
-        regtest: if include_all { generate_for_network("regtest") } else { None },
+        mainnet: address_for(Network::Bitcoin),
+        testnet: address_for(Network::Testnet),
+        signet: if include_all { address_for(Network::Signet) } else { None },
+        regtest: if include_all { address_for(Network::Regtest) } else { None },
     }
 }
 
 /// Build Bitcoin Core importdescriptors export
-fn build_bitcoin_core_export(result: &CompilationResult, context: &str) -> BitcoinCoreExport {
-    let descriptor = build_descriptor(result, context)
+fn build_bitcoin_core_export(result: &CompilationResult, context: &str, include_checksum: bool) -> BitcoinCoreExport {
+    let descriptor = build_descriptor(result, context, include_checksum)
         .unwrap_or_else(|| "INVALID".to_string());
 
     // Check for HD wildcard
@@ -517,7 +515,7 @@ fn build_bitcoin_core_export(result: &CompilationResult, context: &str) -> Bitco
 }
 
 /// Generate a simple descriptor export (Sparrow/Liana compatible)
-pub fn export_descriptor(expression: &str, context: &str, input_type: &str) -> JsValue {
+pub fn export_descriptor(expression: &str, context: &str, input_type: &str, options_js: JsValue) -> JsValue {
     console_log!("=== EXPORT DESCRIPTOR ===");
     console_log!("Expression: '{}'", expression);
     console_log!("Context: '{}'", context);
@@ -532,32 +530,12 @@ pub fn export_descriptor(expression: &str, context: &str, input_type: &str) -> J
         })).unwrap_or_else(|_| JsValue::NULL);
     }
 
-    // Determine input type
-    let input_type_enum = if input_type == "policy" {
-        InputType::Policy
-    } else {
-        InputType::Miniscript
-    };
-
-    // Parse context
-    let compile_context = match context.to_lowercase().as_str() {
-        "legacy" => CompileContext::Legacy,
-        "segwit" => CompileContext::Segwit,
-        "taproot" | "taproot-multi" | "taproot-keypath" => CompileContext::Taproot,
-        _ => CompileContext::Segwit,
-    };
-
-    console_log!("Parsed context: {:?}", compile_context);
-
-    // Create compile options
+    // Create compile options, leaving the taproot mode to the compiler's default
     let compile_options = CompileOptions {
-        input_type: input_type_enum,
-        context: compile_context,
         mode: CompileMode::Default,
-        network_str: "testnet".to_string(),
-        nums_key: None,
-        verbose_debug: false,
+        ..export_compile_options(context, input_type)
     };
+    console_log!("Parsed context: {:?}", compile_options.context);
 
     // Compile
     console_log!("Calling compile_unified...");
@@ -584,7 +562,9 @@ pub fn export_descriptor(expression: &str, context: &str, input_type: &str) -> J
         })).unwrap_or_else(|_| JsValue::NULL);
     }
 
-    let descriptor = build_descriptor(&result, context)
+    let options: ExportOptions = serde_wasm_bindgen::from_value(options_js)
+        .unwrap_or_default();
+    let descriptor = build_descriptor(&result, context, options.include_checksum)
         .unwrap_or_else(|| "INVALID".to_string());
 
     console_log!("Generated descriptor: {}", descriptor);
@@ -595,6 +575,263 @@ pub fn export_descriptor(expression: &str, context: &str, input_type: &str) -> J
     })).unwrap_or_else(|_| JsValue::NULL)
 }
 
+/// Convert a policy key to Liana's multipath `/<0;1>/*` receive/change form
+fn to_liana_multipath_key(key: &str) -> Result<String, String> {
+    if !key.contains("xpub") && !key.contains("tpub") {
+        return Err(format!("Liana requires extended public keys (xpub/tpub) for every key; found '{}'", key));
+    }
+    if key.ends_with("/<0;1>/*") {
+        return Ok(key.to_string());
+    }
+    if let Some(base) = key.strip_suffix("/0/*") {
+        return Ok(format!("{}/<0;1>/*", base));
+    }
+    // Derivation steps come after the origin (if any)
+    let after_origin = key.rsplit(']').next().unwrap_or(key);
+    if after_origin.contains('/') {
+        return Err(format!(
+            "Key '{}' has a derivation that does not map to Liana's <0;1>/* form. Use a bare xpub, xpub/0/* or xpub/<0;1>/*",
+            key
+        ));
+    }
+    Ok(format!("{}/<0;1>/*", key))
+}
+
+/// Check that a policy node is a plain key set: pk(), and() or thresh() of keys
+fn is_liana_key_set(policy: &miniscript::policy::Concrete<String>) -> bool {
+    use miniscript::policy::Concrete;
+    match policy {
+        Concrete::Key(_) => true,
+        Concrete::And(subs) => subs.iter().all(|s| matches!(s.as_ref(), Concrete::Key(_))),
+        Concrete::Thresh(thresh) => thresh.iter().all(|s| matches!(s.as_ref(), Concrete::Key(_))),
+        _ => false,
+    }
+}
+
+/// Match or(primary, and(recovery, older(T))) and return the recovery timelock
+fn match_liana_shape(policy: &miniscript::policy::Concrete<String>) -> Result<u32, String> {
+    use miniscript::policy::Concrete;
+    const GUIDANCE: &str = "Liana descriptors need the shape or(primary_keys, and(recovery_keys, older(T))) where the key sets are pk(), and() or thresh() of keys";
+
+    let branches = match policy {
+        Concrete::Or(branches) if branches.len() == 2 => branches,
+        _ => return Err(format!("Policy is not a two-branch or(). {}", GUIDANCE)),
+    };
+
+    let find_recovery = |node: &Concrete<String>| -> Option<u32> {
+        if let Concrete::And(subs) = node {
+            if subs.len() == 2 {
+                for (a, b) in [(&subs[0], &subs[1]), (&subs[1], &subs[0])] {
+                    if let Concrete::Older(t) = b.as_ref() {
+                        if is_liana_key_set(a) {
+                            return Some(t.to_consensus_u32());
+                        }
+                    }
+                }
+            }
+        }
+        None
+    };
+
+    let (primary, timelock) = match (find_recovery(&branches[0].1), find_recovery(&branches[1].1)) {
+        (_, Some(t)) => (&branches[0].1, t),
+        (Some(t), None) => (&branches[1].1, t),
+        (None, None) => return Err(format!("No recovery branch with older() found. {}", GUIDANCE)),
+    };
+
+    if !is_liana_key_set(primary) {
+        return Err(format!("Primary branch must contain only keys. {}", GUIDANCE));
+    }
+    Ok(timelock)
+}
+
+/// Build a Liana-style multipath descriptor for a primary/recovery policy
+pub(crate) fn build_liana_descriptor(expression: &str, context: &str) -> Result<String, String> {
+    use std::collections::HashMap;
+    use miniscript::policy::Concrete;
+    use miniscript::descriptor::{Descriptor, DescriptorPublicKey};
+    use miniscript::{Segwitv0, Tap, Miniscript};
+    use miniscript::policy::Liftable;
+    use crate::translators::NamedKeyTranslator;
+
+    let trimmed = expression.trim();
+    let policy: Concrete<String> = match trimmed.parse() {
+        Ok(p) => p,
+        Err(policy_err) => {
+            // Accept miniscript input by lifting it back to a policy
+            let lifted = Miniscript::<String, Segwitv0>::from_str_insane(trimmed)
+                .map_err(|e| e.to_string())
+                .and_then(|ms| ms.lift().map_err(|e| e.to_string()))
+                .or_else(|_| Miniscript::<String, Tap>::from_str_insane(trimmed)
+                    .map_err(|e| e.to_string())
+                    .and_then(|ms| ms.lift().map_err(|e| e.to_string())))
+                .map_err(|_| format!("Failed to parse policy: {}", policy_err))?;
+            lifted.to_string().parse()
+                .map_err(|e| format!("Failed to convert miniscript to policy: {}", e))?
+        }
+    };
+
+    let timelock = match_liana_shape(&policy)?;
+    console_log!("Liana shape matched, recovery timelock: {}", timelock);
+
+    // Translate every key into its multipath form
+    let mut key_map = HashMap::new();
+    for key in policy.keys() {
+        let multipath: DescriptorPublicKey = to_liana_multipath_key(key)?.parse()
+            .map_err(|e| format!("Invalid multipath key '{}': {}", key, e))?;
+        key_map.insert(key.clone(), multipath);
+    }
+    let desc_policy: Concrete<DescriptorPublicKey> = policy.translate_pk(&mut NamedKeyTranslator::new(&key_map))
+        .map_err(|e| format!("Invalid multipath policy: {:?}", e))?;
+
+    let descriptor: Descriptor<DescriptorPublicKey> = match context.to_lowercase().as_str() {
+        "segwit" => {
+            let ms = desc_policy.compile::<Segwitv0>()
+                .map_err(|e| format!("Policy compilation failed: {}", e))?;
+            Descriptor::new_wsh(ms).map_err(|e| format!("Descriptor creation failed: {}", e))?
+        }
+        "taproot" | "taproot-multi" | "taproot-keypath" => {
+            desc_policy.compile_tr(Some(crate::taproot::utils::get_nums_descriptor_key()))
+                .map_err(|e| format!("Taproot compilation failed: {}", e))?
+        }
+        _ => return Err("Liana supports only segwit (wsh) and taproot (tr) descriptors".to_string()),
+    };
+
+    let desc_str = format!("{:#}", descriptor);
+    if !desc_str.contains(&format!("older({})", timelock)) {
+        return Err(format!("Recovery timelock older({}) was lost during compilation", timelock));
+    }
+
+    Ok(finish_descriptor(&desc_str, true))
+}
+
+/// Export a primary/recovery policy as a Liana-importable descriptor
+pub fn export_liana(expression: &str, context: &str) -> JsValue {
+    console_log!("Exporting Liana descriptor for: {} (context: {})", expression, context);
+
+    let value = match build_liana_descriptor(expression, context) {
+        Ok(descriptor) => serde_json::json!({
+            "success": true,
+            "descriptor": descriptor
+        }),
+        Err(e) => serde_json::json!({
+            "success": false,
+            "error": e
+        }),
+    };
+
+    serde_wasm_bindgen::to_value(&value).unwrap_or(JsValue::NULL)
+}
+
+/// Map an export context name ("taproot-multi", "taproot-keypath", ...) and input type onto compile options
+fn export_compile_options(context: &str, input_type: &str) -> CompileOptions {
+    let (compile_context, compile_mode) = match context.to_lowercase().as_str() {
+        "legacy" => (CompileContext::Legacy, CompileMode::Default),
+        "taproot" => (CompileContext::Taproot, CompileMode::SingleLeaf),
+        "taproot-multi" => (CompileContext::Taproot, CompileMode::MultiLeaf),
+        "taproot-keypath" => (CompileContext::Taproot, CompileMode::ScriptPath),
+        _ => (CompileContext::Segwit, CompileMode::Default),
+    };
+
+    CompileOptions {
+        input_type: if input_type == "policy" { InputType::Policy } else { InputType::Miniscript },
+        context: compile_context,
+        mode: compile_mode,
+        network_str: "testnet".to_string(),
+        nums_key: None,
+        verbose_debug: false,
+        compare_taproot_modes: false,
+        auto_convert_keys: false,
+        max_expression_length: None,
+        timing: false,
+    }
+}
+
+/// Compile an expression for export and fail on a compile error
+fn compile_for_export(expression: &str, context: &str, input_type: &str) -> Result<CompilationResult, String> {
+    let result = compile_unified(expression, export_compile_options(context, input_type))?;
+    if !result.success {
+        return Err(result.error.unwrap_or_else(|| "Unknown error".to_string()));
+    }
+    Ok(result)
+}
+
+/// Build the payload for one wallet export profile
+///
+/// - `bitcoin-core`: the `importdescriptors` request array
+/// - `sparrow`: the descriptor with its `#checksum`
+/// - `liana`: the `<0;1>/*` multipath primary/recovery descriptor
+/// - `generic`: descriptor, script and address
+pub(crate) fn build_export_profile(
+    expression: &str,
+    context: &str,
+    input_type: &str,
+    profile: &str,
+    options: &ExportOptions,
+) -> Result<serde_json::Value, String> {
+    if expression.trim().is_empty() {
+        return Err("Empty expression - please enter a miniscript or policy".to_string());
+    }
+
+    match profile.to_lowercase().as_str() {
+        "bitcoin-core" | "bitcoincore" | "core" => {
+            let result = compile_for_export(expression, context, input_type)?;
+            let mut export = build_bitcoin_core_export(&result, context, options.include_checksum);
+            for desc in &mut export.importdescriptors {
+                if desc.range.is_some() {
+                    desc.range = Some([options.range_start.unwrap_or(0), options.range_end.unwrap_or(100)]);
+                }
+            }
+            serde_json::to_value(export.importdescriptors).map_err(|e| e.to_string())
+        }
+        "sparrow" => {
+            let result = compile_for_export(expression, context, input_type)?;
+            let descriptor = build_descriptor(&result, context, true)
+                .ok_or_else(|| format!("Cannot build a descriptor for context '{}'", context))?;
+            Ok(serde_json::json!({ "descriptor": descriptor }))
+        }
+        "liana" => {
+            let descriptor = build_liana_descriptor(expression, context)?;
+            Ok(serde_json::json!({ "descriptor": descriptor }))
+        }
+        "generic" => {
+            let result = compile_for_export(expression, context, input_type)?;
+            Ok(serde_json::json!({
+                "descriptor": build_descriptor(&result, context, options.include_checksum),
+                "script_hex": result.script,
+                "script_asm": result.script_asm,
+                "address": result.address,
+            }))
+        }
+        _ => Err(format!("Unknown export profile: {}. Use bitcoin-core, sparrow, liana or generic", profile)),
+    }
+}
+
+/// Export an expression for the wallet selected by `profile`
+pub fn export_profile(expression: &str, context: &str, input_type: &str, profile: &str, options_js: JsValue) -> JsValue {
+    console_log!("Exporting profile '{}' for: {} (context: {}, type: {})", profile, expression, context, input_type);
+
+    let options: ExportOptions = serde_wasm_bindgen::from_value(options_js)
+        .unwrap_or_default();
+
+    let result = match build_export_profile(expression, context, input_type, profile, &options) {
+        Ok(payload) => ExportProfileResult {
+            success: true,
+            error: None,
+            profile: profile.to_string(),
+            payload: Some(payload),
+        },
+        Err(e) => ExportProfileResult {
+            success: false,
+            error: Some(e),
+            profile: profile.to_string(),
+            payload: None,
+        },
+    };
+
+    serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -607,6 +844,57 @@ mod tests {
         assert!(options.include_satisfaction_paths);
         assert_eq!(options.range_start, Some(0));
         assert_eq!(options.range_end, Some(100));
+        assert!(options.include_checksum);
+    }
+
+    #[test]
+    fn test_build_descriptor_checksum_option() {
+        let key = "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+        let result = compile_for_export(&format!("pk({})", key), "segwit", "miniscript").unwrap();
+
+        let bare = build_descriptor(&result, "segwit", false).unwrap();
+        assert_eq!(bare, format!("wsh(pk({}))", key));
+
+        let checksummed = build_descriptor(&result, "segwit", true).unwrap();
+        let (desc, checksum) = checksummed.split_once('#').unwrap();
+        assert_eq!(desc, bare);
+        assert_eq!(checksum, descriptor_checksum(&bare).unwrap());
+
+        // Compiled tr() descriptors arrive with a checksum, which is dropped on request
+        let xonly = "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+        let result = compile_for_export(&format!("pk({})", xonly), "taproot-multi", "miniscript").unwrap();
+        assert!(!build_descriptor(&result, "taproot-multi", false).unwrap().contains('#'));
+        assert!(build_descriptor(&result, "taproot-multi", true).unwrap().contains('#'));
+        let core = build_bitcoin_core_export(&result, "taproot-multi", false);
+        assert!(!core.importdescriptors[0].desc.contains('#'));
+    }
+
+    #[test]
+    fn test_network_addresses_from_one_script() {
+        use crate::address::{generate_address, AddressInput};
+
+        let key = "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+        let result = compile_for_export(&format!("pk({})", key), "legacy", "miniscript").unwrap();
+        let addresses = generate_network_addresses(&result, "legacy", true);
+
+        let reference = |network: &str| {
+            let input = AddressInput {
+                script_or_miniscript: result.script.clone().unwrap(),
+                script_type: "Legacy".to_string(),
+                network: network.to_string(),
+                internal_key: None,
+                use_single_leaf: None,
+            };
+            generate_address(input).unwrap().address
+        };
+        assert_eq!(addresses.mainnet.unwrap(), reference("mainnet"));
+        assert_eq!(addresses.testnet.unwrap(), reference("testnet"));
+        assert_eq!(addresses.signet.unwrap(), reference("signet"));
+        assert_eq!(addresses.regtest.unwrap(), reference("regtest"));
+
+        let mainnet = addresses_for_script(&ScriptBuf::from_hex(result.script.as_deref().unwrap()).unwrap(), true, false);
+        assert!(mainnet.mainnet.unwrap().starts_with('3'));
+        assert!(mainnet.signet.is_none());
     }
 
     #[test]
@@ -629,4 +917,68 @@ mod tests {
         assert!(with_checksum.contains('#'), "Should contain checksum separator #");
         assert!(with_checksum.len() > desc.len(), "Should be longer than input");
     }
+
+    #[test]
+    fn test_build_liana_descriptor() {
+        let primary = "[C8FE8D4F/48h/1h/123h/2h]tpubDC5H7ejMEWt2JF3AF2kdKKokQsAHcksHoHHMgw6S9x5sTs8mZ4rpNPpuNYSJr7RuwiqUgJpYnA6XftMUNW5hGkTduGCtFfBdyj2hXJKN2Xf";
+        let recovery = "tpubDC8Anx4AbMFdpAygLRf4NqUrmKZysVXSodQBbqmKhmaLgjFCR9xHYsgGytkKDTj8n8abDRsYQmv2voqnxdPekdLWHsyt99yqttghUyCYYE8/0/*";
+        let policy = format!("or(pk({}),and(pk({}),older(52560)))", primary, recovery);
+
+        for context in ["segwit", "taproot"] {
+            let descriptor = build_liana_descriptor(&policy, context).unwrap();
+            println!("Liana {} descriptor: {}", context, descriptor);
+
+            let (body, checksum) = descriptor.split_once('#').expect("Descriptor should carry a checksum");
+            assert_eq!(descriptor_checksum(body).unwrap(), checksum, "Checksum should be valid");
+            assert!(body.contains("older(52560)"), "Recovery timelock should be preserved");
+            assert_eq!(body.matches("/<0;1>/*").count(), 2, "Both keys should be multipath");
+            assert!(body.contains("[c8fe8d4f/48'/1'/123'/2']tpub"), "Key origin should be kept");
+
+            let parsed = descriptor.parse::<miniscript::Descriptor<miniscript::DescriptorPublicKey>>();
+            assert!(parsed.is_ok(), "Descriptor should parse back: {:?}", parsed.err());
+        }
+    }
+
+    #[test]
+    fn test_build_liana_descriptor_rejects_other_shapes() {
+        let key = "tpubDC5H7ejMEWt2JF3AF2kdKKokQsAHcksHoHHMgw6S9x5sTs8mZ4rpNPpuNYSJr7RuwiqUgJpYnA6XftMUNW5hGkTduGCtFfBdyj2hXJKN2Xf";
+        let err = build_liana_descriptor(&format!("and(pk({}),older(10))", key), "segwit").unwrap_err();
+        assert!(err.contains("or(primary_keys"), "Error should explain the expected shape: {}", err);
+
+        let err = build_liana_descriptor(
+            "or(pk(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9),and(pk(03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd),older(10)))",
+            "segwit",
+        ).unwrap_err();
+        assert!(err.contains("extended public keys"), "Raw keys should be rejected: {}", err);
+    }
+
+    #[test]
+    fn test_export_profiles_top_level_shape() {
+        let miniscript = "and_v(v:pk(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9),older(144))";
+        let options = ExportOptions::default();
+
+        let core = build_export_profile(miniscript, "segwit", "miniscript", "bitcoin-core", &options).unwrap();
+        let requests = core.as_array().expect("Bitcoin Core payload should be the importdescriptors array");
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0]["desc"].as_str().unwrap().starts_with("wsh("));
+        assert_eq!(requests[0]["timestamp"], "now");
+
+        let sparrow = build_export_profile(miniscript, "segwit", "miniscript", "sparrow", &options).unwrap();
+        let (body, checksum) = sparrow["descriptor"].as_str().unwrap().split_once('#').expect("Sparrow wants a checksum");
+        assert_eq!(descriptor_checksum(body).unwrap(), checksum);
+
+        let generic = build_export_profile(miniscript, "segwit", "miniscript", "generic", &options).unwrap();
+        for field in ["descriptor", "script_hex", "script_asm", "address"] {
+            assert!(generic[field].is_string(), "generic payload should carry {}: {}", field, generic);
+        }
+
+        let primary = "tpubDC5H7ejMEWt2JF3AF2kdKKokQsAHcksHoHHMgw6S9x5sTs8mZ4rpNPpuNYSJr7RuwiqUgJpYnA6XftMUNW5hGkTduGCtFfBdyj2hXJKN2Xf";
+        let recovery = "tpubDC8Anx4AbMFdpAygLRf4NqUrmKZysVXSodQBbqmKhmaLgjFCR9xHYsgGytkKDTj8n8abDRsYQmv2voqnxdPekdLWHsyt99yqttghUyCYYE8";
+        let policy = format!("or(pk({}),and(pk({}),older(52560)))", primary, recovery);
+        let liana = build_export_profile(&policy, "segwit", "policy", "liana", &options).unwrap();
+        assert!(liana["descriptor"].as_str().unwrap().contains("/<0;1>/*"));
+
+        let err = build_export_profile(miniscript, "segwit", "miniscript", "electrum", &options).unwrap_err();
+        assert!(err.contains("Unknown export profile"), "{}", err);
+    }
 }
@@ -8,14 +8,22 @@ use crate::console_log;
 use crate::taproot::utils::NUMS_POINT;
 use crate::descriptors::parser::parse_descriptors;
 use crate::descriptors::utils::expand_descriptor_xonly;
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::validation::XONLY_KEY;
+
+lazy_static! {
+    // Content of a pk() fragment
+    static ref PK_CONTENT: Regex = Regex::new(r"pk\(([^)]+)\)").unwrap();
+    // OP_PUSHBYTES_32 followed by the 32-byte key
+    static ref PUSH32_KEY: Regex = Regex::new(r"20([a-fA-F0-9]{64})").unwrap();
+}
 
 /// Extract x-only key from miniscript expression
 pub fn extract_xonly_key_from_miniscript(miniscript: &str) -> Option<XOnlyPublicKey> {
     // Use regex to find all 64-character hex strings (x-only keys)
-    let key_regex = regex::Regex::new(r"\b[a-fA-F0-9]{64}\b").ok()?;
-    
-    for cap in key_regex.captures_iter(miniscript) {
-        if let Some(key_match) = cap.get(0) {
+    for cap in XONLY_KEY.captures_iter(miniscript) {
+        if let Some(key_match) = cap.get(2) {
             let key_str = key_match.as_str();
             if let Ok(key_bytes) = hex::decode(key_str) {
                 if let Ok(xonly_key) = XOnlyPublicKey::from_slice(&key_bytes) {
@@ -35,46 +43,15 @@ pub fn extract_internal_key_from_expression(expression: &str) -> String {
     console_log!("DEBUG: Extracting internal key from expression: {}", expression);
 
     // Match first pk() pattern to extract internal key
-    let re = regex::Regex::new(r"pk\(([^)]+)\)").unwrap();
-    if let Some(captures) = re.captures(expression) {
-        if let Some(key_match) = captures.get(1) {
-            let extracted_content = key_match.as_str().to_string();
-            console_log!("DEBUG: Extracted content from pk(): {}", extracted_content);
-
-            // Check if the extracted content is a descriptor (contains [ or xpub/tpub)
-            if extracted_content.contains('[') || extracted_content.contains("xpub") || extracted_content.contains("tpub") {
-                console_log!("DEBUG: Content appears to be a descriptor, processing...");
-
-                // Try to parse and expand the descriptor to get the actual key
-                match parse_descriptors(&extracted_content) {
-                    Ok(descriptors) => {
-                        if let Some((_desc_str, desc_info)) = descriptors.iter().next() {
-                            console_log!("DEBUG: Successfully parsed descriptor");
-                            match expand_descriptor_xonly(desc_info, 0) {
-                                Ok(derived_key) => {
-                                    console_log!("DEBUG: Successfully derived x-only key from descriptor: {}", derived_key);
-                                    return derived_key;
-                                },
-                                Err(_e) => {
-                                    console_log!("DEBUG: Failed to expand descriptor: {}", _e);
-                                }
-                            }
-                        }
-                    },
-                    Err(_e) => {
-                        console_log!("DEBUG: Failed to parse as descriptor: {}", _e);
-                    }
-                }
+    if let Some(key_match) = PK_CONTENT.captures(expression).and_then(|captures| captures.get(1)) {
+        let extracted_content = key_match.as_str();
+        console_log!("DEBUG: Extracted content from pk(): {}", extracted_content);
 
-                // If descriptor processing failed, fall back to NUMS point
-                console_log!("DEBUG: Descriptor processing failed, using NUMS point");
-                return NUMS_POINT.to_string();
-            } else {
-                // Not a descriptor, return as-is
-                console_log!("DEBUG: Content is a regular key, returning as-is");
-                return extracted_content;
-            }
-        }
+        // If descriptor processing failed, fall back to NUMS point
+        return resolve_pk_content(extracted_content).unwrap_or_else(|| {
+            console_log!("DEBUG: Descriptor processing failed, using NUMS point");
+            NUMS_POINT.to_string()
+        });
     }
 
     // If no pk() found, use NUMS point
@@ -82,13 +59,103 @@ pub fn extract_internal_key_from_expression(expression: &str) -> String {
     NUMS_POINT.to_string()
 }
 
+/// List the x-only keys that could become the taproot internal key, in order of appearance
+/// and without duplicates
+///
+/// Only keys that alone satisfy the whole policy or one of its top-level `or` branches
+/// qualify: the internal key spends through the key path with no other condition, so a
+/// key that only signs alongside others (inside `and()` or a k>1 `thresh()`) is never offered.
+pub fn list_candidate_internal_keys(expression: &str) -> Vec<String> {
+    use miniscript::policy::{Concrete, Liftable};
+    use miniscript::{Miniscript, Tap};
+
+    let trimmed = expression.trim();
+    let semantic = match trimmed.parse::<Concrete<String>>() {
+        Ok(policy) => policy.lift().ok(),
+        Err(_) => Miniscript::<String, Tap>::from_str_insane(trimmed).ok().and_then(|ms| ms.lift().ok()),
+    };
+    let Some(semantic) = semantic else {
+        return Vec::new();
+    };
+
+    let mut candidates: Vec<String> = Vec::new();
+    for key in sole_signer_keys(&semantic) {
+        let Some(content) = resolve_pk_content(&key) else {
+            continue;
+        };
+        // Compressed keys are offered in their x-only form
+        let Some(xonly) = to_xonly_hex(&content) else {
+            console_log!("DEBUG: Skipping non x-only candidate: {}", content);
+            continue;
+        };
+        if !candidates.contains(&xonly) {
+            candidates.push(xonly);
+        }
+    }
+
+    candidates
+}
+
+// Keys that satisfy the policy on their own: the policy itself, or any branch of a top-level or
+fn sole_signer_keys(policy: &miniscript::policy::semantic::Policy<String>) -> Vec<String> {
+    use miniscript::policy::semantic::Policy;
+
+    match policy {
+        Policy::Key(key) => vec![key.clone()],
+        Policy::Thresh(thresh) if thresh.k() == 1 => {
+            thresh.iter().flat_map(|child| sole_signer_keys(child)).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Resolve the content of a `pk()` to a concrete key, expanding descriptors at index 0
+fn resolve_pk_content(extracted_content: &str) -> Option<String> {
+    // Check if the extracted content is a descriptor (contains [ or xpub/tpub)
+    if !(extracted_content.contains('[') || extracted_content.contains("xpub") || extracted_content.contains("tpub")) {
+        // Not a descriptor, return as-is
+        console_log!("DEBUG: Content is a regular key, returning as-is");
+        return Some(extracted_content.to_string());
+    }
+
+    console_log!("DEBUG: Content appears to be a descriptor, processing...");
+    match parse_descriptors(extracted_content) {
+        Ok(descriptors) => {
+            let (_desc_str, desc_info) = descriptors.iter().next()?;
+            match expand_descriptor_xonly(desc_info, 0) {
+                Ok(derived_key) => {
+                    console_log!("DEBUG: Successfully derived x-only key from descriptor: {}", derived_key);
+                    Some(derived_key)
+                },
+                Err(_e) => {
+                    console_log!("DEBUG: Failed to expand descriptor: {}", _e);
+                    None
+                }
+            }
+        },
+        Err(_e) => {
+            console_log!("DEBUG: Failed to parse as descriptor: {}", _e);
+            None
+        }
+    }
+}
+
+/// Normalize a hex key to its x-only form, dropping the parity byte of compressed keys
+fn to_xonly_hex(key: &str) -> Option<String> {
+    let bytes = hex::decode(key).ok()?;
+    let xonly = match bytes.len() {
+        32 => XOnlyPublicKey::from_slice(&bytes).ok()?,
+        33 => bitcoin::PublicKey::from_slice(&bytes).ok()?.inner.x_only_public_key().0,
+        _ => return None,
+    };
+    Some(xonly.to_string())
+}
+
 /// Extract x-only key from script hex (for Taproot address generation)
 pub fn extract_xonly_key_from_script_hex(script_hex: &str) -> Option<XOnlyPublicKey> {
     // Look for 32-byte key pushes in the script hex
     // Pattern: 20 (OP_PUSHBYTES_32) followed by 64 hex chars (32 bytes)
-    let key_regex = regex::Regex::new(r"20([a-fA-F0-9]{64})").ok()?;
-    
-    for cap in key_regex.captures_iter(script_hex) {
+    for cap in PUSH32_KEY.captures_iter(script_hex) {
         if let Some(key_match) = cap.get(1) {  // Group 1 is the key without the 20 prefix
             let key_str = key_match.as_str();
             if let Ok(key_bytes) = hex::decode(key_str) {
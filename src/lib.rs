@@ -12,9 +12,9 @@ mod translators;
 mod opcodes;
 mod utils;
 pub(crate) mod parse;
-mod lift;
+pub mod lift;
 pub mod address;
-mod taproot;
+pub mod taproot;
 pub mod analyze;
 pub mod export;
 
@@ -23,7 +23,7 @@ pub mod descriptors;
 pub mod keys;
 pub mod validation;
 
-use types::{CompilationResult, LiftResult, AddressResult};
+use types::{CompilationResult, AddressAssertionResult, LiftResult, AddressResult, KeyPathMatrixResult, ContractSummaryResult, TaprootStandardnessResult, PathCountResult, SatisfiablePathsResult, StructuredPathsResult, ScriptPubKeyResult, ComboOutputsResult, AddressScriptMatchResult, DescriptorEquivalenceResult, DescriptorPreviewResult, DescriptorIdResult, FragmentTypeCheckResult};
 use wasm_bindgen::prelude::*;
 
 
@@ -34,48 +34,101 @@ pub fn compile_unified(expression: &str, options_js: JsValue) -> JsValue {
     let options: compile::options::CompileOptions = match serde_wasm_bindgen::from_value(options_js) {
         Ok(opts) => opts,
         Err(e) => {
-            let result = CompilationResult {
-                success: false,
-                error: Some(format!("Invalid options: {}", e)),
-                script: None,
-                script_asm: None,
-                address: None,
-                script_size: None,
-                miniscript_type: None,
-                compiled_miniscript: None,
-                max_satisfaction_size: None,
-                max_weight_to_satisfy: None,
-                sanity_check: None,
-                is_non_malleable: None,
-                debug_info: None,
-                debug_info_leaves: None,
-            };
+            let result = CompilationResult::failure(format!("Invalid options: {}", e));
             return serde_wasm_bindgen::to_value(&result).unwrap();
         }
     };
 
     let result = compile::engine::compile_unified(expression, options)
-        .unwrap_or_else(|e| CompilationResult {
-            success: false,
-            error: Some(e),
-            script: None,
-            script_asm: None,
-            address: None,
-            script_size: None,
-            miniscript_type: None,
-            compiled_miniscript: None,
-            max_satisfaction_size: None,
-            max_weight_to_satisfy: None,
-            sanity_check: None,
-            is_non_malleable: None,
-            debug_info: None,
-            debug_info_leaves: None,
-        });
+        .unwrap_or_else(CompilationResult::failure);
 
     serde_wasm_bindgen::to_value(&result).unwrap()
 }
 
 
+// Compile a list of expressions with the same options in one call
+#[wasm_bindgen]
+pub fn compile_batch(expressions_js: JsValue, options_js: JsValue) -> JsValue {
+    let expressions: Vec<String> = match serde_wasm_bindgen::from_value(expressions_js) {
+        Ok(list) => list,
+        Err(e) => {
+            return serde_wasm_bindgen::to_value(&vec![CompilationResult::failure(format!("Invalid expressions list: {}", e))]).unwrap();
+        }
+    };
+
+    let options: compile::options::CompileOptions = match serde_wasm_bindgen::from_value(options_js) {
+        Ok(opts) => opts,
+        Err(e) => {
+            let error = format!("Invalid options: {}", e);
+            let results: Vec<CompilationResult> = expressions.iter()
+                .map(|_| CompilationResult::failure(error.clone()))
+                .collect();
+            return serde_wasm_bindgen::to_value(&results).unwrap();
+        }
+    };
+
+    let results = compile::engine::compile_batch(&expressions, &options);
+    serde_wasm_bindgen::to_value(&results).unwrap()
+}
+
+// Compile a policy in the cheapest context its keys allow
+#[wasm_bindgen]
+pub fn compile_auto_context(policy: &str) -> JsValue {
+    use crate::compile::types::AutoContextResult;
+
+    let result = match compile::policy::compile_auto_context(policy) {
+        Ok(choice) => AutoContextResult { success: true, error: None, choice: Some(choice) },
+        Err(e) => AutoContextResult { success: false, error: Some(e), choice: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Flatten nested and/or and drop duplicate branches of a policy
+#[wasm_bindgen]
+pub fn simplify_policy(policy: &str) -> JsValue {
+    use crate::compile::types::SimplifyPolicyResult;
+
+    let result = match compile::simplify::simplify_policy(policy) {
+        Ok((simplified, simplifications)) => SimplifyPolicyResult {
+            success: true,
+            error: None,
+            policy: Some(simplified),
+            simplifications: Some(simplifications),
+        },
+        Err(e) => SimplifyPolicyResult { success: false, error: Some(e), policy: None, simplifications: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Compile a policy and report the chosen miniscript with its size, opcode count and weight
+#[wasm_bindgen]
+pub fn policy_to_optimal_miniscript(policy: &str, context: &str) -> JsValue {
+    use crate::compile::types::OptimalMiniscriptResult;
+
+    let result = match compile::policy::policy_to_optimal_miniscript(policy, context) {
+        Ok(optimal) => OptimalMiniscriptResult { success: true, error: None, result: Some(optimal) },
+        Err(e) => OptimalMiniscriptResult { success: false, error: Some(e), result: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Explain which fragments make a miniscript malleable and why
+#[wasm_bindgen]
+pub fn explain_malleability(expression: &str, context: &str) -> JsValue {
+    use crate::compile::types::MalleabilityResult;
+
+    let result = match compile::debug::explain_malleability(expression, context) {
+        Ok(sources) => MalleabilityResult {
+            success: true,
+            error: None,
+            non_malleable: Some(sources.is_empty()),
+            sources: Some(sources),
+        },
+        Err(e) => MalleabilityResult { success: false, error: Some(e), non_malleable: None, sources: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
 // These functions are now in taproot/branches.rs module
 
 // Get miniscript branches for taproot descriptors
@@ -96,35 +149,404 @@ pub fn get_taproot_branch_weights(descriptor: &str) -> JsValue {
     crate::taproot::branches::get_taproot_branch_weights(descriptor)
 }
 
+// Get the control block for a taproot leaf
+#[wasm_bindgen]
+pub fn taproot_control_block(descriptor: &str, leaf_index: usize) -> JsValue {
+    let result = match crate::taproot::control::taproot_control_block(descriptor, leaf_index) {
+        Ok(info) => crate::taproot::control::ControlBlockResult { success: true, error: None, info: Some(info) },
+        Err(e) => crate::taproot::control::ControlBlockResult { success: false, error: Some(e), info: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// List tapleaf hash, merkle branch and control block for every leaf of a taproot descriptor
+#[wasm_bindgen]
+pub fn taproot_leaf_details(descriptor: &str) -> JsValue {
+    let result = match crate::taproot::control::taproot_leaf_details(descriptor) {
+        Ok(leaves) => crate::taproot::control::TaprootLeafDetailsResult { success: true, error: None, leaves: Some(leaves) },
+        Err(e) => crate::taproot::control::TaprootLeafDetailsResult { success: false, error: Some(e), leaves: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// BIP371 PSBT output fields (internal key, tap tree, per-key leaf hashes) of a taproot descriptor
+#[wasm_bindgen]
+pub fn psbt_taproot_fields(descriptor: &str) -> JsValue {
+    let result = match crate::taproot::control::psbt_taproot_fields(descriptor) {
+        Ok(fields) => crate::taproot::control::PsbtTaprootFieldsResult { success: true, error: None, fields: Some(fields) },
+        Err(e) => crate::taproot::control::PsbtTaprootFieldsResult { success: false, error: Some(e), fields: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// BIP341 tapleaf hash of a leaf script, for matching it against a descriptor's tree
+#[wasm_bindgen]
+pub fn tapleaf_hash(script_hex: &str, leaf_version: Option<u8>) -> JsValue {
+    let result = match crate::taproot::control::tapleaf_hash(script_hex, leaf_version) {
+        Ok(hash) => crate::taproot::control::TapleafHashResult { success: true, error: None, tapleaf_hash: Some(hash) },
+        Err(e) => crate::taproot::control::TapleafHashResult { success: false, error: Some(e), tapleaf_hash: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Check a taproot descriptor's script-path spends against Bitcoin Core's P2TR relay policy
+#[wasm_bindgen]
+pub fn taproot_standardness(descriptor: &str) -> JsValue {
+    let result = match validation::taproot_standardness_warnings(descriptor) {
+        Ok(warnings) => TaprootStandardnessResult {
+            success: true,
+            error: None,
+            standard: Some(warnings.is_empty()),
+            warnings: Some(warnings),
+        },
+        Err(e) => TaprootStandardnessResult { success: false, error: Some(e), standard: None, warnings: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// BIP340 tagged hash (TapLeaf, TapBranch, TapTweak, ...) of hex data
+#[wasm_bindgen]
+pub fn tagged_hash(tag: &str, data_hex: &str) -> JsValue {
+    let result = match crate::taproot::utils::tagged_hash(tag, data_hex) {
+        Ok(hash) => crate::taproot::utils::TaggedHashResult { success: true, error: None, hash: Some(hash) },
+        Err(e) => crate::taproot::utils::TaggedHashResult { success: false, error: Some(e), hash: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Expose the taproot tweak (internal key, merkle root, tweak, output key) for auditing
+#[wasm_bindgen]
+pub fn taproot_tweak_details(descriptor: &str) -> JsValue {
+    let result = match crate::taproot::tweak::taproot_tweak_details(descriptor) {
+        Ok(details) => crate::taproot::tweak::TaprootTweakResult { success: true, error: None, details: Some(details) },
+        Err(e) => crate::taproot::tweak::TaprootTweakResult { success: false, error: Some(e), details: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Compare spend weights of the single-leaf, multi-leaf and script-path taproot modes
+#[wasm_bindgen]
+pub fn taproot_mode_weights(expression: &str) -> JsValue {
+    let result = match crate::taproot::weights::taproot_mode_weights(expression) {
+        Ok(modes) => crate::taproot::weights::TaprootModeWeightsResult { success: true, error: None, modes: Some(modes) },
+        Err(e) => crate::taproot::weights::TaprootModeWeightsResult { success: false, error: Some(e), modes: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Type-check a miniscript fragment in isolation (no top-level B requirement)
+#[wasm_bindgen]
+pub fn typecheck_fragment(fragment: &str, context: &str) -> JsValue {
+    let result = match compile::debug::typecheck_fragment(fragment, context) {
+        Ok(info) => FragmentTypeCheckResult {
+            success: true,
+            error: None,
+            annotated_expression: Some(info.annotated_expression),
+            type_properties: Some(info.type_properties),
+            extended_properties: Some(info.extended_properties),
+        },
+        Err(e) => FragmentTypeCheckResult { success: false, error: Some(e), annotated_expression: None, type_properties: None, extended_properties: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// List supported miniscript fragments and wrappers per context
+#[wasm_bindgen]
+pub fn supported_fragments() -> JsValue {
+    serde_wasm_bindgen::to_value(&crate::validation::supported_fragments()).unwrap()
+}
+
+// List x-only keys that alone satisfy a top-level branch and could serve as the taproot internal key
+#[wasm_bindgen]
+pub fn list_candidate_internal_keys(expression: &str) -> JsValue {
+    serde_wasm_bindgen::to_value(&crate::keys::list_candidate_internal_keys(expression)).unwrap()
+}
+
 // Lift a Bitcoin script to miniscript
 #[wasm_bindgen]
 pub fn lift_to_miniscript(bitcoin_script: &str) -> JsValue {
     lift::lift_to_miniscript(bitcoin_script)
 }
 
+// Lift several Bitcoin scripts in one call, one result per script in order
+#[wasm_bindgen]
+pub fn lift_batch(scripts_js: JsValue) -> JsValue {
+    let results = match serde_wasm_bindgen::from_value::<Vec<String>>(scripts_js) {
+        Ok(scripts) => lift::lift_batch(&scripts),
+        Err(e) => vec![LiftResult {
+            success: false,
+            error: Some(format!("Invalid scripts list: {}", e)),
+            miniscript: None,
+            policy: None,
+            liftable: None,
+            attempts: None,
+            warnings: None,
+            verified_roundtrip: None,
+            non_minimal: None,
+        }],
+    };
+    serde_wasm_bindgen::to_value(&results).unwrap()
+}
+
 // Lift a miniscript to policy
 #[wasm_bindgen]
 pub fn lift_to_policy(miniscript: &str) -> JsValue {
     lift::lift_to_policy(miniscript)
 }
 
+// Recover a concrete policy from a miniscript where the structure maps cleanly
+#[wasm_bindgen]
+pub fn miniscript_to_concrete_policy(miniscript: &str, context: &str) -> JsValue {
+    let result = match lift::miniscript_to_concrete_policy(miniscript, context) {
+        Ok(policy) => LiftResult { success: true, error: None, miniscript: None, policy: Some(policy), liftable: None, attempts: None, warnings: None, verified_roundtrip: None, non_minimal: None },
+        Err(e) => LiftResult { success: false, error: Some(e), miniscript: None, policy: None, liftable: None, attempts: None, warnings: None, verified_roundtrip: None, non_minimal: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
 // Analyze a miniscript expression
 #[wasm_bindgen]
 pub fn analyze_miniscript(expression: &str, context: &str) -> JsValue {
     analyze::analyze_miniscript(expression, context)
 }
 
-// Analyze a policy expression
+// Analyze a miniscript expression, overriding the path enumeration caps
 #[wasm_bindgen]
-pub fn analyze_policy(policy: &str) -> JsValue {
-    analyze::analyze_policy(policy)
+pub fn analyze_miniscript_with_limits(expression: &str, context: &str, max_paths: Option<usize>, max_thresh_combinations: Option<u32>) -> JsValue {
+    let limits = analyze::PathLimits::new(max_paths, max_thresh_combinations.map(u64::from));
+    analyze::analyze_miniscript_with_limits(expression, context, limits)
+}
+
+// Analyze a policy expression, optionally attaching top-level or() weights to the path groups
+#[wasm_bindgen]
+pub fn analyze_policy(policy: &str, include_weights: Option<bool>) -> JsValue {
+    analyze::analyze_policy(policy, include_weights.unwrap_or(false))
+}
+
+// Analyze a policy expression, overriding the path enumeration caps
+#[wasm_bindgen]
+pub fn analyze_policy_with_limits(policy: &str, include_weights: Option<bool>, max_paths: Option<usize>, max_thresh_combinations: Option<u32>) -> JsValue {
+    let limits = analyze::PathLimits::new(max_paths, max_thresh_combinations.map(u64::from));
+    analyze::analyze_policy_with_limits(policy, include_weights.unwrap_or(false), limits)
+}
+// Report which spending paths each key participates in
+#[wasm_bindgen]
+pub fn key_path_matrix(expression: &str, context: &str) -> JsValue {
+    let result = match analyze::key_path_matrix(expression, context) {
+        Ok(keys) => KeyPathMatrixResult { success: true, error: None, keys: Some(keys) },
+        Err(e) => KeyPathMatrixResult { success: false, error: Some(e), keys: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
 }
+
+// Report which spending paths the given keys and preimages can satisfy
+#[wasm_bindgen]
+pub fn satisfiable_paths(expression: &str, context: &str, available_keys_js: JsValue, available_preimages_js: JsValue) -> JsValue {
+    let inputs = serde_wasm_bindgen::from_value::<Vec<String>>(available_keys_js)
+        .and_then(|keys| serde_wasm_bindgen::from_value::<Vec<String>>(available_preimages_js).map(|preimages| (keys, preimages)))
+        .map_err(|e| format!("Invalid key or preimage list: {}", e));
+    let result = match inputs.and_then(|(keys, preimages)| analyze::satisfiable_paths(expression, context, &keys, &preimages)) {
+        Ok((satisfiable, unsatisfiable)) => SatisfiablePathsResult {
+            success: true,
+            error: None,
+            satisfiable: Some(satisfiable),
+            unsatisfiable: Some(unsatisfiable),
+        },
+        Err(e) => SatisfiablePathsResult { success: false, error: Some(e), satisfiable: None, unsatisfiable: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// List spending paths with typed conditions for tree views
+#[wasm_bindgen]
+pub fn spending_paths_structured(expression: &str, context: &str) -> JsValue {
+    let result = match analyze::spending_paths_structured(expression, context) {
+        Ok(paths) => StructuredPathsResult { success: true, error: None, paths: Some(paths) },
+        Err(e) => StructuredPathsResult { success: false, error: Some(e), paths: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Summarize the spending policy as numbered sentences, naming keys from an optional key map
+#[wasm_bindgen]
+pub fn contract_summary(expression: &str, context: &str, key_names_js: JsValue) -> JsValue {
+    let key_names = if key_names_js.is_undefined() || key_names_js.is_null() {
+        Ok(std::collections::HashMap::new())
+    } else {
+        serde_wasm_bindgen::from_value::<std::collections::HashMap<String, String>>(key_names_js)
+            .map_err(|e| format!("Invalid key map: {}", e))
+    };
+    let result = match key_names.and_then(|names| analyze::contract_summary(expression, context, &names)) {
+        Ok(summary) => ContractSummaryResult { success: true, error: None, summary: Some(summary) },
+        Err(e) => ContractSummaryResult { success: false, error: Some(e), summary: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Estimate the number of spending paths before running full enumeration
+#[wasm_bindgen]
+pub fn estimate_path_count(expression: &str, context: &str) -> JsValue {
+    let result = match analyze::estimate_path_count(expression, context) {
+        Ok(count) => PathCountResult { success: true, error: None, path_count: Some(count) },
+        Err(e) => PathCountResult { success: false, error: Some(e), path_count: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
 // Generate address for network switching (Legacy/Segwit/Taproot)
 #[wasm_bindgen]
 pub fn generate_address_for_network(script_hex: &str, script_type: &str, network: &str) -> JsValue {
     address::generate_address_for_network(script_hex, script_type, network)
 }
 
+// Derive the scriptPubKey for one index of a ranged descriptor
+#[wasm_bindgen]
+pub fn script_pubkey_at_index(descriptor: &str, index: u32, network: &str) -> JsValue {
+    let result = match address::script_pubkey_at_index(descriptor, index, network) {
+        Ok(derived) => ScriptPubKeyResult {
+            success: true,
+            error: None,
+            script_hex: Some(derived.script_hex),
+            address: derived.address,
+        },
+        Err(e) => ScriptPubKeyResult { success: false, error: Some(e.to_string()), script_hex: None, address: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Expand a combo() descriptor into all of its outputs
+#[wasm_bindgen]
+pub fn combo_outputs(descriptor: &str, index: u32, network: &str) -> JsValue {
+    let result = match address::combo_outputs(descriptor, index, network) {
+        Ok(outputs) => ComboOutputsResult { success: true, error: None, outputs: Some(outputs) },
+        Err(e) => ComboOutputsResult { success: false, error: Some(e.to_string()), outputs: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Compare the scriptPubKeys two descriptors produce at the sampled indices
+#[wasm_bindgen]
+pub fn descriptors_equivalent(desc_a: &str, desc_b: &str, network: &str, sample_indices_js: JsValue) -> JsValue {
+    let indices = if sample_indices_js.is_undefined() || sample_indices_js.is_null() {
+        Ok(Vec::new())
+    } else {
+        serde_wasm_bindgen::from_value::<Vec<u32>>(sample_indices_js).map_err(|e| format!("Invalid sample indices: {}", e))
+    };
+    let result = match indices.and_then(|indices| {
+        address::descriptors_equivalent(desc_a, desc_b, network, &indices).map_err(|e| e.to_string())
+    }) {
+        Ok(found) => DescriptorEquivalenceResult {
+            success: true,
+            error: None,
+            equivalent: Some(found.equivalent),
+            diverging_index: found.diverging_index,
+        },
+        Err(e) => DescriptorEquivalenceResult { success: false, error: Some(e), equivalent: None, diverging_index: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Check that a redeem, witness or leaf script produces a known address
+#[wasm_bindgen]
+pub fn match_address_to_script(address: &str, script_hex: &str, network: &str) -> JsValue {
+    let result = match address::match_address_to_script(address, script_hex, network) {
+        Ok(found) => AddressScriptMatchResult {
+            success: true,
+            error: None,
+            matches: Some(found.matches),
+            output_type: Some(found.output_type),
+        },
+        Err(e) => AddressScriptMatchResult { success: false, error: Some(e.to_string()), matches: None, output_type: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Find the spending path with the lowest fee at a fee rate in sat/vB
+#[wasm_bindgen]
+pub fn cheapest_spend(expression: &str, context: &str, fee_rate_sat_vb: f64) -> JsValue {
+    use crate::analyze::spend::CheapestSpendResult;
+
+    let result = match analyze::spend::cheapest_spend(expression, context, fee_rate_sat_vb) {
+        Ok(cheapest) => CheapestSpendResult { success: true, error: None, cheapest: Some(cheapest) },
+        Err(e) => CheapestSpendResult { success: false, error: Some(e), cheapest: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Break the worst-case weight of a k-of-n multisig spend into script, signature and overhead parts
+#[wasm_bindgen]
+pub fn weight_breakdown(expression: &str, context: &str) -> JsValue {
+    use crate::analyze::spend::WeightBreakdownResult;
+
+    let result = match analyze::spend::weight_breakdown(expression, context) {
+        Ok(breakdown) => WeightBreakdownResult { success: true, error: None, breakdown: Some(breakdown) },
+        Err(e) => WeightBreakdownResult { success: false, error: Some(e), breakdown: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Compile a policy template over placeholder names with a per-signer key map into a ranged descriptor
+#[wasm_bindgen]
+pub fn compile_descriptor_template(template: &str, key_map_js: JsValue, context: &str) -> JsValue {
+    use crate::descriptors::types::{DescriptorTemplateResult, TemplateKey};
+
+    let result = serde_wasm_bindgen::from_value::<Vec<TemplateKey>>(key_map_js)
+        .map_err(|e| format!("Invalid key map: {}", e))
+        .and_then(|keys| descriptors::template::compile_descriptor_template(template, &keys, context));
+    let result = match result {
+        Ok(descriptor) => DescriptorTemplateResult { success: true, error: None, descriptor: Some(descriptor) },
+        Err(e) => DescriptorTemplateResult { success: false, error: Some(e), descriptor: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Preview the first derived keys for each HD descriptor in an expression
+#[wasm_bindgen]
+pub fn preview_descriptor_keys(expression: &str, count: u32) -> JsValue {
+    let result = match descriptors::utils::preview_descriptor_keys(expression, count) {
+        Ok(previews) => DescriptorPreviewResult { success: true, error: None, previews },
+        Err(e) => DescriptorPreviewResult { success: false, error: Some(e), previews: Vec::new() },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Stable id of a descriptor for tracking and deduplication across sessions
+#[wasm_bindgen]
+pub fn descriptor_id(descriptor: &str) -> JsValue {
+    let result = match descriptors::utils::descriptor_id(descriptor) {
+        Ok(id) => DescriptorIdResult { success: true, error: None, id: Some(id) },
+        Err(e) => DescriptorIdResult { success: false, error: Some(e), id: None },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+// Compile an expression and check its address against an expected one
+#[wasm_bindgen]
+pub fn compile_and_assert(expression: &str, options_js: JsValue, expected_address: &str) -> JsValue {
+    let outcome = serde_wasm_bindgen::from_value::<compile::options::CompileOptions>(options_js)
+        .map_err(|e| format!("Invalid options: {}", e))
+        .and_then(|options| compile::engine::compile_and_assert(expression, options, expected_address));
+    let result = match outcome {
+        Ok((matches, actual)) => AddressAssertionResult {
+            success: true,
+            error: None,
+            matches,
+            mismatch: if matches { None } else { address::network_mismatch(expected_address, &actual) },
+            actual: Some(actual),
+            expected: expected_address.to_string(),
+        },
+        Err(e) => AddressAssertionResult {
+            success: false,
+            error: Some(e),
+            matches: false,
+            actual: None,
+            expected: expected_address.to_string(),
+            mismatch: None,
+        },
+    };
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
 // Get build information for debugging deployment issues
 #[wasm_bindgen]
 pub fn get_wasm_build_info() -> JsValue {
@@ -164,6 +586,18 @@ pub fn export_comprehensive(expression: &str, context: &str, input_type: &str, o
 
 // Export simple descriptor (Sparrow/Liana compatible)
 #[wasm_bindgen]
-pub fn export_descriptor(expression: &str, context: &str, input_type: &str) -> JsValue {
-    export::export_descriptor(expression, context, input_type)
+pub fn export_descriptor(expression: &str, context: &str, input_type: &str, options_js: JsValue) -> JsValue {
+    export::export_descriptor(expression, context, input_type, options_js)
+}
+
+// Export for the wallet selected by profile (bitcoin-core, sparrow, liana or generic)
+#[wasm_bindgen]
+pub fn export_profile(expression: &str, context: &str, input_type: &str, profile: &str, options_js: JsValue) -> JsValue {
+    export::export_profile(expression, context, input_type, profile, options_js)
+}
+
+// Export a primary/recovery policy as a Liana multipath descriptor
+#[wasm_bindgen]
+pub fn export_liana(expression: &str, context: &str) -> JsValue {
+    export::export_liana(expression, context)
 }
@@ -3,108 +3,246 @@
 use wasm_bindgen::JsValue;
 use crate::console_log;
 use crate::opcodes::parse_asm_to_script;
-use miniscript::{Miniscript, Legacy, Segwitv0, Tap, policy::Liftable};
+use miniscript::{Miniscript, MiniscriptKey, Legacy, Segwitv0, Tap, ExtParams, policy::Liftable};
+use miniscript::policy::{Concrete, Semantic};
 use bitcoin::{ScriptBuf, Script};
+use crate::types::LiftAttempt;
+use crate::parse::helpers::unwrap_script_descriptor;
+use crate::validation::{check_expression_length, MAX_EXPRESSION_LENGTH};
 
 pub(crate) fn lift_to_miniscript(bitcoin_script: &str) -> JsValue {
     console_log!("Lifting Bitcoin script to miniscript: {}", bitcoin_script);
     
-    let result = match perform_lift_to_miniscript(bitcoin_script) {
-        Ok(miniscript) => crate::LiftResult {
+    let result = lift_script_result(bitcoin_script);
+    
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+/// Lift many scripts in one call, one result per script in input order
+/// A script that fails to lift only fails its own entry, with its per-context attempts
+pub fn lift_batch(scripts: &[String]) -> Vec<crate::LiftResult> {
+    console_log!("=== BATCH LIFT ({} scripts) ===", scripts.len());
+
+    scripts.iter().map(|script| lift_script_result(script)).collect()
+}
+
+// Lift one script into the result shape shared by lift_to_miniscript and lift_batch
+fn lift_script_result(bitcoin_script: &str) -> crate::LiftResult {
+    let non_minimal = parse_script_input(bitcoin_script.trim()).ok()
+        .map(|script| has_non_minimal_encoding(&script));
+    match lift_script_to_miniscript(bitcoin_script) {
+        Ok((miniscript, warnings, verified_roundtrip)) => crate::LiftResult {
             success: true,
             error: None,
             miniscript: Some(miniscript),
             policy: None,
+            liftable: Some(true),
+            attempts: None,
+            warnings: if warnings.is_empty() { None } else { Some(warnings) },
+            verified_roundtrip: Some(verified_roundtrip),
+            non_minimal,
         },
-        Err(e) => crate::LiftResult {
+        Err((e, attempts)) => crate::LiftResult {
             success: false,
             error: Some(e),
             miniscript: None,
             policy: None,
+            liftable: Some(false),
+            attempts: Some(attempts),
+            warnings: None,
+            verified_roundtrip: None,
+            non_minimal,
         }
-    };
-    
-    serde_wasm_bindgen::to_value(&result).unwrap()
+    }
 }
 
 pub(crate) fn lift_to_policy(miniscript: &str) -> JsValue {
     console_log!("Lifting miniscript to policy: {}", miniscript);
     
-    let result = match perform_lift_to_policy(miniscript) {
+    let result = match lift_miniscript_to_semantic_policy(miniscript) {
         Ok(policy) => crate::LiftResult {
             success: true,
             error: None,
             miniscript: None,
             policy: Some(policy),
+            liftable: Some(true),
+            attempts: None,
+            warnings: None,
+            verified_roundtrip: None,
+            non_minimal: None,
         },
-        Err(e) => crate::LiftResult {
+        Err((e, attempts)) => crate::LiftResult {
             success: false,
             error: Some(e),
             miniscript: None,
             policy: None,
+            liftable: Some(false),
+            attempts: Some(attempts),
+            warnings: None,
+            verified_roundtrip: None,
+            non_minimal: None,
         }
     };
     
     serde_wasm_bindgen::to_value(&result).unwrap()
 }
 
-// Internal function to perform lift to miniscript
-fn perform_lift_to_miniscript(bitcoin_script: &str) -> Result<String, String> {
+/// Lifted miniscript, caveats about the lift, and whether it re-encodes to the original script
+pub type LiftedScript = (String, Vec<String>, bool);
+
+/// Lift a Bitcoin script (hex or ASM) to miniscript, trying Legacy, Segwit and Taproot in turn
+/// Scripts that only commit to a key hash (P2PKH pattern) fall back to `expr_raw_pkh` with a warning.
+/// On success also reports whether the miniscript re-encodes to exactly the original bytes.
+/// On failure returns the formatted message together with one structured attempt per context
+pub fn lift_script_to_miniscript(bitcoin_script: &str) -> Result<LiftedScript, (String, Vec<LiftAttempt>)> {
     if bitcoin_script.trim().is_empty() {
-        return Err("Empty Bitcoin script".to_string());
+        return Err(("Empty Bitcoin script".to_string(), Vec::new()));
     }
+    check_expression_length(bitcoin_script, MAX_EXPRESSION_LENGTH).map_err(|e| (e, Vec::new()))?;
     
     let trimmed = bitcoin_script.trim();
     console_log!("Processing Bitcoin script ASM: {}", trimmed);
     
     // Parse script from hex or ASM
-    let script = if trimmed.len() % 2 == 0 && trimmed.chars().all(|c| c.is_ascii_hexdigit()) {
-        match hex::decode(trimmed) {
-            Ok(bytes) => ScriptBuf::from_bytes(bytes),
-            Err(_) => return Err("Invalid hex script".to_string()),
-        }
-    } else {
-        parse_asm_to_script(trimmed)?
-    };
+    let script = parse_script_input(trimmed).map_err(|e| (e, Vec::new()))?;
     
     console_log!("Successfully parsed Bitcoin script, length: {} bytes", script.len());
     
     // Try to lift for different contexts
-    let mut context_errors = Vec::new();
+    let mut attempts = Vec::new();
     
     // Try Legacy
     match try_lift_script_to_miniscript::<Legacy>(script.as_script()) {
-        Ok(ms) => return Ok(ms),
-        Err(e) => {
-            context_errors.push(("Legacy", e));
+        Ok((ms, verified)) => return Ok((ms, roundtrip_warnings(verified), verified)),
+        Err((insane_err, parse_err)) => {
+            attempts.push(LiftAttempt { context: "Legacy".to_string(), parse_insane_error: Some(insane_err), parse_error: Some(parse_err) });
             console_log!("Legacy lift failed");
         }
     }
     
     // Try Segwit
     match try_lift_script_to_miniscript::<Segwitv0>(script.as_script()) {
-        Ok(ms) => return Ok(ms),
-        Err(e) => {
-            context_errors.push(("Segwit", e));
+        Ok((ms, verified)) => return Ok((ms, roundtrip_warnings(verified), verified)),
+        Err((insane_err, parse_err)) => {
+            attempts.push(LiftAttempt { context: "Segwit".to_string(), parse_insane_error: Some(insane_err), parse_error: Some(parse_err) });
             console_log!("Segwit lift failed");
         }
     }
     
     // Try Taproot
     match try_lift_script_to_miniscript::<Tap>(script.as_script()) {
-        Ok(ms) => return Ok(ms),
-        Err(e) => {
-            context_errors.push(("Taproot", e));
+        Ok((ms, verified)) => return Ok((ms, roundtrip_warnings(verified), verified)),
+        Err((insane_err, parse_err)) => {
+            attempts.push(LiftAttempt { context: "Taproot".to_string(), parse_insane_error: Some(insane_err), parse_error: Some(parse_err) });
             console_log!("Taproot lift failed");
         }
     }
     
+    // Last resort: keep raw key hashes as expr_raw_pkh
+    let raw_pkh = try_lift_raw_pkh::<Legacy>(script.as_script())
+        .or_else(|| try_lift_raw_pkh::<Segwitv0>(script.as_script()))
+        .or_else(|| try_lift_raw_pkh::<Tap>(script.as_script()));
+    if let Some((ms, verified)) = raw_pkh {
+        let warning = "⚠️ Lifted using expr_raw_pkh: the script only commits to a public key hash, so the key behind it is unknown until it is revealed at spend time".to_string();
+        let mut warnings = vec![warning];
+        warnings.extend(roundtrip_warnings(verified));
+        return Ok((ms, warnings, verified));
+    }
+    
     // Format error message
-    format_lift_error(context_errors)
+    Err((format_lift_error(&attempts), attempts))
+}
+
+// Parse a script given as hex or ASM
+fn parse_script_input(trimmed: &str) -> Result<ScriptBuf, String> {
+    if trimmed.len() % 2 == 0 && trimmed.chars().all(|c| c.is_ascii_hexdigit()) {
+        hex::decode(trimmed)
+            .map(ScriptBuf::from_bytes)
+            .map_err(|_| "Invalid hex script".to_string())
+    } else {
+        parse_asm_to_script(trimmed)
+    }
+}
+
+/// Whether a script uses a non-minimal encoding that policy rejects (MINIMALDATA)
+/// Covers pushes that should use a shorter opcode (e.g. OP_PUSHDATA1 for 10 bytes, or a
+/// one-byte push of 1-16 instead of OP_1..OP_16) and non-minimal numbers. Only pushes that
+/// miniscript uses as numbers are read as numbers: the operand of a timelock or equality
+/// check (thresh and multi_a `k`) and the `k` and `n` of a CHECKMULTISIG.
+pub fn has_non_minimal_encoding(script: &Script) -> bool {
+    use bitcoin::opcodes::all::*;
+    use bitcoin::script::{read_scriptint, read_scriptint_non_minimal, Error, Instruction};
+
+    if script.instructions_minimal().any(|instruction| matches!(instruction, Err(Error::NonMinimalPush))) {
+        return true;
+    }
+
+    let instructions: Vec<Instruction> = script.instructions().filter_map(Result::ok).collect();
+    let non_minimal_number = |index: Option<usize>| match index.and_then(|i| instructions.get(i)) {
+        Some(Instruction::PushBytes(push)) if push.len() <= 4 => {
+            matches!(read_scriptint(push.as_bytes()), Err(Error::NonMinimalPush))
+        }
+        _ => false,
+    };
+    let number_value = |index: usize| match instructions.get(index)? {
+        Instruction::Op(op) => match op.classify(bitcoin::opcodes::ClassifyContext::Legacy) {
+            bitcoin::opcodes::Class::PushNum(n) => usize::try_from(n).ok(),
+            _ => None,
+        },
+        Instruction::PushBytes(push) if push.len() <= 4 => {
+            read_scriptint_non_minimal(push.as_bytes()).ok().and_then(|n| usize::try_from(n).ok())
+        }
+        Instruction::PushBytes(_) => None,
+    };
+
+    instructions.iter().enumerate().any(|(index, instruction)| {
+        let Instruction::Op(op) = instruction else {
+            return false;
+        };
+        let previous = index.checked_sub(1);
+        match *op {
+            OP_CSV | OP_CLTV | OP_EQUAL | OP_EQUALVERIFY | OP_NUMEQUAL | OP_NUMEQUALVERIFY => non_minimal_number(previous),
+            OP_CHECKMULTISIG | OP_CHECKMULTISIGVERIFY => {
+                // k <keys...> n CHECKMULTISIG
+                let k = previous.and_then(number_value).and_then(|n| index.checked_sub(n + 2));
+                non_minimal_number(previous) || non_minimal_number(k)
+            }
+            _ => false,
+        }
+    })
+}
+
+// Flag a lift whose miniscript does not encode back to the original script
+fn roundtrip_warnings(verified: bool) -> Vec<String> {
+    if verified {
+        Vec::new()
+    } else {
+        vec!["⚠️ Approximate lift: the miniscript re-encodes to different bytes than the original script".to_string()]
+    }
+}
+
+// Lift a script that contains raw public key hashes (DUP HASH160 <hash> EQUALVERIFY)
+// Returns the miniscript and whether it re-encodes to the original script
+fn try_lift_raw_pkh<Ctx>(script: &Script) -> Option<(String, bool)>
+where
+    Ctx: miniscript::ScriptContext,
+    for<'a> Ctx::Key: std::fmt::Display + std::str::FromStr,
+    <Ctx::Key as std::str::FromStr>::Err: std::fmt::Display,
+{
+    let ext = ExtParams::insane().raw_pkh();
+    match Miniscript::<Ctx::Key, Ctx>::parse_with_ext(script, &ext) {
+        Ok(ms) if ms.contains_raw_pkh() => {
+            console_log!("Lifted with raw pkh support: {}", ms);
+            Some((ms.to_string(), ms.encode().as_script() == script))
+        }
+        _ => None,
+    }
 }
 
 // Try to lift script to miniscript for a specific context
-fn try_lift_script_to_miniscript<Ctx>(script: &Script) -> Result<String, String> 
+// Returns the miniscript and whether it re-encodes to the original script,
+// or the parse_insane and parse errors when both parsers fail
+fn try_lift_script_to_miniscript<Ctx>(script: &Script) -> Result<(String, bool), (String, String)> 
 where 
     Ctx: miniscript::ScriptContext,
     for<'a> Ctx::Key: std::fmt::Display + std::str::FromStr,
@@ -117,7 +255,7 @@ where
         Ok(ms) => {
             let ms_string = ms.to_string();
             console_log!("Successfully lifted to miniscript using parse_insane: {}", ms_string);
-            Ok(ms_string)
+            Ok((ms_string, ms.encode().as_script() == script))
         }
         Err(insane_err) => {
             console_log!("parse_insane failed: {}", insane_err);
@@ -126,11 +264,11 @@ where
                 Ok(ms) => {
                     let ms_string = ms.to_string();
                     console_log!("Successfully lifted to miniscript using parse: {}", ms_string);
-                    Ok(ms_string)
+                    Ok((ms_string, ms.encode().as_script() == script))
                 }
                 Err(parse_err) => {
                     console_log!("Both parse_insane and parse failed");
-                    Err(format!("parse_insane: {}, parse: {}", insane_err, parse_err))
+                    Err((insane_err.to_string(), parse_err.to_string()))
                 }
             }
         }
@@ -138,71 +276,66 @@ where
 }
 
 // Format lift error message
-fn format_lift_error(context_errors: Vec<(&str, String)>) -> Result<String, String> {
+fn format_lift_error(attempts: &[LiftAttempt]) -> String {
     let mut error_msg = String::from("❌ Script is not liftable to Miniscript\n\n");
     error_msg.push_str("This Bitcoin script cannot be lifted to miniscript. Attempted lifting with both standard and non-standard parsers across all contexts:\n\n");
     
-    for (context_name, error) in context_errors {
-        error_msg.push_str(&format!("📍 {} Context:\n", context_name));
-        
-        // Extract detailed errors if available
-        if let Some(pos) = error.find("parse_insane: ") {
-            let after = &error[pos + 14..];
-            if let Some(comma_pos) = after.find(", parse: ") {
-                let insane_err = &after[..comma_pos];
-                let parse_err = &after[comma_pos + 9..];
-                error_msg.push_str(&format!("   • parse_insane: ❌ {}\n", insane_err));
-                error_msg.push_str(&format!("   • parse: ❌ {}\n\n", parse_err));
-            } else {
-                error_msg.push_str(&format!("   • Error: ❌ {}\n\n", error));
-            }
-        } else {
-            error_msg.push_str(&format!("   • Error: ❌ {}\n\n", error));
+    for attempt in attempts {
+        error_msg.push_str(&format!("📍 {} Context:\n", attempt.context));
+        if let Some(insane_err) = &attempt.parse_insane_error {
+            error_msg.push_str(&format!("   • parse_insane: ❌ {}\n", insane_err));
+        }
+        if let Some(parse_err) = &attempt.parse_error {
+            error_msg.push_str(&format!("   • parse: ❌ {}\n", parse_err));
         }
+        error_msg.push('\n');
     }
     
-    error_msg.push_str("Note: Scripts containing raw public key hashes (P2PKH) or certain non-miniscript constructs cannot be lifted.");
+    error_msg.push_str("Note: Scripts containing non-miniscript constructs cannot be lifted; raw public key hashes (P2PKH) were also tried as expr_raw_pkh.");
     
-    Err(error_msg)
+    error_msg
 }
 
-// Internal function to perform lift to policy
-fn perform_lift_to_policy(miniscript: &str) -> Result<String, String> {
+/// Lift a miniscript to its semantic policy string, trying Legacy, Segwit and Taproot in turn
+/// On failure returns the formatted message together with one structured attempt per context
+pub fn lift_miniscript_to_semantic_policy(miniscript: &str) -> Result<String, (String, Vec<LiftAttempt>)> {
     if miniscript.trim().is_empty() {
-        return Err("Empty miniscript".to_string());
+        return Err(("Empty miniscript".to_string(), Vec::new()));
     }
+    check_expression_length(miniscript, MAX_EXPRESSION_LENGTH).map_err(|e| (e, Vec::new()))?;
     
-    let trimmed = miniscript.trim();
+    // sh()/wsh()/sh(wsh()) wrappers do not change the policy; lift the inner miniscript
+    let trimmed = unwrap_script_descriptor(miniscript).map_or(miniscript.trim(), |(inner, _, _)| inner);
     console_log!("Attempting to lift miniscript to policy: {}", trimmed);
     
     // Try different contexts
-    let mut errors = Vec::new();
+    let mut attempts = Vec::new();
     
     // Try Legacy
     match lift_miniscript_to_policy::<Legacy>(trimmed) {
         Ok(policy) => return Ok(policy),
-        Err(e) => errors.push(("Legacy", e))
+        Err(e) => attempts.push(LiftAttempt { context: "Legacy".to_string(), parse_insane_error: None, parse_error: Some(e) })
     }
     
     // Try Segwit
     match lift_miniscript_to_policy::<Segwitv0>(trimmed) {
         Ok(policy) => return Ok(policy),
-        Err(e) => errors.push(("Segwit", e))
+        Err(e) => attempts.push(LiftAttempt { context: "Segwit".to_string(), parse_insane_error: None, parse_error: Some(e) })
     }
     
     // Try Taproot
     match lift_miniscript_to_policy::<Tap>(trimmed) {
         Ok(policy) => return Ok(policy),
-        Err(e) => errors.push(("Taproot", e))
+        Err(e) => attempts.push(LiftAttempt { context: "Taproot".to_string(), parse_insane_error: None, parse_error: Some(e) })
     }
     
     // Format error message
     let mut error_msg = String::from("Failed to lift miniscript to policy:\n");
-    for (context, err) in errors {
-        error_msg.push_str(&format!("  {} context: {}\n", context, err));
+    for attempt in &attempts {
+        error_msg.push_str(&format!("  {} context: {}\n", attempt.context, attempt.parse_error.as_deref().unwrap_or_default()));
     }
     
-    Err(error_msg)
+    Err((error_msg, attempts))
 }
 
 // Lift miniscript to policy for a specific context
@@ -227,3 +360,60 @@ where
     }
 }
 
+
+/// Recover a concrete policy string from a miniscript
+/// Lifts to the semantic policy and rebuilds it with pk/and/or/thresh/timelock/hashlock
+/// fragments; fails when the lifted policy has no clean concrete equivalent
+pub fn miniscript_to_concrete_policy(miniscript: &str, context: &str) -> Result<String, String> {
+    let trimmed = miniscript.trim();
+    if trimmed.is_empty() {
+        return Err("Empty miniscript".to_string());
+    }
+
+    let semantic = match context.to_lowercase().as_str() {
+        "legacy" | "p2sh" => lift_to_semantic::<Legacy>(trimmed)?,
+        "segwit" | "segwitv0" | "p2wsh" => lift_to_semantic::<Segwitv0>(trimmed)?,
+        "taproot" | "tap" | "p2tr" => lift_to_semantic::<Tap>(trimmed)?,
+        _ => return Err(format!("Unknown context: {}. Use legacy, segwit, or taproot.", context)),
+    };
+
+    // Semantic policies carry no branch probabilities, so or() branches are left unweighted
+    let policy_str = semantic_to_concrete_string(&semantic)?;
+    policy_str.parse::<Concrete<String>>()
+        .map_err(|e| format!("Reconstructed policy is not valid: {}", e))?;
+    console_log!("Recovered concrete policy: {}", policy_str);
+    Ok(policy_str)
+}
+
+// Parse a miniscript with string keys and lift it to its semantic policy
+fn lift_to_semantic<Ctx: miniscript::ScriptContext>(miniscript: &str) -> Result<Semantic<String>, String> {
+    let ms: Miniscript<String, Ctx> = miniscript.parse()
+        .map_err(|e| format!("Miniscript parsing failed: {}", e))?;
+    ms.lift().map_err(|e| format!("Policy lifting failed: {}", e))
+}
+
+// Rebuild a semantic policy node as concrete policy syntax
+fn semantic_to_concrete_string<Pk: MiniscriptKey>(policy: &Semantic<Pk>) -> Result<String, String> {
+    match policy {
+        Semantic::Unsatisfiable => Err("Miniscript is unsatisfiable and has no concrete policy".to_string()),
+        Semantic::Trivial => Err("Miniscript is trivially satisfiable and has no concrete policy".to_string()),
+        Semantic::Key(pk) => Ok(format!("pk({})", pk)),
+        Semantic::After(t) => Ok(format!("after({})", t.to_consensus_u32())),
+        Semantic::Older(t) => Ok(format!("older({})", t.to_consensus_u32())),
+        Semantic::Sha256(h) => Ok(format!("sha256({})", h)),
+        Semantic::Hash256(h) => Ok(format!("hash256({})", h)),
+        Semantic::Ripemd160(h) => Ok(format!("ripemd160({})", h)),
+        Semantic::Hash160(h) => Ok(format!("hash160({})", h)),
+        Semantic::Thresh(thresh) => {
+            let children = thresh.iter()
+                .map(|child| semantic_to_concrete_string(child.as_ref()))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            match (thresh.k(), thresh.n()) {
+                (2, 2) => Ok(format!("and({})", children.join(","))),
+                (1, 2) => Ok(format!("or({})", children.join(","))),
+                (k, _) => Ok(format!("thresh({},{})", k, children.join(","))),
+            }
+        }
+    }
+}
@@ -4,6 +4,128 @@ pub(crate) fn is_descriptor_wrapper(expression: &str) -> bool {
 	expression.starts_with("wsh(") || expression.starts_with("sh(") || expression.starts_with("wpkh(")
 }
 
+// Nested segwit: a P2WSH script wrapped in P2SH
+pub(crate) fn is_nested_segwit_wrapper(expression: &str) -> bool {
+	expression.starts_with("sh(wsh(")
+}
+
+// Peel sh()/wsh()/sh(wsh()) off a script descriptor (and its #checksum)
+// Returns the inner miniscript, the context it runs under and the output type
+pub(crate) fn unwrap_script_descriptor(expression: &str) -> Option<(&str, &'static str, &'static str)> {
+	let trimmed = expression.trim();
+	if !is_descriptor_wrapper(trimmed) {
+		return None;
+	}
+	let body = trimmed.split('#').next().unwrap_or(trimmed);
+
+	let (inner, context, output_type) = if is_nested_segwit_wrapper(body) {
+		(body.strip_prefix("sh(wsh(")?.strip_suffix("))")?, "segwit", "P2SH-P2WSH (nested segwit)")
+	} else if let Some(rest) = body.strip_prefix("wsh(") {
+		(rest.strip_suffix(')')?, "segwit", "P2WSH")
+	} else if let Some(rest) = body.strip_prefix("sh(") {
+		(rest.strip_suffix(')')?, "legacy", "P2SH")
+	} else {
+		return None;
+	};
+
+	// wpkh() and sh(wpkh()) are key descriptors, not miniscript wrappers
+	if inner.starts_with("wpkh(") {
+		return None;
+	}
+	Some((inner, context, output_type))
+}
+
+// Invisible characters picked up when copying from web pages and documents:
+// zero-width space, zero-width non-joiner/joiner, word joiner and the byte order mark
+pub(crate) const INVISIBLE_CHARS: [char; 5] = ['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+
+// Remove insignificant whitespace, e.g. line breaks and indentation in pretty-printed expressions,
+// along with invisible copy-paste characters
+// Whitespace is only insignificant at the ends and next to `(`, `)`, `,`, `{` and `}`; inside an
+// argument (`older(1 44)`, a key split across lines) it is an error rather than silently joined
+pub(crate) fn strip_whitespace(expression: &str) -> Result<String, String> {
+	const DELIMITERS: [char; 5] = ['(', ')', ',', '{', '}'];
+
+	let visible = strip_invisible(expression);
+	let mut stripped = String::with_capacity(visible.len());
+	let mut chars = visible.trim().chars().peekable();
+	while let Some(c) = chars.next() {
+		if !c.is_whitespace() {
+			stripped.push(c);
+			continue;
+		}
+		while chars.next_if(|next| next.is_whitespace()).is_some() {}
+		let before = stripped.chars().last();
+		let after = chars.peek().copied();
+		if !before.is_some_and(|b| DELIMITERS.contains(&b)) && !after.is_some_and(|a| DELIMITERS.contains(&a)) {
+			let token_start = stripped.rfind(DELIMITERS).map_or(0, |i| i + 1);
+			let next_token: String = chars.clone().take_while(|c| !DELIMITERS.contains(c)).collect();
+			return Err(format!(
+				"Unexpected whitespace inside '{} {}' - remove it if the argument was split by mistake",
+				&stripped[token_start..], next_token.trim()
+			));
+		}
+	}
+	Ok(stripped)
+}
+
+// Remove invisible copy-paste characters only, keeping the user's formatting
+pub(crate) fn strip_invisible(expression: &str) -> String {
+	expression.chars().filter(|c| !INVISIBLE_CHARS.contains(c)).collect()
+}
+
+// The parser's canonical rendering of a policy, descriptor or miniscript (descriptors without checksum)
+// None when the expression does not parse
+pub(crate) fn canonical_expression(expression: &str, is_policy: bool, context: &str) -> Option<String> {
+	use miniscript::{Legacy, Miniscript, Segwitv0, Tap};
+	use miniscript::policy::Concrete;
+
+	if is_policy {
+		return expression.parse::<Concrete<String>>().ok().map(|policy| policy.to_string());
+	}
+	if ["tr(", "wsh(", "sh(", "wpkh(", "pkh("].iter().any(|prefix| expression.starts_with(prefix)) {
+		return canonical_descriptor(expression).ok();
+	}
+	match context {
+		"legacy" => Miniscript::<String, Legacy>::from_str_insane(expression).ok().map(|ms| ms.to_string()),
+		"segwit" => Miniscript::<String, Segwitv0>::from_str_insane(expression).ok().map(|ms| ms.to_string()),
+		_ => Miniscript::<String, Tap>::from_str_insane(expression).ok().map(|ms| ms.to_string()),
+	}
+}
+
+// The parser's rendering of a descriptor, without checksum (an existing checksum is verified)
+pub(crate) fn canonical_descriptor(descriptor: &str) -> Result<String, String> {
+	use miniscript::{Descriptor, DescriptorPublicKey};
+
+	descriptor.parse::<Descriptor<DescriptorPublicKey>>()
+		.map(|descriptor| format!("{:#}", descriptor))
+		.map_err(|e| format!("Invalid descriptor: {}", e))
+}
+
+// Split a trailing `#checksum` off a descriptor, verifying it against the body
+// Returns the body and whether a checksum was present; a mismatch is an error
+pub(crate) fn strip_descriptor_checksum(expression: &str) -> Result<(&str, bool), String> {
+	use miniscript::descriptor::checksum::desc_checksum;
+
+	let Some((body, checksum)) = expression.rsplit_once('#') else {
+		return Ok((expression, false));
+	};
+	let expected = desc_checksum(body).map_err(|e| format!("Invalid descriptor: {}", e))?;
+	if checksum != expected {
+		return Err(format!("Descriptor checksum mismatch: got '{}', expected '{}'", checksum, expected));
+	}
+	Ok((body, true))
+}
+
+// Attach a freshly computed checksum to a descriptor, replacing any existing one
+pub(crate) fn with_fresh_checksum(descriptor: &str) -> String {
+	let body = descriptor.split('#').next().unwrap_or(descriptor);
+	match miniscript::descriptor::checksum::desc_checksum(body) {
+		Ok(checksum) => format!("{}#{}", body, checksum),
+		Err(_) => descriptor.to_string(),
+	}
+}
+
 pub(crate) fn needs_descriptor_processing(expression: &str) -> bool {
 	let trimmed = expression.trim();
 	(trimmed.contains("tpub") || trimmed.contains("xpub") || trimmed.contains("[")) 
@@ -0,0 +1,228 @@
+//! Control block generation for taproot script-path spends
+
+use serde::Serialize;
+use miniscript::Descriptor;
+use miniscript::descriptor::Tr;
+use bitcoin::XOnlyPublicKey;
+use bitcoin::hashes::Hash;
+use bitcoin::taproot::LeafVersion;
+use crate::console_log;
+use crate::taproot::utils::tagged_hash_bytes;
+
+
+/// Control block and leaf data needed to build a script-path spend
+#[derive(Serialize, Debug, Clone)]
+pub struct ControlBlockInfo {
+    pub control_block_hex: String,
+    pub leaf_script_hex: String,
+    pub leaf_version: u8,
+    pub depth: u8,
+}
+
+/// Result wrapper for the WASM control block endpoint
+#[derive(Serialize)]
+pub(crate) struct ControlBlockResult {
+    pub success: bool,
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub info: Option<ControlBlockInfo>,
+}
+
+/// Everything needed to spend one leaf of a taproot tree by its script path
+#[derive(Serialize, Debug, Clone)]
+pub struct TaprootLeafDetail {
+    pub index: usize,
+    pub script_asm: String,
+    pub tapleaf_hash: String,
+    /// Sibling hashes from the leaf up to the merkle root
+    pub merkle_branch: Vec<String>,
+    pub control_block_hex: String,
+}
+
+/// Result wrapper for the WASM leaf details endpoint
+#[derive(Serialize)]
+pub(crate) struct TaprootLeafDetailsResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub leaves: Option<Vec<TaprootLeafDetail>>,
+}
+
+/// Result wrapper for the WASM tapleaf hash endpoint
+#[derive(Serialize)]
+pub(crate) struct TapleafHashResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub tapleaf_hash: Option<String>,
+}
+
+/// One leaf of the PSBT_OUT_TAP_TREE field
+#[derive(Serialize, Debug, Clone)]
+pub struct PsbtTapLeaf {
+    pub depth: u8,
+    pub leaf_version: u8,
+    pub script_hex: String,
+    pub leaf_hash: String,
+}
+
+/// Tapleaf hashes a key signs for, as carried by PSBT_OUT_TAP_BIP32_DERIVATION
+#[derive(Serialize, Debug, Clone)]
+pub struct PsbtTapKeyLeaves {
+    pub key: String,
+    /// Empty for a key that only signs on the key path
+    pub leaf_hashes: Vec<String>,
+}
+
+/// BIP371 taproot output fields of a tr() descriptor
+#[derive(Serialize, Debug, Clone)]
+pub struct PsbtTaprootFields {
+    /// PSBT_OUT_TAP_INTERNAL_KEY
+    pub internal_key: String,
+    /// Merkle root of the script tree (None for a key-only descriptor)
+    pub merkle_root: Option<String>,
+    /// PSBT_OUT_TAP_TREE leaves, in DFS order
+    pub tap_tree: Vec<PsbtTapLeaf>,
+    /// Leaf hashes of each key, internal key first
+    pub key_leaf_hashes: Vec<PsbtTapKeyLeaves>,
+}
+
+/// Result wrapper for the WASM PSBT taproot fields endpoint
+#[derive(Serialize)]
+pub(crate) struct PsbtTaprootFieldsResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub fields: Option<PsbtTaprootFields>,
+}
+
+/// Parse a tr() descriptor, rejecting every other descriptor type
+fn parse_tr(descriptor: &str) -> Result<Tr<XOnlyPublicKey>, String> {
+    let desc: Descriptor<XOnlyPublicKey> = descriptor.trim().parse()
+        .map_err(|e| format!("Failed to parse descriptor: {}", e))?;
+
+    match desc {
+        Descriptor::Tr(tr) => Ok(tr),
+        _ => Err("Not a taproot descriptor".to_string()),
+    }
+}
+
+/// Build the control block for the leaf at `leaf_index` (DFS order) of a tr() descriptor
+pub fn taproot_control_block(descriptor: &str, leaf_index: usize) -> Result<ControlBlockInfo, String> {
+    console_log!("Building control block for leaf {} of: {}", leaf_index, descriptor);
+
+    let tr = parse_tr(descriptor)?;
+
+    let leaves: Vec<_> = tr.iter_scripts().collect();
+    if leaves.is_empty() {
+        return Err("No script paths (key-only descriptor)".to_string());
+    }
+    if leaf_index >= leaves.len() {
+        return Err(format!("Leaf index {} out of range (descriptor has {} leaves)", leaf_index, leaves.len()));
+    }
+
+    let (depth, ms) = leaves[leaf_index];
+    let script = ms.encode();
+    let spend_info = tr.spend_info();
+    let control_block = spend_info
+        .control_block(&(script.clone(), LeafVersion::TapScript))
+        .ok_or_else(|| "Failed to build control block for leaf".to_string())?;
+
+    Ok(ControlBlockInfo {
+        control_block_hex: hex::encode(control_block.serialize()),
+        leaf_script_hex: script.to_hex_string(),
+        leaf_version: LeafVersion::TapScript.to_consensus(),
+        depth,
+    })
+}
+
+/// Tapleaf hash, merkle branch and control block for every leaf (DFS order) of a tr() descriptor
+pub fn taproot_leaf_details(descriptor: &str) -> Result<Vec<TaprootLeafDetail>, String> {
+    console_log!("Collecting leaf details of: {}", descriptor);
+
+    let tr = parse_tr(descriptor)?;
+    let spend_info = tr.spend_info();
+
+    let leaves: Vec<TaprootLeafDetail> = tr.iter_scripts()
+        .enumerate()
+        .map(|(index, (_depth, ms))| {
+            let script = ms.encode();
+            let control_block = spend_info
+                .control_block(&(script.clone(), LeafVersion::TapScript))
+                .ok_or_else(|| format!("Failed to build control block for leaf {}", index))?;
+
+            Ok(TaprootLeafDetail {
+                index,
+                script_asm: script.to_asm_string(),
+                tapleaf_hash: leaf_hash_hex(&script, LeafVersion::TapScript),
+                merkle_branch: control_block.merkle_branch.iter()
+                    .map(|hash| hex::encode(hash.to_byte_array()))
+                    .collect(),
+                control_block_hex: hex::encode(control_block.serialize()),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    if leaves.is_empty() {
+        return Err("No script paths (key-only descriptor)".to_string());
+    }
+    Ok(leaves)
+}
+
+/// BIP341 tapleaf hash of a leaf script: `TapLeaf` tagged hash of version || compact_size(script) || script
+///
+/// `leaf_version` defaults to tapscript (0xc0).
+pub fn tapleaf_hash(script_hex: &str, leaf_version: Option<u8>) -> Result<String, String> {
+    let script_bytes = hex::decode(script_hex.trim())
+        .map_err(|e| format!("Invalid script hex: {}", e))?;
+    let version = match leaf_version {
+        Some(version) => LeafVersion::from_consensus(version)
+            .map_err(|e| format!("Invalid leaf version 0x{:02x}: {}", version, e))?,
+        None => LeafVersion::TapScript,
+    };
+
+    Ok(leaf_hash_hex(&bitcoin::ScriptBuf::from_bytes(script_bytes), version))
+}
+
+/// Hex tapleaf hash of a script, computed with the `TapLeaf` tagged hash
+fn leaf_hash_hex(script: &bitcoin::Script, version: LeafVersion) -> String {
+    // Consensus-encoding a script prefixes its compact_size length
+    let mut data = vec![version.to_consensus()];
+    data.extend(bitcoin::consensus::serialize(&script.to_owned()));
+    hex::encode(tagged_hash_bytes("TapLeaf", &data))
+}
+
+/// Collect the BIP371 PSBT_OUT_TAP_* data of a tr() descriptor: internal key, tree leaves
+/// with their depth, version and hash, and the leaf hashes each key signs for
+pub fn psbt_taproot_fields(descriptor: &str) -> Result<PsbtTaprootFields, String> {
+    console_log!("Collecting PSBT taproot fields of: {}", descriptor);
+
+    let tr = parse_tr(descriptor)?;
+    let internal_key = *tr.internal_key();
+    let mut key_leaf_hashes = vec![PsbtTapKeyLeaves { key: internal_key.to_string(), leaf_hashes: Vec::new() }];
+
+    let tap_tree = tr.iter_scripts()
+        .map(|(depth, ms)| {
+            let script = ms.encode();
+            let leaf_hash = leaf_hash_hex(&script, LeafVersion::TapScript);
+            for key in ms.iter_pk() {
+                let key = key.to_string();
+                match key_leaf_hashes.iter_mut().find(|entry| entry.key == key) {
+                    Some(entry) if entry.leaf_hashes.contains(&leaf_hash) => {}
+                    Some(entry) => entry.leaf_hashes.push(leaf_hash.clone()),
+                    None => key_leaf_hashes.push(PsbtTapKeyLeaves { key, leaf_hashes: vec![leaf_hash.clone()] }),
+                }
+            }
+            PsbtTapLeaf {
+                depth,
+                leaf_version: LeafVersion::TapScript.to_consensus(),
+                script_hex: script.to_hex_string(),
+                leaf_hash,
+            }
+        })
+        .collect();
+
+    Ok(PsbtTaprootFields {
+        internal_key: internal_key.to_string(),
+        merkle_root: tr.spend_info().merkle_root().map(|root| hex::encode(root.to_byte_array())),
+        tap_tree,
+        key_leaf_hashes,
+    })
+}
@@ -3,4 +3,6 @@
 pub mod weights;
 pub mod branches;
 pub mod utils;
+pub mod control;
+pub mod tweak;
 
@@ -0,0 +1,67 @@
+//! Taproot output key tweak details for independent auditing
+
+use serde::Serialize;
+use miniscript::Descriptor;
+use bitcoin::XOnlyPublicKey;
+use bitcoin::hashes::Hash;
+use bitcoin::secp256k1::Scalar;
+use crate::console_log;
+use crate::utils::secp;
+use crate::taproot::utils::tagged_hash_bytes;
+
+
+/// Intermediate values of the BIP341 tweak: `output_key = internal_key + tweak*G`
+#[derive(Serialize, Debug, Clone)]
+pub struct TaprootTweakDetails {
+    pub internal_key: String,
+    /// Absent for key-only descriptors, whose tweak commits to no script tree
+    pub merkle_root: Option<String>,
+    /// `TapTweakHash(internal_key || merkle_root)`, big-endian
+    pub tweak_scalar_hex: String,
+    pub output_key: String,
+}
+
+/// Result wrapper for the WASM tweak details endpoint
+#[derive(Serialize)]
+pub(crate) struct TaprootTweakResult {
+    pub success: bool,
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub details: Option<TaprootTweakDetails>,
+}
+
+/// Compute the tweak of a tr() descriptor from its internal key and merkle root
+pub fn taproot_tweak_details(descriptor: &str) -> Result<TaprootTweakDetails, String> {
+    console_log!("Computing taproot tweak for: {}", descriptor);
+
+    let desc: Descriptor<XOnlyPublicKey> = descriptor.trim().parse()
+        .map_err(|e| format!("Failed to parse descriptor: {}", e))?;
+    let tr = match desc {
+        Descriptor::Tr(tr) => tr,
+        _ => return Err("Not a taproot descriptor".to_string()),
+    };
+
+    let internal_key = *tr.internal_key();
+    let merkle_root = tr.spend_info().merkle_root();
+    let mut data = internal_key.serialize().to_vec();
+    if let Some(root) = merkle_root {
+        data.extend_from_slice(root.as_byte_array());
+    }
+    let tweak = tagged_hash_bytes("TapTweak", &data);
+    let scalar = Scalar::from_be_bytes(tweak)
+        .map_err(|_| "Taproot tweak is out of range".to_string())?;
+
+    // Apply the tweak by hand so the reported scalar is the one that produced the key
+    let (output_key, _parity) = internal_key.add_tweak(secp(), &scalar)
+        .map_err(|e| format!("Failed to apply taproot tweak: {}", e))?;
+    if output_key != tr.spend_info().output_key().to_x_only_public_key() {
+        return Err("Tweaked key does not match the descriptor's output key".to_string());
+    }
+
+    Ok(TaprootTweakDetails {
+        internal_key: internal_key.to_string(),
+        merkle_root: merkle_root.map(|root| hex::encode(root.to_byte_array())),
+        tweak_scalar_hex: hex::encode(tweak),
+        output_key: output_key.to_string(),
+    })
+}
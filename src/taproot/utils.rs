@@ -1,14 +1,212 @@
 //! Utility functions
 
-use bitcoin::XOnlyPublicKey;
+use bitcoin::{TapNodeHash, XOnlyPublicKey};
+use miniscript::descriptor::{DescriptorPublicKey, SinglePub, SinglePubKey, TapTree, Tr};
+use lazy_static::lazy_static;
+use serde::Serialize;
+
+/// Deepest taproot tree a control block can commit to (BIP341: 128 path hashes)
+pub const MAX_TAPROOT_TREE_DEPTH: usize = 128;
+
+/// Leaf count above which a taproot tree is flagged as impractically wide
+pub const LARGE_TAPROOT_TREE_LEAVES: usize = 256;
+
+/// Leaf script size above which relay policy treats a tapscript as non-standard
+pub const MAX_STANDARD_TAPLEAF_SIZE: usize = 10_000;
+
+/// Most witness stack elements a script may start with (consensus MAX_STACK_SIZE)
+pub const MAX_TAPSCRIPT_STACK_ELEMENTS: usize = 1000;
+
 /// Standard NUMS point for taproot (unspendable key)
 pub const NUMS_POINT: &str = "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
 
+lazy_static! {
+    /// NUMS point parsed once; the same point is valid on every network
+    static ref NUMS_XONLY: XOnlyPublicKey = NUMS_POINT.parse().expect("Valid NUMS point");
+}
+
 /// Get the Taproot NUMS (Nothing Up My Sleeve) point for unspendable key-path
-pub(crate) fn get_taproot_nums_point() -> XOnlyPublicKey {
-    // Standard NUMS point used in Taproot when key-path spending should be disabled
-    let nums_bytes = hex::decode(NUMS_POINT).expect("Valid NUMS hex");
-    XOnlyPublicKey::from_slice(&nums_bytes).expect("Valid NUMS point")
+pub fn get_nums_point() -> XOnlyPublicKey {
+    *NUMS_XONLY
+}
+
+/// The NUMS point as a descriptor key, for `compile_tr` and descriptor templates
+pub fn get_nums_descriptor_key() -> DescriptorPublicKey {
+    DescriptorPublicKey::Single(SinglePub { origin: None, key: SinglePubKey::XOnly(get_nums_point()) })
+}
+
+/// Result wrapper for the WASM tagged hash endpoint
+#[derive(Serialize)]
+pub(crate) struct TaggedHashResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub hash: Option<String>,
 }
 
+/// BIP340 tagged hash: `SHA256(SHA256(tag) || SHA256(tag) || data)`
+pub(crate) fn tagged_hash_bytes(tag: &str, data: &[u8]) -> [u8; 32] {
+    use bitcoin::hashes::{sha256, Hash, HashEngine};
+
+    let tag_hash = sha256::Hash::hash(tag.as_bytes());
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag_hash.as_byte_array());
+    engine.input(tag_hash.as_byte_array());
+    engine.input(data);
+    sha256::Hash::from_engine(engine).to_byte_array()
+}
+
+/// BIP340 tagged hash of hex data under `tag` (e.g. "TapLeaf", "TapBranch", "TapTweak"), as hex
+pub fn tagged_hash(tag: &str, data_hex: &str) -> Result<String, String> {
+    let data = hex::decode(data_hex.trim())
+        .map_err(|e| format!("Invalid data hex: {}", e))?;
+    Ok(hex::encode(tagged_hash_bytes(tag, &data)))
+}
+
+/// Derive a provably unspendable internal key unique to a script tree (BIP341)
+///
+/// The key is `H + rG`, where `H` is the NUMS point and `r = SHA256(H || merkle_root)`.
+/// Nobody knows the discrete log of `H`, so nobody knows it for `H + rG` either, and
+/// anyone holding the tree can recompute `r` to check the key. Unlike the bare NUMS
+/// point, each tree yields its own key, so script-only outputs are not linkable
+/// through a shared internal key.
+pub fn derive_unspendable_internal_key(merkle_root: TapNodeHash) -> Result<XOnlyPublicKey, String> {
+    use bitcoin::hashes::{sha256, Hash};
+    use bitcoin::secp256k1::Scalar;
+
+    let nums = get_nums_point();
+    let mut preimage = nums.serialize().to_vec();
+    preimage.extend_from_slice(merkle_root.as_byte_array());
+    let r = Scalar::from_be_bytes(sha256::Hash::hash(&preimage).to_byte_array())
+        .map_err(|_| "Derived tweak is out of range".to_string())?;
+
+    let (key, _parity) = nums.add_tweak(crate::utils::secp(), &r)
+        .map_err(|e| format!("Failed to derive unspendable internal key: {}", e))?;
+    Ok(key)
+}
+
+/// Unique unspendable internal key for a tree, taken from the tree's merkle root
+pub(crate) fn unspendable_key_for_tree(tree: &TapTree<XOnlyPublicKey>) -> Result<XOnlyPublicKey, String> {
+    // The merkle root does not depend on the internal key, so NUMS stands in while computing it
+    let descriptor = Tr::new(get_nums_point(), Some(tree.clone()))
+        .map_err(|e| format!("Failed to build taproot tree: {}", e))?;
+    let merkle_root = descriptor.spend_info().merkle_root()
+        .ok_or("Taproot tree has no merkle root")?;
+    derive_unspendable_internal_key(merkle_root)
+}
 
+/// Whether an internal key is the NUMS point or the unique key derived from the tree's merkle root
+pub(crate) fn is_unspendable_internal_key(tr: &Tr<XOnlyPublicKey>) -> bool {
+    let internal_key = *tr.internal_key();
+    internal_key == get_nums_point()
+        || tr.spend_info().merkle_root()
+            .and_then(|root| derive_unspendable_internal_key(root).ok())
+            .is_some_and(|derived| derived == internal_key)
+}
+
+/// Check a taproot tree's shape before it is committed to a descriptor
+///
+/// Errors when the tree is deeper than a control block allows and returns a
+/// warning when it has so many leaves that building or spending it is impractical.
+pub fn validate_tap_tree<Pk: miniscript::MiniscriptKey>(tree: &TapTree<Pk>) -> Result<Option<String>, String> {
+    let depth = tree.height();
+    if depth > MAX_TAPROOT_TREE_DEPTH {
+        return Err(format!(
+            "Taproot tree depth {} exceeds the maximum of {} (control block limit)",
+            depth, MAX_TAPROOT_TREE_DEPTH
+        ));
+    }
+
+    let leaves = tree.iter().count();
+    if leaves > LARGE_TAPROOT_TREE_LEAVES {
+        Ok(Some(format!(
+            "taproot tree has {} leaves (more than {}) — it may be impractical to build and spend",
+            leaves, LARGE_TAPROOT_TREE_LEAVES
+        )))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Warn about a compiled tr() descriptor's tree: too many leaves, or leaves past per-script limits
+pub(crate) fn tap_tree_warnings(descriptor: &str) -> Vec<String> {
+    use miniscript::Descriptor;
+
+    let Ok(Descriptor::Tr(tr)) = descriptor.parse::<Descriptor<XOnlyPublicKey>>() else {
+        return Vec::new();
+    };
+    let Some(tree) = tr.tap_tree() else {
+        return Vec::new();
+    };
+
+    let mut warnings: Vec<String> = validate_tap_tree(tree).ok().flatten().into_iter().collect();
+    for (_, leaf) in tree.iter() {
+        warnings.extend(tapleaf_limit_warnings(leaf));
+    }
+    warnings
+}
+
+/// Warn when a single tapleaf would be non-standard or exceed the witness stack limit
+///
+/// Tapscript drops the 201-opcode limit, and its signature-operation budget (50 plus the
+/// witness size) is always met by a miniscript satisfaction: only non-empty signatures
+/// spend from it, and each adds at least 64 bytes to the witness. That leaves the script
+/// size and the number of witness elements as the per-leaf limits to check.
+pub fn tapleaf_limit_warnings<Pk: miniscript::MiniscriptKey>(leaf: &miniscript::Miniscript<Pk, miniscript::Tap>) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let script_size = leaf.script_size();
+    if script_size > MAX_STANDARD_TAPLEAF_SIZE {
+        warnings.push(format!(
+            "tapleaf {} is {} bytes, above the {}-byte standardness limit — it may not relay",
+            leaf_label(leaf), script_size, MAX_STANDARD_TAPLEAF_SIZE
+        ));
+    }
+
+    if let Ok(elements) = leaf.max_satisfaction_witness_elements() {
+        if elements > MAX_TAPSCRIPT_STACK_ELEMENTS {
+            warnings.push(format!(
+                "tapleaf {} needs up to {} witness elements, above the limit of {}",
+                leaf_label(leaf), elements, MAX_TAPSCRIPT_STACK_ELEMENTS
+            ));
+        }
+    }
+    warnings
+}
+
+// Short form of a leaf for messages; large leaves are truncated
+fn leaf_label<Pk: miniscript::MiniscriptKey>(leaf: &miniscript::Miniscript<Pk, miniscript::Tap>) -> String {
+    let text = leaf.to_string();
+    match text.char_indices().nth(40) {
+        Some((cut, _)) => format!("{}...", &text[..cut]),
+        None => text,
+    }
+}
+
+/// Parse a NUMS key option, reusing the cached point for the standard NUMS hex
+pub(crate) fn parse_nums_key(nums_key: &str) -> Result<XOnlyPublicKey, String> {
+    if nums_key == NUMS_POINT {
+        return Ok(get_nums_point());
+    }
+    nums_key.parse().map_err(|_| format!("Failed to parse NUMS key: {}", nums_key))
+}
+
+/// Warn when a tr() descriptor's internal key also appears inside a leaf script
+pub(crate) fn internal_key_reuse_warning(descriptor: &str) -> Option<String> {
+    use miniscript::Descriptor;
+
+    let descriptor = descriptor.parse::<Descriptor<XOnlyPublicKey>>().ok()?;
+    let Descriptor::Tr(tr) = descriptor else {
+        return None;
+    };
+
+    let internal_key = *tr.internal_key();
+    let reused = tr.iter_scripts().any(|(_, ms)| ms.iter_pk().any(|pk| pk == internal_key));
+    if reused {
+        Some(format!(
+            "internal key {} is also used in a script leaf — this links the key-path and script-path spends",
+            internal_key
+        ))
+    } else {
+        None
+    }
+}
@@ -2,6 +2,7 @@
 
 use miniscript::{Miniscript, Tap};
 use bitcoin::XOnlyPublicKey;
+use serde::Serialize;
 
 /// Compute Taproot witness weight breakdown for display
 pub(crate) fn taproot_witness_breakdown(ms: &Miniscript<XOnlyPublicKey, Tap>, leaf_script_len: usize, _depth: usize) -> (u64, u64, u64, u64) {
@@ -42,3 +43,172 @@ pub(crate) fn taproot_witness_breakdown(ms: &Miniscript<XOnlyPublicKey, Tap>, le
 
 	(sig_wu, script_wu, control_wu, total_wu)
 }
+
+/// Key-path witness: stack count + length prefix + 65-byte signature (worst-case sighash byte)
+const KEY_PATH_WITNESS_WU: u64 = 1 + 1 + 65;
+
+/// Spend weights of one taproot compile mode
+#[derive(Serialize, Debug, Clone)]
+pub struct TaprootModeWeight {
+	pub mode: String,
+	pub descriptor: String,
+	/// Witness weight of the most expensive spending path
+	pub worst_case_weight: u64,
+	/// Witness weight of the cheapest spending path
+	pub best_case_weight: u64,
+	/// "key-path" or the leaf miniscript of the cheapest path
+	pub best_case_path: String,
+}
+
+/// Result wrapper for the WASM mode weights endpoint
+#[derive(Serialize)]
+pub(crate) struct TaprootModeWeightsResult {
+	pub success: bool,
+	pub error: Option<String>,
+	pub modes: Option<Vec<TaprootModeWeight>>,
+}
+
+/// Compile the expression in single-leaf, multi-leaf and script-path modes and report spend weights for each
+pub fn taproot_mode_weights(expression: &str) -> Result<Vec<TaprootModeWeight>, String> {
+	use crate::compile::engine::compile_unified;
+	use crate::compile::options::CompileOptions;
+
+	["single-leaf", "multi-leaf", "script-path"].iter().map(|mode| {
+		// Policies and miniscripts share the entry point; fall back to policy when miniscript fails
+		let result = compile_unified(expression, CompileOptions::for_miniscript("taproot", Some(mode), None, None)?)
+			.ok()
+			.filter(|r| r.success)
+			.map(Ok)
+			.unwrap_or_else(|| compile_unified(expression, CompileOptions::for_policy("taproot", Some(mode), None)?))?;
+		if !result.success {
+			return Err(format!("{} compilation failed: {}", mode, result.error.unwrap_or_default()));
+		}
+		let descriptor = result.compiled_miniscript
+			.ok_or_else(|| format!("{} compilation returned no descriptor", mode))?;
+		let descriptor = descriptor.split("|LEAF_ASM:").next().unwrap_or_default().to_string();
+		// Single-leaf policy compilation reports the bare leaf, which sits under the NUMS key
+		let descriptor = if descriptor.starts_with("tr(") {
+			descriptor
+		} else {
+			format!("tr({},{})", crate::taproot::utils::NUMS_POINT, descriptor)
+		};
+		let (worst_case_weight, best_case_weight, best_case_path) = descriptor_spend_weights(&descriptor)?;
+		Ok(TaprootModeWeight {
+			mode: mode.to_string(),
+			descriptor,
+			worst_case_weight,
+			best_case_weight,
+			best_case_path,
+		})
+	}).collect()
+}
+
+/// Worst and best witness weight over the key path (when spendable) and every script leaf
+/// A leaf's worst case uses its largest satisfaction, its best case the smallest one
+pub(crate) fn descriptor_spend_weights(descriptor: &str) -> Result<(u64, u64, String), String> {
+	use miniscript::Descriptor;
+	use miniscript::miniscript::satisfy::Witness;
+
+	let desc: Descriptor<XOnlyPublicKey> = descriptor.trim().parse()
+		.map_err(|e| format!("Failed to parse descriptor: {}", e))?;
+	let tr = match desc {
+		Descriptor::Tr(tr) => tr,
+		_ => return Err("Not a taproot descriptor".to_string()),
+	};
+
+	// (worst-case weight, best-case weight, path)
+	let mut paths: Vec<(u64, u64, String)> = Vec::new();
+	if !crate::taproot::utils::is_unspendable_internal_key(&tr) {
+		paths.push((KEY_PATH_WITNESS_WU, KEY_PATH_WITNESS_WU, "key-path".to_string()));
+	}
+	for (depth, ms) in tr.iter_scripts() {
+		let sat_size = ms.max_satisfaction_size()
+			.map_err(|e| format!("Leaf {} is not satisfiable: {}", ms, e))? as u64;
+		let sat_elems = ms.max_satisfaction_witness_elements()
+			.map_err(|e| format!("Leaf {} is not satisfiable: {}", ms, e))? as u64;
+		let script_len = ms.script_size() as u64;
+		let worst = leaf_witness_weight(sat_elems, sat_size, script_len, depth);
+		// With every asset available the satisfier picks the smallest satisfaction
+		let best = match ms.build_template(&AllAssets).stack {
+			Witness::Stack(items) => leaf_witness_weight(
+				items.len() as u64,
+				items.iter().map(placeholder_size).sum(),
+				script_len,
+				depth,
+			),
+			Witness::Unavailable | Witness::Impossible => worst,
+		};
+		paths.push((worst, best, ms.to_string()));
+	}
+
+	let worst = paths.iter().map(|(w, _, _)| *w).max()
+		.ok_or_else(|| "Descriptor has no spending path".to_string())?;
+	let (_, best, best_path) = paths.into_iter().min_by_key(|(_, b, _)| *b)
+		.ok_or_else(|| "Descriptor has no spending path".to_string())?;
+	Ok((worst, best, best_path))
+}
+
+/// Script-path witness weight: item count, satisfaction items, leaf script and control block
+pub(crate) fn leaf_witness_weight(sat_elems: u64, sat_size: u64, script_len: u64, depth: u8) -> u64 {
+	use bitcoin::VarInt;
+
+	let control_len = 33 + 32 * depth as u64;
+	VarInt(sat_elems + 2).size() as u64
+		+ sat_size
+		+ VarInt(script_len).size() as u64 + script_len
+		+ VarInt(control_len).size() as u64 + control_len
+}
+
+// Serialized size of one tapscript witness item, length prefix included
+// Uses the same sizes as rust-miniscript's max_satisfaction_size, so best and worst cases compare
+fn placeholder_size(item: &miniscript::miniscript::satisfy::Placeholder<XOnlyPublicKey>) -> u64 {
+	use miniscript::miniscript::satisfy::Placeholder;
+
+	match item {
+		Placeholder::Pubkey(_, size) | Placeholder::PubkeyHash(_, size) => *size as u64,
+		Placeholder::EcdsaSigPk(_) | Placeholder::EcdsaSigPkHash(_) => 73,
+		Placeholder::SchnorrSigPk(_, _, size) | Placeholder::SchnorrSigPkHash(_, _, size) => *size as u64 + 1,
+		Placeholder::HashDissatisfaction
+		| Placeholder::Sha256Preimage(_)
+		| Placeholder::Hash256Preimage(_)
+		| Placeholder::Ripemd160Preimage(_)
+		| Placeholder::Hash160Preimage(_) => 33,
+		Placeholder::PushOne => 2,
+		Placeholder::PushZero => 1,
+		Placeholder::TapScript(script) => script.len() as u64,
+		Placeholder::TapControlBlock(control) => control.serialize().len() as u64,
+	}
+}
+
+// Asset provider that can satisfy everything, with worst-case (65-byte) Schnorr signatures
+struct AllAssets;
+
+impl miniscript::plan::AssetProvider<XOnlyPublicKey> for AllAssets {
+	fn provider_lookup_tap_leaf_script_sig(&self, _: &XOnlyPublicKey, _: &bitcoin::taproot::TapLeafHash) -> Option<usize> {
+		Some(65)
+	}
+
+	fn provider_lookup_sha256(&self, _: &miniscript::bitcoin::hashes::sha256::Hash) -> bool {
+		true
+	}
+
+	fn provider_lookup_hash256(&self, _: &miniscript::hash256::Hash) -> bool {
+		true
+	}
+
+	fn provider_lookup_ripemd160(&self, _: &miniscript::bitcoin::hashes::ripemd160::Hash) -> bool {
+		true
+	}
+
+	fn provider_lookup_hash160(&self, _: &miniscript::bitcoin::hashes::hash160::Hash) -> bool {
+		true
+	}
+
+	fn check_older(&self, _: bitcoin::relative::LockTime) -> bool {
+		true
+	}
+
+	fn check_after(&self, _: bitcoin::absolute::LockTime) -> bool {
+		true
+	}
+}
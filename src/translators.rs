@@ -77,3 +77,38 @@ impl Translator<DescriptorPublicKey, XOnlyPublicKey, ()> for XOnlyDescriptorKeyT
 }
 
 
+
+/// Translator replacing placeholder key names with descriptor keys from a key map
+pub struct NamedKeyTranslator<'a> {
+    keys: &'a std::collections::HashMap<String, DescriptorPublicKey>,
+}
+
+impl<'a> NamedKeyTranslator<'a> {
+    pub fn new(keys: &'a std::collections::HashMap<String, DescriptorPublicKey>) -> Self {
+        Self { keys }
+    }
+}
+
+impl Translator<String, DescriptorPublicKey, String> for NamedKeyTranslator<'_> {
+    fn pk(&mut self, pk: &String) -> Result<DescriptorPublicKey, String> {
+        self.keys.get(pk)
+            .cloned()
+            .ok_or_else(|| format!("No key provided for '{}'", pk))
+    }
+
+    fn sha256(&mut self, hash: &String) -> Result<<DescriptorPublicKey as MiniscriptKey>::Sha256, String> {
+        hash.parse().map_err(|e| format!("Invalid sha256 hash '{}': {}", hash, e))
+    }
+
+    fn hash256(&mut self, hash: &String) -> Result<<DescriptorPublicKey as MiniscriptKey>::Hash256, String> {
+        hash.parse().map_err(|e| format!("Invalid hash256 hash '{}': {}", hash, e))
+    }
+
+    fn ripemd160(&mut self, hash: &String) -> Result<<DescriptorPublicKey as MiniscriptKey>::Ripemd160, String> {
+        hash.parse().map_err(|e| format!("Invalid ripemd160 hash '{}': {}", hash, e))
+    }
+
+    fn hash160(&mut self, hash: &String) -> Result<<DescriptorPublicKey as MiniscriptKey>::Hash160, String> {
+        hash.parse().map_err(|e| format!("Invalid hash160 hash '{}': {}", hash, e))
+    }
+}
@@ -1,6 +1,7 @@
 //! Data structures for the miniscript compiler
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use bitcoin::bip32::{Xpub, DerivationPath, Fingerprint};
 
 
@@ -23,6 +24,106 @@ pub struct CompilationResult {
     pub debug_info: Option<DebugInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub debug_info_leaves: Option<Vec<LeafDebugInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warnings: Option<Vec<String>>,
+    /// Whether the descriptor keys contain a wildcard (needs a range on import)
+    pub is_ranged: Option<bool>,
+    /// Number of `*` wildcards across the descriptor keys
+    pub derivation_wildcards: Option<usize>,
+    /// Friendly output type name, e.g. "P2WSH" or "P2TR (key+script)"
+    pub output_descriptor_type: Option<String>,
+    /// Short description of recognizable multisig/timelock shapes
+    pub output_summary: Option<String>,
+    /// Taproot internal key origin: "NUMS", "NUMS-derived", "extracted" or "user-provided" (None for other script types)
+    pub internal_key_source: Option<String>,
+    /// Output script for Legacy (P2SH) and Segwit (P2WSH); `script` holds the redeem/witness script
+    pub script_pubkey_hex: Option<String>,
+    /// Milliseconds spent per compile stage, when `CompileOptions.timing` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings: Option<HashMap<String, f64>>,
+    /// Structure of the compiled descriptor, so callers need not re-parse `compiled_miniscript`
+    pub descriptor_info: Option<CompiledDescriptorInfo>,
+    /// Taproot only: the `{...}` script tree of the compiled descriptor, as built (None for a single leaf)
+    pub tree_braces: Option<String>,
+    /// For `<0;1>` multipath descriptors: the first receive (`/0/0`) and change (`/1/0`) addresses
+    pub receive_change_addresses: Option<ReceiveChangeAddresses>,
+    /// Bytes of the output script locking the funds (e.g. 34 for P2WSH and P2TR)
+    pub script_pubkey_bytes: Option<usize>,
+    /// Segwit only: bytes of the witness script revealed when spending (`script_size` for P2WSH)
+    pub witness_script_bytes: Option<usize>,
+    /// Virtual size of the whole input (outpoint, sequence, scriptSig and witness) on its costliest spend
+    pub worst_case_spend_vbytes: Option<u64>,
+    /// The input as entered, with invisible copy-paste characters removed but formatting kept
+    pub original_expression: Option<String>,
+    /// The parser's canonical form of the compiled input, for diffing against `original_expression`
+    pub normalized_expression: Option<String>,
+    /// Policy compiled for taproot: each tree leaf's miniscript, in tree order
+    pub tap_leaf_miniscripts: Option<Vec<String>>,
+    /// Segwit only: sha256 of the witness script, the witness program of the P2WSH output
+    pub witness_script_hash: Option<String>,
+    /// Nested P2SH-P2WSH only: hash160 of the redeem script, the hash in the P2SH output
+    pub redeem_script_hash: Option<String>,
+}
+
+impl CompilationResult {
+    /// A failed compilation carrying only its error message
+    pub fn failure(error: impl Into<String>) -> Self {
+        CompilationResult {
+            success: false,
+            error: Some(error.into()),
+            script: None,
+            script_asm: None,
+            address: None,
+            script_size: None,
+            miniscript_type: None,
+            compiled_miniscript: None,
+            max_satisfaction_size: None,
+            max_weight_to_satisfy: None,
+            sanity_check: None,
+            is_non_malleable: None,
+            debug_info: None,
+            debug_info_leaves: None,
+            warnings: None,
+            is_ranged: None,
+            derivation_wildcards: None,
+            output_descriptor_type: None,
+            output_summary: None,
+            internal_key_source: None,
+            script_pubkey_hex: None,
+            timings: None,
+            descriptor_info: None,
+            tree_braces: None,
+            receive_change_addresses: None,
+            script_pubkey_bytes: None,
+            witness_script_bytes: None,
+            worst_case_spend_vbytes: None,
+            original_expression: None,
+            normalized_expression: None,
+            tap_leaf_miniscripts: None,
+            witness_script_hash: None,
+            redeem_script_hash: None,
+        }
+    }
+}
+
+/// First receive and change addresses of a multipath descriptor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiveChangeAddresses {
+    pub receive: String,
+    pub change: String,
+}
+
+/// Attributes of a compiled output descriptor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompiledDescriptorInfo {
+    /// Top-level descriptor kind: "bare", "pkh", "wpkh", "sh", "sh-wpkh", "sh-wsh", "wsh" or "tr"
+    pub kind: String,
+    /// Taproot internal key, absent for other kinds
+    pub internal_key: Option<String>,
+    pub has_tree: bool,
+    /// Number of taproot script leaves (0 without a tree)
+    pub leaf_count: usize,
+    pub is_ranged: bool,
 }
 
 /// Debug information for verbose mode
@@ -78,6 +179,16 @@ pub struct ExtendedProperties {
     pub max_dissat_size: Option<(usize, usize)>,
 }
 
+/// Result of type-checking a standalone miniscript fragment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FragmentTypeCheckResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub annotated_expression: Option<String>,
+    pub type_properties: Option<TypeProperties>,
+    pub extended_properties: Option<ExtendedProperties>,
+}
+
 /// Result structure for lift operations
 #[derive(Serialize, Deserialize)]
 pub struct LiftResult {
@@ -85,6 +196,31 @@ pub struct LiftResult {
     pub error: Option<String>,
     pub miniscript: Option<String>,
     pub policy: Option<String>,
+    /// Whether lifting succeeded in any context (set by the lift endpoints)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub liftable: Option<bool>,
+    /// Per-context failures when nothing could be lifted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<Vec<LiftAttempt>>,
+    /// Caveats about a successful lift (e.g. keys only known by their hash)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warnings: Option<Vec<String>>,
+    /// Whether the lifted miniscript re-encodes to exactly the original script
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_roundtrip: Option<bool>,
+    /// Whether the script uses a non-minimal push or number encoding (set when lifting a script)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub non_minimal: Option<bool>,
+}
+
+/// One failed lift attempt in a single script context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiftAttempt {
+    pub context: String,
+    /// Error from the non-standard (insane) parser, when it was tried
+    pub parse_insane_error: Option<String>,
+    /// Error from the standard parser
+    pub parse_error: Option<String>,
 }
 
 /// Result structure for address generation
@@ -96,6 +232,68 @@ pub struct AddressResult {
 }
 
 
+/// Result of compiling an expression and comparing its address with an expected one
+#[derive(Serialize, Deserialize)]
+pub struct AddressAssertionResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub matches: bool,
+    pub actual: Option<String>,
+    pub expected: String,
+    /// Why the addresses differ when they encode the same script for different networks
+    pub mismatch: Option<String>,
+}
+
+/// Derived keys previewed for a single HD descriptor
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DescriptorKeyPreview {
+    pub descriptor: String,
+    pub fingerprint: String,
+    pub keys: Vec<String>,
+}
+
+/// Result structure for previewing derived descriptor keys
+#[derive(Serialize, Deserialize)]
+pub struct DescriptorPreviewResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub previews: Vec<DescriptorKeyPreview>,
+}
+/// Result structure for descriptor id computation
+#[derive(Serialize, Deserialize)]
+pub struct DescriptorIdResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub id: Option<String>,
+}
+/// Result structure for scriptPubKey derivation at an HD index
+#[derive(Serialize, Deserialize)]
+pub struct ScriptPubKeyResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub script_hex: Option<String>,
+    pub address: Option<String>,
+}
+
+/// Result structure for comparing two descriptors' outputs
+#[derive(Serialize, Deserialize)]
+pub struct DescriptorEquivalenceResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub equivalent: Option<bool>,
+    pub diverging_index: Option<u32>,
+}
+
+/// Result structure for matching a script against a known address
+#[derive(Serialize, Deserialize)]
+pub struct AddressScriptMatchResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub matches: Option<bool>,
+    pub output_type: Option<String>,
+}
+
+
 /// Information about a parsed HD wallet descriptor
 #[derive(Debug, Clone)]
 pub struct DescriptorInfo {
@@ -152,8 +350,21 @@ pub struct AnalysisResult {
     /// Warnings (e.g., trivially satisfiable, unsatisfiable, etc.)
     pub warnings: Option<Vec<String>>,
 
+    /// True when a thresh had too many combinations to enumerate and was summarized
+    pub paths_truncated: Option<bool>,
+
     /// Source type: "miniscript" or "policy"
     pub source: Option<String>,
+
+    /// Output type of an unwrapped sh()/wsh() descriptor, e.g. "P2SH-P2WSH (nested segwit)"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_type: Option<String>,
+
+    /// The input as entered, before canonicalization
+    pub original_expression: Option<String>,
+
+    /// The parser's canonical form of the input
+    pub normalized_expression: Option<String>,
 }
 
 /// Tree node for policy visualization
@@ -177,6 +388,14 @@ pub struct PolicyTreeNode {
 
     /// Child nodes
     pub children: Vec<PolicyTreeNode>,
+
+    /// For after/older nodes: the decoded lock, e.g. "block 800000", "1/1/2025" or "~3 days"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub human: Option<String>,
+
+    /// For after/older nodes: "height" or "time"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_kind: Option<String>,
 }
 
 impl PolicyTreeNode {
@@ -232,6 +451,9 @@ pub struct KeyAnalysis {
     pub min_signatures: Option<usize>,
     /// Max signatures needed across all paths
     pub max_signatures: Option<usize>,
+    /// Taproot only: x-only and compressed forms of each entry in `unique_keys`, in the same order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_forms: Option<Vec<String>>,
 }
 
 /// Complexity analysis
@@ -257,6 +479,8 @@ pub struct TimelockAnalysis {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimelockInfo {
     pub value: u32,
+    /// Consensus rule the timelock opcode enforces and the transaction fields a spend must set
+    pub consensus_rule: String,
 }
 
 /// Hashlock analysis information
@@ -266,6 +490,10 @@ pub struct HashlockAnalysis {
     pub hash256_count: usize,
     pub ripemd160_count: usize,
     pub hash160_count: usize,
+    /// Distinct hash locks, formatted as fragment(hash)
+    pub distinct_hashes: Vec<String>,
+    /// Hash locks required more than once within a single spending path
+    pub redundant_hashes: Vec<String>,
 }
 
 /// Security analysis information
@@ -277,6 +505,8 @@ pub struct SecurityAnalysis {
     pub within_resource_limits: bool,
     pub passes_sanity_check: bool,
     pub is_safe: bool,
+    /// Overall rating: "safe", "caution" or "dangerous" (see analyze::risk_level)
+    pub risk_level: String,
 }
 
 /// Size and weight analysis information
@@ -313,4 +543,152 @@ pub struct SpendingPathGroup {
     /// Nested groups for nested or() structures
     #[serde(skip_serializing_if = "Option::is_none")]
     pub children: Option<Vec<SpendingPathGroup>>,
-}
\ No newline at end of file
+
+    /// Share of the policy's top-level `or` weights, as a percentage (policy input with weights requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight_percent: Option<f64>,
+}
+/// Availability of a miniscript fragment or wrapper per script context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FragmentSupport {
+    /// Fragment name (e.g., "multi_a") or wrapper letter (e.g., "v")
+    pub name: String,
+
+    /// "fragment" or "wrapper"
+    pub kind: String,
+
+    /// Usable in Legacy (P2SH)
+    pub legacy: bool,
+
+    /// Usable in Segwit v0 (P2WSH)
+    pub segwit: bool,
+
+    /// Usable in Taproot (tapscript leaves)
+    pub taproot: bool,
+}
+
+/// Spending paths a single key participates in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyPathEntry {
+    /// Key as written in the expression
+    pub key: String,
+
+    /// 1-based path numbers, matching the "Path N" labels of spending_paths
+    pub paths: Vec<usize>,
+}
+
+/// Result of the key/path matrix endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyPathMatrixResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub keys: Option<Vec<KeyPathEntry>>,
+}
+
+/// Availability of one spending path for a given set of keys and preimages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathSatisfiability {
+    /// 1-based path number, matching the "Path N" labels of spending_paths
+    pub path: usize,
+
+    /// Conditions of the path as listed by the analysis
+    pub conditions: Vec<String>,
+
+    /// Conditions that cannot be met with the available keys and preimages
+    pub missing: Vec<String>,
+}
+
+/// Result of the satisfiable paths endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SatisfiablePathsResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub satisfiable: Option<Vec<PathSatisfiability>>,
+    pub unsatisfiable: Option<Vec<PathSatisfiability>>,
+}
+
+/// One condition of a spending path, typed for tree rendering
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathCondition {
+    /// "signature", "after", "older", "sha256", "hash256", "ripemd160", "hash160",
+    /// "trivial" or "threshold_summary"
+    pub kind: String,
+
+    /// The condition as worded in spending_paths, e.g. "wait 144 blocks"
+    pub detail: String,
+
+    /// Signing key, for signatures
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+
+    /// Consensus value, for timelocks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<u32>,
+
+    /// Full digest, for hashlocks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+/// A spending path as a list of typed conditions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredPath {
+    pub conditions: Vec<PathCondition>,
+
+    /// Whether the path needs at least one signature
+    pub requires_signature: bool,
+
+    /// Weight of the scriptSig and witness in weight units, when the path can be planned
+    pub witness_weight: Option<u64>,
+}
+
+/// Result of the structured spending paths endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredPathsResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub paths: Option<Vec<StructuredPath>>,
+}
+
+/// One output of an expanded combo() descriptor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComboOutput {
+    /// "P2PK", "P2PKH", "P2WPKH" or "P2SH-P2WPKH"
+    pub output_type: String,
+    pub script_hex: String,
+    /// None for P2PK, which has no address form
+    pub address: Option<String>,
+}
+
+/// Result of expanding a combo() descriptor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComboOutputsResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub outputs: Option<Vec<ComboOutput>>,
+}
+
+/// Result of the taproot descriptor standardness check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaprootStandardnessResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub standard: Option<bool>,
+    pub warnings: Option<Vec<String>>,
+}
+
+/// Result of the contract summary endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractSummaryResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub summary: Option<String>,
+}
+
+/// Result of the spending path count estimate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathCountResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub path_count: Option<u64>,
+}
@@ -1,6 +1,19 @@
 //! Utility functions and macros for the miniscript compiler
 
 use wasm_bindgen::prelude::*;
+use bitcoin::secp256k1::{Secp256k1, VerifyOnly};
+use lazy_static::lazy_static;
+
+
+lazy_static! {
+    /// Shared verification-only secp256k1 context used for key derivation and taproot tweaking
+    static ref SECP: Secp256k1<VerifyOnly> = Secp256k1::verification_only();
+}
+
+/// Get the shared secp256k1 verification context
+pub(crate) fn secp() -> &'static Secp256k1<VerifyOnly> {
+    &SECP
+}
 
 
 #[wasm_bindgen]
@@ -24,4 +37,19 @@ macro_rules! console_log {
         // No-op for native Rust tests
         ()
     }
-}
\ No newline at end of file
+}
+
+/// Current time in milliseconds: `Date.now()` in the browser, the system clock natively
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+/// Current time in milliseconds: `Date.now()` in the browser, the system clock natively
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now_ms() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs_f64() * 1000.0)
+        .unwrap_or(0.0)
+}
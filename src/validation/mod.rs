@@ -5,6 +5,127 @@
 
 use miniscript::{Miniscript, Legacy, Segwitv0, Tap, DescriptorPublicKey, Descriptor, ScriptContext};
 use std::str::FromStr;
+use crate::types::FragmentSupport;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref AFTER_VALUE: Regex = Regex::new(r"\bafter\((\d+)\)").unwrap();
+    static ref TIMELOCK_VALUE: Regex = Regex::new(r"\b(older|after)\((\d+)\)").unwrap();
+    static ref MULTI_A_ARGS: Regex = Regex::new(r"\bmulti_a\(([^()]*)\)").unwrap();
+    static ref MULTI_CALL: Regex = Regex::new(r"\bmulti\(").unwrap();
+    // multi/multi_a/sortedmulti/sortedmulti_a, capturing the arguments (threshold first)
+    static ref MULTISIG_ARGS: Regex = Regex::new(r"\b(?:sorted)?multi(?:_a)?\(([^()]*)\)").unwrap();
+    // A single-key child of a thresh: pk(), pkh(), pk_k(), pk_h(), optionally behind wrappers
+    static ref KEY_CHILD: Regex = Regex::new(r"^(?:[a-z]+:)?(?:pk|pkh|pk_k|pk_h)\(([^()]*)\)$").unwrap();
+    static ref THRESH_CALL: Regex = Regex::new(r"\bthresh\(").unwrap();
+    // 64-hex token (group 2), with the hash fragment name captured (group 1) when it is a sha256/hash256 digest
+    pub(crate) static ref XONLY_KEY: Regex = Regex::new(r"(sha256\(|hash256\()?\b([a-fA-F0-9]{64})\b").unwrap();
+    // Compressed key token, capturing its x-only part
+    pub(crate) static ref COMPRESSED_KEY: Regex = Regex::new(r"\b0[23]([a-fA-F0-9]{64})\b").unwrap();
+    // Key argument of a pk() policy fragment
+    pub(crate) static ref POLICY_KEY_ARG: Regex = Regex::new(r"\bpk\(([^()]*)\)").unwrap();
+}
+
+const PROBE_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+const PROBE_HASH20: &str = "0000000000000000000000000000000000000001";
+
+/// Default upper bound on the length of an expression accepted from the wasm boundary
+pub const MAX_EXPRESSION_LENGTH: usize = 100_000;
+
+/// Reject expressions longer than `limit` characters before they reach the parser
+pub fn check_expression_length(expression: &str, limit: usize) -> Result<(), String> {
+    if expression.chars().count() > limit {
+        return Err(format!("Expression too long (limit {} chars)", limit));
+    }
+    Ok(())
+}
+
+// Fragments whose arguments are keys (multi's threshold is flagged as a key position too)
+const KEY_FRAGMENTS: [&str; 11] = ["pk", "pkh", "pk_k", "pk_h", "multi", "multi_a", "sortedmulti", "sortedmulti_a", "wpkh", "tr", "rawtr"];
+
+/// Reject non-ASCII characters, which neither the policy nor the miniscript grammar allows
+///
+/// Lookalike letters (e.g. Cyrillic `а` for Latin `a`) pasted into a key pass a glance
+/// but fail decoding with an unhelpful error. The 1-based position counts characters of
+/// the input as given; invisible characters are skipped since compilation strips them.
+pub fn check_non_ascii(expression: &str) -> Result<(), String> {
+    let mut open: Vec<&str> = Vec::new();
+    let mut name_start = 0;
+
+    for (position, (i, c)) in expression.char_indices().enumerate() {
+        match c {
+            '(' => {
+                open.push(expression[name_start..i].trim().rsplit(':').next().unwrap_or_default());
+                name_start = i + 1;
+            }
+            ')' => {
+                open.pop();
+                name_start = i + 1;
+            }
+            ',' | '{' | '}' => name_start = i + 1,
+            c if c.is_ascii() || crate::parse::helpers::INVISIBLE_CHARS.contains(&c) => {}
+            _ => {
+                let in_key = open.last().is_some_and(|name| KEY_FRAGMENTS.contains(name));
+                return Err(if in_key {
+                    format!("Invalid character (non-ASCII) in key — possible copy-paste artifact at position {}", position + 1)
+                } else {
+                    format!("Invalid character (non-ASCII) at position {}", position + 1)
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reject empty arguments such as `or(pk(A),)` or `thresh(2,pk(A),,pk(B))`
+///
+/// The parser reports these with a generic error; this names the fragment
+/// and the 1-based position of the empty argument instead.
+pub fn validate_arguments(expression: &str) -> Result<(), String> {
+    // One entry per open parenthesis: fragment name, current argument position, argument seen
+    let mut open: Vec<(&str, usize, bool)> = Vec::new();
+    let mut name_start = 0;
+
+    for (i, c) in expression.char_indices() {
+        match c {
+            '(' => {
+                let name = expression[name_start..i].rsplit(':').next().unwrap_or_default();
+                open.push((name, 1, false));
+                name_start = i + 1;
+            }
+            ',' | ')' => {
+                let Some((name, position, seen)) = open.last_mut() else {
+                    name_start = i + 1;
+                    continue;
+                };
+                if !*seen {
+                    return Err(format!("Empty argument in {}(...) at position {}", name, position));
+                }
+                if c == ',' {
+                    *position += 1;
+                    *seen = false;
+                } else {
+                    open.pop();
+                    if let Some((_, _, seen)) = open.last_mut() {
+                        *seen = true;
+                    }
+                }
+                name_start = i + 1;
+            }
+            c if c.is_whitespace() => {}
+            '{' | '}' => name_start = i + 1,
+            _ => {
+                if let Some((_, _, seen)) = open.last_mut() {
+                    *seen = true;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
 
 /// Validate inner miniscript for a specific context
 pub fn validate_inner_miniscript(inner_miniscript: &str, context: &str) -> Result<(String, String, Option<String>, usize, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>, Option<String>), String> {
@@ -49,3 +170,413 @@ where
         Err(e) => Err(e.to_string())
     }
 }
+
+/// Sample expressions used to probe which fragments each context accepts
+fn fragment_probes() -> Vec<(&'static str, &'static str, String)> {
+    vec![
+        ("pk", "fragment", "pk(A)".to_string()),
+        ("pkh", "fragment", "pkh(A)".to_string()),
+        ("multi", "fragment", "multi(1,A,B)".to_string()),
+        ("multi_a", "fragment", "multi_a(1,A,B)".to_string()),
+        ("older", "fragment", "older(144)".to_string()),
+        ("after", "fragment", "after(144)".to_string()),
+        ("sha256", "fragment", format!("sha256({})", PROBE_HASH)),
+        ("hash256", "fragment", format!("hash256({})", PROBE_HASH)),
+        ("ripemd160", "fragment", format!("ripemd160({})", PROBE_HASH20)),
+        ("hash160", "fragment", format!("hash160({})", PROBE_HASH20)),
+        ("and_v", "fragment", "and_v(v:pk(A),pk(B))".to_string()),
+        ("and_b", "fragment", "and_b(pk(A),s:pk(B))".to_string()),
+        ("or_b", "fragment", "or_b(pk(A),s:pk(B))".to_string()),
+        ("or_c", "fragment", "t:or_c(pk(A),v:pk(B))".to_string()),
+        ("or_d", "fragment", "or_d(pk(A),pk(B))".to_string()),
+        ("or_i", "fragment", "or_i(pk(A),pk(B))".to_string()),
+        ("andor", "fragment", "andor(pk(A),pk(B),pk(C))".to_string()),
+        ("thresh", "fragment", "thresh(1,pk(A),s:pk(B))".to_string()),
+        ("a", "wrapper", "and_b(pk(A),a:pk(B))".to_string()),
+        ("s", "wrapper", "and_b(pk(A),s:pk(B))".to_string()),
+        ("c", "wrapper", "c:pk_k(A)".to_string()),
+        ("d", "wrapper", "andor(pk(A),pk(B),dv:older(144))".to_string()),
+        ("v", "wrapper", "and_v(v:pk(A),pk(B))".to_string()),
+        ("j", "wrapper", "and_b(pk(A),sj:pk(B))".to_string()),
+        ("n", "wrapper", "n:pk(A)".to_string()),
+        ("l", "wrapper", "l:pk(A)".to_string()),
+        ("u", "wrapper", "u:pk(A)".to_string()),
+        ("t", "wrapper", "tv:pk(A)".to_string()),
+    ]
+}
+
+/// Check whether a context accepts an expression (type rules and context limits, not sanity)
+fn context_accepts<Ctx: ScriptContext>(expression: &str) -> bool {
+    Miniscript::<String, Ctx>::from_str_insane(expression).is_ok()
+}
+
+/// List supported miniscript fragments and wrappers with per-context availability
+/// The matrix is derived by probing the parser, so it tracks the miniscript version in use
+pub fn supported_fragments() -> Vec<FragmentSupport> {
+    fragment_probes()
+        .into_iter()
+        .map(|(name, kind, probe)| FragmentSupport {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            legacy: context_accepts::<Legacy>(&probe),
+            segwit: context_accepts::<Segwitv0>(&probe),
+            taproot: context_accepts::<Tap>(&probe),
+        })
+        .collect()
+}
+
+/// Block height known to be mined; `after()` heights at or below it are already satisfied
+const KNOWN_MINED_HEIGHT: u64 = 840_000;
+
+/// Roughly a century of blocks past `KNOWN_MINED_HEIGHT`
+const FAR_FUTURE_HEIGHT: u64 = KNOWN_MINED_HEIGHT + 100 * 52_560;
+
+/// Unix timestamp of the Bitcoin genesis block
+const GENESIS_TIMESTAMP: u64 = 1_231_006_505;
+
+/// Advisories for `after(N)` values that are valid but probably not what was meant
+///
+/// Small block heights are usually a mistyped date and are already satisfied,
+/// as are timestamps before the genesis block; very large heights lock funds
+/// for more than a century.
+pub fn timelock_advisories(expression: &str) -> Vec<String> {
+    let mut advisories = Vec::new();
+
+    for caps in AFTER_VALUE.captures_iter(expression) {
+        let value = match caps[1].parse::<u64>() {
+            Ok(value) if value > 0 => value,
+            _ => continue,
+        };
+
+        let advisory = if value < 500_000_000 {
+            if value <= KNOWN_MINED_HEIGHT {
+                Some(format!(
+                    "⚠️ after({}) is a block height that has already been mined, so the lock is already satisfied. If you meant a date, use a Unix timestamp (500000000 or more)",
+                    value
+                ))
+            } else if value > FAR_FUTURE_HEIGHT {
+                Some(format!("⚠️ after({}) is a block height more than a century away", value))
+            } else {
+                None
+            }
+        } else if value < GENESIS_TIMESTAMP {
+            Some(format!(
+                "⚠️ after({}) is a timestamp before the Bitcoin genesis block, so the lock is already satisfied",
+                value
+            ))
+        } else {
+            None
+        };
+
+        if let Some(advisory) = advisory {
+            if !advisories.contains(&advisory) {
+                advisories.push(advisory);
+            }
+        }
+    }
+
+    advisories
+}
+
+/// Reject degenerate `older(N)`/`after(N)` values before they reach the parser
+///
+/// Zero locks, relative locks with bits outside the BIP68 value field and
+/// time flag, and absolute locks above 0x7FFFFFFF are reported with a
+/// targeted message instead of the parser's generic error.
+pub fn validate_timelocks(expression: &str) -> Result<(), String> {
+    for caps in TIMELOCK_VALUE.captures_iter(expression) {
+        let fragment = &caps[1];
+        let raw = &caps[2];
+        let value = match raw.parse::<u64>() {
+            Ok(value) => value,
+            Err(_) => return Err(format!("{}({}) is out of range: timelock values must fit in 32 bits", fragment, raw)),
+        };
+
+        if fragment == "older" {
+            if value == 0 {
+                return Err("older(0) is not a valid relative timelock: the value must be at least 1".to_string());
+            }
+            if value & !0x0040_FFFF != 0 {
+                return Err(format!(
+                    "older({}) exceeds the BIP68 value field: use 1-65535 blocks, or set the time flag (4194304) plus 1-65535 units of 512 seconds",
+                    value
+                ));
+            }
+            if value & 0xFFFF == 0 {
+                return Err(format!(
+                    "older({}) is a time-based relative timelock of zero units: the value field must be at least 1",
+                    value
+                ));
+            }
+        } else {
+            if value == 0 {
+                return Err("after(0) is not a valid absolute timelock: use a block height or Unix timestamp of at least 1".to_string());
+            }
+            if value > u32::MAX as u64 {
+                return Err(format!(
+                    "after({}) is out of range: nLockTime is a 32-bit field (maximum 4294967295)",
+                    value
+                ));
+            }
+            if value > 0x7FFF_FFFF {
+                return Err(format!(
+                    "after({}) exceeds the maximum absolute timelock of 2147483647 (0x7FFFFFFF)",
+                    value
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Maximum number of keys in a tapscript `multi_a` (BIP342 stack and CHECKSIGADD limits)
+pub const MAX_MULTI_A_KEYS: usize = 999;
+
+/// Error for a legacy multi() under Taproot, where OP_CHECKMULTISIG is disabled
+pub const MULTI_IN_TAPROOT_ERROR: &str = "multi() is not valid under Taproot — use multi_a() which uses OP_CHECKSIGADD";
+
+/// Check `multi_a(k, ...)` fragments against the script context and their threshold
+///
+/// `multi_a` compiles to a CHECKSIG/CHECKSIGADD chain that only exists in tapscript,
+/// so it is rejected elsewhere with a pointer to `multi`; the reverse holds for `multi`,
+/// which Taproot rejects with a pointer to `multi_a`. Under Taproot `multi_a` has no
+/// 15/20-key cap, but `k` must lie between 1 and the number of keys.
+pub fn validate_multi_a(expression: &str, context: &str) -> Result<(), String> {
+    if context == "taproot" && MULTI_CALL.is_match(expression) {
+        return Err(MULTI_IN_TAPROOT_ERROR.to_string());
+    }
+
+    for caps in MULTI_A_ARGS.captures_iter(expression) {
+        if context != "taproot" {
+            return Err(format!(
+                "multi_a is only valid in Taproot (it uses OP_CHECKSIGADD); use multi() in the {} context",
+                context
+            ));
+        }
+
+        let mut args = caps[1].split(',').map(str::trim);
+        let raw_k = args.next().unwrap_or_default();
+        let k = raw_k.parse::<usize>()
+            .map_err(|_| format!("multi_a threshold '{}' is not a number", raw_k))?;
+        let n = args.count();
+
+        if k == 0 {
+            return Err("multi_a threshold must be at least 1".to_string());
+        }
+        if k > n {
+            return Err(format!("multi_a threshold {} exceeds the {} keys provided", k, n));
+        }
+        if n > MAX_MULTI_A_KEYS {
+            return Err(format!("multi_a has {} keys, more than the tapscript limit of {}", n, MAX_MULTI_A_KEYS));
+        }
+    }
+
+    Ok(())
+}
+
+/// Largest P2WSH witnessScript Bitcoin Core will relay (consensus allows 10000 bytes)
+pub const MAX_STANDARD_P2WSH_SCRIPT_SIZE: usize = 3600;
+/// Most witness stack items (excluding the witnessScript) Bitcoin Core will relay for P2WSH
+pub const MAX_STANDARD_P2WSH_STACK_ITEMS: usize = 100;
+
+/// Sane parsing that lets satisfactions exceed the standardness resource limits
+///
+/// Such scripts are consensus-valid; `segwit_standardness_warnings` reports them instead.
+fn standardness_relaxed_params() -> miniscript::ExtParams {
+    miniscript::ExtParams::sane().exceed_resource_limitations()
+}
+
+/// Parse a Segwit v0 miniscript with only the relay (policy) limits relaxed
+///
+/// Relaxing the resource limits also drops rust-miniscript's consensus checks, so the
+/// 201-opcode and 10000-byte script limits are enforced again here: such scripts can never be spent.
+pub(crate) fn parse_segwit_relaxed<Pk>(miniscript: &str) -> Result<Miniscript<Pk, Segwitv0>, miniscript::Error>
+where
+    Pk: miniscript::FromStrKey,
+{
+    use miniscript::ScriptContext;
+
+    let ms = Miniscript::<Pk, Segwitv0>::from_str_ext(miniscript, &standardness_relaxed_params())?;
+    Segwitv0::check_global_consensus_validity(&ms)?;
+    Segwitv0::check_local_consensus_validity(&ms)?;
+    Ok(ms)
+}
+
+/// Warn when a Segwit v0 miniscript is consensus-valid but its spend would not be relayed
+///
+/// Checks the witnessScript size and the number of witness stack items of the largest
+/// satisfaction against Bitcoin Core's P2WSH standardness policy.
+pub fn segwit_standardness_warnings(miniscript: &str) -> Vec<String> {
+    let Ok(ms) = parse_segwit_relaxed::<bitcoin::PublicKey>(miniscript) else {
+        return Vec::new();
+    };
+    let mut warnings = Vec::new();
+
+    let script_size = ms.script_size();
+    if script_size > MAX_STANDARD_P2WSH_SCRIPT_SIZE {
+        warnings.push(format!(
+            "⚠️ Non-standard: the witnessScript is {} bytes, above the {}-byte P2WSH relay limit. It is consensus-valid, but nodes will not relay a spend",
+            script_size, MAX_STANDARD_P2WSH_SCRIPT_SIZE
+        ));
+    }
+
+    if let Ok(elements) = ms.max_satisfaction_witness_elements() {
+        // The count includes the witnessScript itself, which the relay limit does not
+        let stack_items = elements.saturating_sub(1);
+        if stack_items > MAX_STANDARD_P2WSH_STACK_ITEMS {
+            let weight = ms.max_satisfaction_size().map(|size| format!(" ({} WU of witness)", size)).unwrap_or_default();
+            warnings.push(format!(
+                "⚠️ Non-standard: the largest satisfaction needs {} witness stack items{}, above the P2WSH relay limit of {}. It is consensus-valid, but nodes will not relay that spend",
+                stack_items, weight, MAX_STANDARD_P2WSH_STACK_ITEMS
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Largest transaction weight Bitcoin Core will relay (consensus allows a whole block)
+pub const MAX_STANDARD_TX_WEIGHT: u64 = 400_000;
+
+/// Warn when a taproot descriptor's script-path spends are consensus-valid but would not be relayed
+///
+/// Walks every leaf of the script tree and checks its worst-case witness weight, including
+/// the control block at the leaf's depth, against Bitcoin Core's standard transaction weight.
+/// Miniscript satisfactions never push an item over the 80-byte tapscript relay limit
+/// (signatures are at most 65 bytes), so only the weight is checked.
+pub fn taproot_standardness_warnings(descriptor: &str) -> Result<Vec<String>, String> {
+    let descriptor = Descriptor::<DescriptorPublicKey>::from_str(descriptor.trim())
+        .map_err(|e| format!("Failed to parse descriptor: {}", e))?;
+    match descriptor {
+        Descriptor::Tr(tr) => Ok(tr_standardness_warnings(&tr)),
+        _ => Err("Not a taproot descriptor".to_string()),
+    }
+}
+
+/// `taproot_standardness_warnings` for an already parsed tr() descriptor
+pub(crate) fn tr_standardness_warnings<Pk: miniscript::MiniscriptKey>(tr: &miniscript::descriptor::Tr<Pk>) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for (index, (depth, leaf)) in tr.iter_scripts().enumerate() {
+        let (Ok(sat_elems), Ok(sat_size)) = (leaf.max_satisfaction_witness_elements(), leaf.max_satisfaction_size()) else {
+            continue;
+        };
+        let weight = crate::taproot::weights::leaf_witness_weight(sat_elems as u64, sat_size as u64, leaf.script_size() as u64, depth);
+        if weight > MAX_STANDARD_TX_WEIGHT {
+            warnings.push(format!(
+                "⚠️ Non-standard: spending leaf {} (depth {}) needs a {} WU witness, above the {} WU standard transaction weight. It is consensus-valid, but nodes will not relay that spend",
+                index, depth, weight, MAX_STANDARD_TX_WEIGHT
+            ));
+        }
+    }
+    warnings
+}
+
+/// Recognize a policy pasted as miniscript, or a miniscript pasted as policy
+///
+/// Only fires when the expression does not parse as the declared `input_type` at all
+/// (keys are taken as plain strings) but does parse as the other one, so key or type
+/// errors in an otherwise well-formed expression keep their own message.
+pub fn input_type_mismatch_hint(expression: &str, input_type: &str, context: &str) -> Option<String> {
+    fn parses_as_miniscript<Ctx: ScriptContext>(expression: &str) -> bool {
+        Miniscript::<String, Ctx>::from_str_ext(expression, &miniscript::ExtParams::allow_all()).is_ok()
+    }
+    let parses_as_policy = miniscript::policy::Concrete::<String>::from_str(expression).is_ok();
+    let parses_as_miniscript = match context {
+        "legacy" => parses_as_miniscript::<Legacy>(expression),
+        "taproot" => parses_as_miniscript::<Tap>(expression),
+        _ => parses_as_miniscript::<Segwitv0>(expression),
+    };
+
+    match input_type {
+        "miniscript" if parses_as_policy && !parses_as_miniscript => {
+            Some("This looks like a policy, but you selected miniscript".to_string())
+        }
+        "policy" if parses_as_miniscript && !parses_as_policy => {
+            Some("This looks like a miniscript, but you selected policy".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Check the keys of every multisig for validity and repeats
+///
+/// Covers multi(), multi_a(), their sorted forms and thresh() whose children are all single
+/// keys. Hex keys must be points on the curve and descriptor keys must parse; placeholder
+/// names are left to the parser. A repeated key turns a k-of-n into a weaker threshold.
+pub fn check_multisig_keys(expression: &str) -> Result<(), String> {
+    let mut groups: Vec<Vec<&str>> = MULTISIG_ARGS.captures_iter(expression)
+        .map(|caps| caps.get(1).map_or("", |m| m.as_str()).split(',').skip(1).map(str::trim).collect())
+        .collect();
+    for call in THRESH_CALL.find_iter(expression) {
+        let args = top_level_args(&expression[call.end()..]);
+        let keys: Option<Vec<&str>> = args.iter().skip(1)
+            .map(|arg| KEY_CHILD.captures(arg).and_then(|caps| caps.get(1)).map(|m| m.as_str()))
+            .collect();
+        if let Some(keys) = keys.filter(|keys| !keys.is_empty()) {
+            groups.push(keys);
+        }
+    }
+
+    for keys in groups {
+        let mut seen = std::collections::HashSet::new();
+        for key in keys {
+            if !valid_key_point(key) {
+                return Err(format!("Invalid key point: {}", key));
+            }
+            if !seen.insert(key.to_lowercase()) {
+                return Err(format!("Duplicate key in multisig: {}", key));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Arguments of a call, given the text right after its opening parenthesis
+fn top_level_args(rest: &str) -> Vec<&str> {
+    let mut args = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ',' if depth == 0 => {
+                args.push(&rest[start..i]);
+                start = i + 1;
+            }
+            ')' if depth == 0 => {
+                args.push(&rest[start..i]);
+                break;
+            }
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    args
+}
+
+// Whether a key argument names a valid point; non-hex names other than descriptor keys pass
+fn valid_key_point(key: &str) -> bool {
+    let is_hex = key.chars().all(|c| c.is_ascii_hexdigit());
+    match key.len() {
+        66 if is_hex => bitcoin::PublicKey::from_str(key).is_ok(),
+        64 if is_hex => bitcoin::XOnlyPublicKey::from_str(key).is_ok(),
+        _ if key.contains('[') || key.starts_with("xpub") || key.starts_with("tpub") => DescriptorPublicKey::from_str(key).is_ok(),
+        _ => true,
+    }
+}
+
+/// Error for an expression that mixes x-only and compressed keys
+pub const MIXED_KEY_TYPES_ERROR: &str = "Mixed key types: found both x-only (Taproot) and compressed (Legacy/Segwit) keys — use a single key type";
+
+/// Reject expressions containing both x-only and compressed hex keys
+///
+/// No single context accepts both, so this is reported before the per-context
+/// key checks, which would only flag whichever kind the context does not expect.
+/// 64-hex digests of `sha256()`/`hash256()` are not keys and are ignored.
+pub fn check_mixed_key_types(expression: &str) -> Result<(), String> {
+    let has_xonly = XONLY_KEY.captures_iter(expression).any(|caps| caps.get(1).is_none());
+    if has_xonly && COMPRESSED_KEY.is_match(expression) {
+        return Err(MIXED_KEY_TYPES_ERROR.to_string());
+    }
+    Ok(())
+}
@@ -497,3 +497,220 @@ fn test_hd_descriptor_compilation() {
     
     println!("✓ HD descriptor compilation test passed");
 }
+
+#[test]
+fn test_script_pubkey_at_index_wildcard_wsh() {
+    use bitcoin::bip32::{ChildNumber, Xpub};
+    use bitcoin::secp256k1::Secp256k1;
+    use bitcoin::{Address, ScriptBuf};
+    use miniscript_wasm::address::script_pubkey_at_index;
+    use std::str::FromStr;
+
+    let tpub = "tpubDDEe6Dc3LW1JEUzExDRZ3XBzcAzYxMTfVU5KojsTwXoJ4st6LzqgbFZ1HhDBdTptjXH9MwgdYG4K7MNJBfQktc6AoS8WeAWFDHwDTu99bZa";
+    let descriptor = format!("wsh(pk([C8FE8D4F/48h/1h/123h/2h]{}/0/*))", tpub);
+
+    let derived = script_pubkey_at_index(&descriptor, 7, "testnet").unwrap();
+
+    // Build the same output by hand: child key m/0/7, witness script <key> CHECKSIG
+    let secp = Secp256k1::verification_only();
+    let child = Xpub::from_str(tpub).unwrap()
+        .derive_pub(&secp, &[ChildNumber::from_normal_idx(0).unwrap(), ChildNumber::from_normal_idx(7).unwrap()])
+        .unwrap();
+    let witness_script = bitcoin::script::Builder::new()
+        .push_key(&bitcoin::PublicKey::new(child.public_key))
+        .push_opcode(bitcoin::opcodes::all::OP_CHECKSIG)
+        .into_script();
+    let expected_spk = ScriptBuf::new_p2wsh(&witness_script.wscript_hash());
+
+    assert_eq!(derived.script_hex, expected_spk.to_hex_string());
+    let expected_address = Address::from_script(&expected_spk, Network::Testnet).unwrap().to_string();
+    assert_eq!(derived.address.as_deref(), Some(expected_address.as_str()));
+
+    // Different indices give different scripts
+    let other = script_pubkey_at_index(&descriptor, 8, "testnet").unwrap();
+    assert_ne!(other.script_hex, derived.script_hex);
+
+    // Hardened indices cannot be derived from an xpub
+    assert!(script_pubkey_at_index(&descriptor, 0x8000_0000, "testnet").is_err());
+}
+
+#[test]
+fn test_generate_address_reports_internal_key_source() {
+    let generate = |internal_key: Option<&str>, use_single_leaf: Option<bool>| {
+        generate_address(AddressInput {
+            script_or_miniscript: TEST_MINISCRIPT_TAPROOT.to_string(),
+            script_type: "Taproot".to_string(),
+            network: "mainnet".to_string(),
+            internal_key: internal_key.map(str::to_string),
+            use_single_leaf,
+        }).expect("Taproot address generation should succeed")
+    };
+
+    let script_path = generate(Some(TEST_INTERNAL_KEY_SCRIPT_ONLY), None);
+    assert_eq!(script_path.internal_key_source.as_deref(), Some("NUMS"));
+
+    let multi_leaf = generate(None, None);
+    assert_eq!(multi_leaf.internal_key_source.as_deref(), Some("extracted"));
+
+    // A key outside the script is used as given, so the address differs from both modes above
+    let user_key = "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+    let provided = generate(Some(user_key), None);
+    assert_eq!(provided.internal_key_source.as_deref(), Some("user-provided"));
+    assert_ne!(provided.address, multi_leaf.address);
+    assert_ne!(provided.address, script_path.address);
+
+    // Non-taproot addresses have no internal key
+    let segwit = generate_address(AddressInput {
+        script_or_miniscript: TEST_MINISCRIPT_SEGWIT.to_string(),
+        script_type: "Segwit v0".to_string(),
+        network: "mainnet".to_string(),
+        internal_key: None,
+        use_single_leaf: None,
+    }).unwrap();
+    assert_eq!(segwit.internal_key_source, None);
+}
+
+#[test]
+fn test_compile_reports_internal_key_source() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let source = |mode: &str, nums_key: Option<&str>| {
+        let options = CompileOptions::for_miniscript("taproot", Some(mode), nums_key.map(str::to_string), Some(Network::Bitcoin)).unwrap();
+        let result = compile_unified(TEST_MINISCRIPT_TAPROOT, options).unwrap();
+        assert!(result.success, "{} should compile: {:?}", mode, result.error);
+        result.internal_key_source
+    };
+
+    assert_eq!(source("script-path", None).as_deref(), Some("NUMS"));
+    assert_eq!(source("multi-leaf", None).as_deref(), Some("extracted"));
+    let user_key = "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+    assert_eq!(source("script-path", Some(user_key)).as_deref(), Some("user-provided"));
+}
+
+#[test]
+fn test_match_address_to_script() {
+    use miniscript_wasm::address::match_address_to_script;
+
+    // Witness script of pk(02f9308a...): <key> OP_CHECKSIG
+    let witness_script = "2102f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9ac";
+
+    let found = match_address_to_script(EXPECTED_SEGWIT_MAINNET, witness_script, "mainnet").unwrap();
+    assert!(found.matches);
+    assert_eq!(found.output_type, "P2WSH");
+
+    // The same script compiled for legacy sits behind the P2SH address
+    let legacy = match_address_to_script(EXPECTED_LEGACY_MAINNET, witness_script, "mainnet").unwrap();
+    assert!(legacy.matches);
+    assert_eq!(legacy.output_type, "P2SH");
+
+    // A different key gives a different witness program
+    let other_script = "2103a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bdac";
+    let mismatch = match_address_to_script(EXPECTED_SEGWIT_MAINNET, other_script, "mainnet").unwrap();
+    assert!(!mismatch.matches);
+    assert_eq!(mismatch.output_type, "P2WSH");
+
+    // The address must belong to the requested network
+    assert!(match_address_to_script(EXPECTED_SEGWIT_MAINNET, witness_script, "testnet").is_err());
+}
+
+#[test]
+fn test_descriptors_equivalent() {
+    use miniscript_wasm::address::descriptors_equivalent;
+
+    let a = "tpubDC5H7ejMEWt2JF3AF2kdKKokQsAHcksHoHHMgw6S9x5sTs8mZ4rpNPpuNYSJr7RuwiqUgJpYnA6XftMUNW5hGkTduGCtFfBdyj2hXJKN2Xf/0/*";
+    let b = "tpubDC8Anx4AbMFdpAygLRf4NqUrmKZysVXSodQBbqmKhmaLgjFCR9xHYsgGytkKDTj8n8abDRsYQmv2voqnxdPekdLWHsyt99yqttghUyCYYE8/0/*";
+    let indices = [0, 1, 5, 100];
+
+    // sortedmulti sorts the derived keys, so source order does not matter
+    let found = descriptors_equivalent(
+        &format!("wsh(sortedmulti(2,{},{}))", a, b),
+        &format!("wsh(sortedmulti(2,{},{}))", b, a),
+        "testnet",
+        &indices,
+    ).unwrap();
+    assert!(found.equivalent);
+    assert_eq!(found.diverging_index, None);
+
+    // A different threshold changes every output
+    let found = descriptors_equivalent(
+        &format!("wsh(sortedmulti(2,{},{}))", a, b),
+        &format!("wsh(sortedmulti(1,{},{}))", a, b),
+        "testnet",
+        &indices,
+    ).unwrap();
+    assert!(!found.equivalent);
+    assert_eq!(found.diverging_index, Some(0));
+
+    // Fixed descriptors compare their single output
+    let key = "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+    assert!(descriptors_equivalent(&format!("wsh(pk({}))", key), &format!("wsh(c:pk_k({}))", key), "mainnet", &indices).unwrap().equivalent);
+    assert!(!descriptors_equivalent(&format!("wsh(pk({}))", key), &format!("sh(pk({}))", key), "mainnet", &[]).unwrap().equivalent);
+}
+
+#[test]
+fn test_rawtr_address_uses_output_key_untweaked() {
+    use bitcoin::key::TweakedPublicKey;
+    use bitcoin::{Address, ScriptBuf, XOnlyPublicKey};
+    use miniscript_wasm::address::script_pubkey_at_index;
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use std::str::FromStr;
+
+    let output_key = XOnlyPublicKey::from_str(TEST_INTERNAL_KEY_MULTI_LEAF).unwrap();
+    let tweaked = TweakedPublicKey::dangerous_assume_tweaked(output_key);
+    let descriptor = format!("rawtr({})", TEST_INTERNAL_KEY_MULTI_LEAF);
+
+    let derived = script_pubkey_at_index(&descriptor, 0, "mainnet").unwrap();
+    assert_eq!(derived.script_hex, ScriptBuf::new_p2tr_tweaked(tweaked).to_hex_string());
+    assert_eq!(derived.address.unwrap(), Address::p2tr_tweaked(tweaked, Network::Bitcoin).to_string());
+
+    // Checksums are verified
+    let checksum = miniscript::descriptor::checksum::desc_checksum(&descriptor).unwrap();
+    assert!(script_pubkey_at_index(&format!("{}#{}", descriptor, checksum), 0, "mainnet").is_ok());
+    assert!(script_pubkey_at_index(&format!("{}#qqqqqqqq", descriptor), 0, "mainnet").is_err());
+
+    // There is no script tree to compile into
+    let options = CompileOptions::for_miniscript("taproot", None, None, None).unwrap();
+    let Err(err) = compile_unified(&descriptor, options) else {
+        panic!("rawtr() should not compile");
+    };
+    assert!(err.contains("rawtr()"), "{}", err);
+}
+
+#[test]
+fn test_combo_outputs_lists_every_script_type() {
+    use bitcoin::{Address, CompressedPublicKey, PublicKey, ScriptBuf};
+    use miniscript_wasm::address::{combo_outputs, script_pubkey_at_index};
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use std::str::FromStr;
+
+    let key_hex = "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+    let key = PublicKey::from_str(key_hex).unwrap();
+    let compressed = CompressedPublicKey::from_str(key_hex).unwrap();
+    let descriptor = format!("combo({})", key_hex);
+
+    let outputs = combo_outputs(&descriptor, 0, "mainnet").unwrap();
+    let types: Vec<&str> = outputs.iter().map(|o| o.output_type.as_str()).collect();
+    assert_eq!(types, vec!["P2PK", "P2PKH", "P2WPKH", "P2SH-P2WPKH"]);
+
+    assert_eq!(outputs[0].script_hex, ScriptBuf::new_p2pk(&key).to_hex_string());
+    assert!(outputs[0].address.is_none());
+    assert_eq!(outputs[1].address.as_deref(), Some(Address::p2pkh(key, Network::Bitcoin).to_string().as_str()));
+    assert_eq!(outputs[2].address.as_deref(), Some(Address::p2wpkh(&compressed, Network::Bitcoin).to_string().as_str()));
+    assert_eq!(outputs[3].address.as_deref(), Some(Address::p2shwpkh(&compressed, Network::Bitcoin).to_string().as_str()));
+
+    // Uncompressed keys have no segwit outputs
+    let uncompressed = "04f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9388f7b0f632de8140fe337e62a37f3566500a99934c2231b6cb9fd7584b8e672";
+    let outputs = combo_outputs(&format!("combo({})", uncompressed), 0, "mainnet").unwrap();
+    assert_eq!(outputs.len(), 2);
+
+    // Places that need exactly one output reject combo()
+    assert!(script_pubkey_at_index(&descriptor, 0, "mainnet").is_err());
+    let options = CompileOptions::for_miniscript("taproot", None, None, None).unwrap();
+    let Err(err) = compile_unified(&descriptor, options) else {
+        panic!("combo() should not compile");
+    };
+    assert!(err.contains("combo()"), "{}", err);
+}
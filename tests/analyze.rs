@@ -8,7 +8,7 @@
 mod analyze_tests {
     use miniscript::policy::{Concrete, Liftable};
     use miniscript::{Miniscript, Segwitv0};
-    use miniscript_wasm::analyze::{semantic_to_tree, enumerate_spending_paths};
+    use miniscript_wasm::analyze::{semantic_to_tree, enumerate_spending_paths, get_grouped_paths, key_path_matrix, estimate_path_count, paths_truncated};
 
     // ========================================
     // Tests for semantic_to_tree()
@@ -59,6 +59,39 @@ mod analyze_tests {
         assert!(tree.contains("pk(Charlie)"));
     }
 
+    #[test]
+    fn test_tree_timelock_nodes_are_decoded() {
+        // 0x400000 flags a time-based relative lock: 170 units of 512 seconds
+        let policy: Concrete<String> = "or(and(pk(Alice),older(144)),and(pk(Bob),older(4194474)))".parse().unwrap();
+        let tree = serde_json::to_value(semantic_to_tree(&policy.lift().unwrap(), 0)).unwrap();
+
+        fn find<'a>(node: &'a serde_json::Value, value: &str) -> Option<&'a serde_json::Value> {
+            if node["value"] == value {
+                return Some(node);
+            }
+            node["children"].as_array()?.iter().find_map(|child| find(child, value))
+        }
+
+        let blocks = find(&tree, "144").unwrap();
+        assert_eq!(blocks["type"], "older");
+        assert_eq!(blocks["lock_kind"], "height");
+        assert_eq!(blocks["human"], "144 blocks");
+
+        let time = find(&tree, "4194474").unwrap();
+        assert_eq!(time["lock_kind"], "time");
+        assert_eq!(time["human"], "~1 days");
+
+        // Non-timelock nodes carry neither field
+        let key = find(&tree, "Alice").unwrap();
+        assert!(key.get("human").is_none() && key.get("lock_kind").is_none());
+
+        let absolute: Concrete<String> = "and(pk(Alice),after(800000))".parse().unwrap();
+        let tree = serde_json::to_value(semantic_to_tree(&absolute.lift().unwrap(), 0)).unwrap();
+        let height = find(&tree, "800000").unwrap();
+        assert_eq!(height["lock_kind"], "height");
+        assert_eq!(height["human"], "block 800000");
+    }
+
     #[test]
     fn test_tree_with_timelock() {
         let policy: Concrete<String> = "and(pk(Alice),older(144))".parse().unwrap();
@@ -216,6 +249,502 @@ mod analyze_tests {
         assert_eq!(paths.len(), 2);
     }
 
+    #[test]
+    fn test_paths_andor() {
+        // andor(A,B,C) is "if A then B else C": two paths, A+B or C alone
+        let ms = Miniscript::<String, Segwitv0>::from_str_insane("andor(pk(A),pk(B),older(144))").unwrap();
+        let semantic = ms.lift().unwrap();
+        let paths = enumerate_spending_paths(&semantic);
+
+        assert_eq!(paths.len(), 2, "andor should produce exactly two paths: {:?}", paths);
+        assert_eq!(paths[0], "Path 1: A signs + B signs");
+        assert!(paths[1].starts_with("Path 2: wait 144 blocks"), "Second path should be the timelock: {}", paths[1]);
+        assert!(paths[1].contains("no signature required"), "Timelock-only path should carry the warning");
+    }
+
+    #[test]
+    fn test_paths_and_n() {
+        // and_n(X,Y) is andor(X,Y,0): the unsatisfiable else-branch adds no path
+        let ms: Miniscript<String, Segwitv0> = "and_n(pk(A),pk(B))".parse().unwrap();
+        let semantic = ms.lift().unwrap();
+        let paths = enumerate_spending_paths(&semantic);
+
+        assert_eq!(paths, vec!["Path 1: A signs + B signs".to_string()]);
+    }
+
+    #[test]
+    fn test_grouped_summary_heterogeneous_thresh() {
+        let ms: Miniscript<String, Segwitv0> = "thresh(2,pk(A),snj:and_v(v:pk(B),older(144)),s:pk(C))".parse().unwrap();
+        let semantic = ms.lift().unwrap();
+        let groups = get_grouped_paths(&semantic);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].path_count, 3);
+        assert_eq!(
+            groups[0].summary.as_deref(),
+            Some("2-of-3 threshold: {A, (B + wait 144 blocks), C}"),
+            "Compound thresh children should be described, not dropped"
+        );
+    }
+
+    #[test]
+    fn test_grouped_summary_nested_or_branch() {
+        let policy: Concrete<String> = "or(and(pk(A),or(pk(B),pk(D))),and(pk(C),older(144)))".parse().unwrap();
+        let semantic = policy.lift().unwrap();
+        let groups = get_grouped_paths(&semantic);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].summary.as_deref(), Some("A + (B or D)"));
+    }
+
+    #[test]
+    fn test_key_path_matrix() {
+        let matrix = key_path_matrix("or(and(pk(A),pk(B)),pk(C))", "policy").unwrap();
+
+        assert_eq!(matrix.len(), 3);
+        let paths_for = |key: &str| matrix.iter().find(|e| e.key == key).unwrap().paths.clone();
+        assert_eq!(paths_for("A"), vec![1], "A participates only in the A+B path");
+        assert_eq!(paths_for("B"), vec![1], "B shares the path with A");
+        assert_eq!(paths_for("C"), vec![2], "C has its own path");
+
+        // Miniscript input goes through the same enumeration
+        let matrix = key_path_matrix("or_d(pk(A),and_v(v:pk(B),older(144)))", "segwit").unwrap();
+        assert_eq!(matrix.iter().find(|e| e.key == "B").unwrap().paths, vec![2]);
+    }
+
+    #[test]
+    fn test_estimate_path_count_matches_enumeration() {
+        let policy_str = "thresh(2,or(pk(A),pk(B)),and(pk(C),or(pk(D),after(500000))),or(pk(E),and(pk(F),older(144))),pk(G))";
+        let policy: Concrete<String> = policy_str.parse().unwrap();
+        let semantic = policy.lift().unwrap();
+        let enumerated = enumerate_spending_paths(&semantic).len() as u64;
+
+        assert_eq!(estimate_path_count(policy_str, "policy").unwrap(), enumerated);
+
+        let ms = "or_d(pk(A),and_v(v:pk(B),older(144)))";
+        assert_eq!(estimate_path_count(ms, "segwit").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_bare_terminals_lift_to_trivial_and_unsatisfiable() {
+        use miniscript_wasm::analyze::degenerate_spend_warning;
+
+        let trivial = degenerate_spend_warning("1", "segwit").unwrap();
+        assert!(trivial.contains("trivially satisfiable"), "{}", trivial);
+
+        // `0` is rejected by rust-miniscript's lift but still maps to Unsatisfiable
+        let unsat = degenerate_spend_warning("0", "segwit").unwrap();
+        assert!(unsat.contains("unsatisfiable"), "{}", unsat);
+        assert_eq!(estimate_path_count("0", "segwit").unwrap(), 0);
+
+        // A `0` branch simply drops out of the compound script
+        assert_eq!(degenerate_spend_warning("or_i(0,pk(A))", "segwit"), None);
+        let matrix = key_path_matrix("or_i(0,pk(A))", "segwit").unwrap();
+        assert_eq!(matrix.len(), 1);
+        assert_eq!(matrix[0].key, "A");
+    }
+
+    #[test]
+    fn test_satisfiable_paths_with_two_of_three_keys() {
+        use miniscript_wasm::analyze::satisfiable_paths;
+
+        let held = vec!["A".to_string(), "B".to_string()];
+        let (satisfiable, unsatisfiable) = satisfiable_paths("multi(2,A,B,C)", "segwit", &held, &[]).unwrap();
+
+        assert_eq!(satisfiable.len(), 1);
+        assert_eq!(satisfiable[0].conditions, vec!["A signs", "B signs"]);
+        assert_eq!(unsatisfiable.len(), 2);
+        assert!(unsatisfiable.iter().all(|p| p.missing == vec!["C signs"]));
+
+        // A hashlock path opens up once the preimage is supplied
+        let sha = "66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925";
+        let ms = format!("or_d(pk(C),and_v(v:pk(A),sha256({})))", sha);
+        let (without, _) = satisfiable_paths(&ms, "segwit", &held, &[]).unwrap();
+        assert!(without.is_empty());
+        let (with, _) = satisfiable_paths(&ms, "segwit", &held, &["00".repeat(32)]).unwrap();
+        assert_eq!(with.len(), 1);
+        assert_eq!(with[0].path, 2);
+    }
+
+    #[test]
+    fn test_satisfiable_paths_matches_compressed_and_xonly_forms_safely() {
+        use miniscript_wasm::analyze::satisfiable_paths;
+
+        let xonly = "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+        let ms = format!("pk({})", xonly);
+
+        // The compressed form of the key satisfies its x-only pk()
+        let (satisfiable, _) = satisfiable_paths(&ms, "taproot", &[format!("02{}", xonly)], &[]).unwrap();
+        assert_eq!(satisfiable.len(), 1);
+
+        // Only a 02/03 prefix marks a compressed key
+        let (satisfiable, _) = satisfiable_paths(&ms, "taproot", &[format!("ff{}", xonly)], &[]).unwrap();
+        assert!(satisfiable.is_empty());
+
+        // A 66-byte non-ASCII key must not panic on a byte slice
+        let (satisfiable, _) = satisfiable_paths(&ms, "taproot", &["é".repeat(33)], &[]).unwrap();
+        assert!(satisfiable.is_empty());
+    }
+
+    #[test]
+    fn test_risk_level_for_policies() {
+        use miniscript_wasm::analyze::perform_policy_analysis;
+
+        let sha = "66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925";
+        let cases = [
+            ("and(pk(A),pk(B))".to_string(), "safe"),
+            ("or(pk(A),and(pk(B),older(144)))".to_string(), "safe"),
+            ("or(pk(A),older(144))".to_string(), "caution"),
+            (format!("or(pk(A),sha256({}))", sha), "caution"),
+            ("TRIVIAL".to_string(), "dangerous"),
+            ("UNSATISFIABLE".to_string(), "dangerous"),
+        ];
+
+        for (policy, expected) in cases {
+            let analysis = perform_policy_analysis(&policy).unwrap();
+            assert_eq!(analysis.security.unwrap().risk_level, expected, "policy {}", policy);
+        }
+    }
+
+    #[test]
+    fn test_redundant_and_dominated_or_branches_warn() {
+        use miniscript_wasm::analyze::perform_policy_analysis;
+
+        let warnings = |policy: &str| perform_policy_analysis(policy).unwrap().warnings.unwrap_or_default();
+
+        let duplicate = warnings("or(pk(A),pk(A))");
+        assert!(duplicate.iter().any(|w| w.contains("Branch 2 is redundant") && w.contains("Branch 1")), "{:?}", duplicate);
+
+        let dominated = warnings("or(older(144),older(288))");
+        assert!(dominated.iter().any(|w| w.contains("Branch 2 (older(288)) is dominated by Branch 1 (older(144))")), "{:?}", dominated);
+
+        // Different keys and mixed timelock kinds are independent branches
+        assert!(!warnings("or(pk(A),pk(B))").iter().any(|w| w.contains("Branch")));
+        assert!(!warnings("or(and(pk(A),older(144)),and(pk(B),after(500000)))").iter().any(|w| w.contains("Branch")));
+    }
+
+    #[test]
+    fn test_analyze_nested_segwit_descriptor() {
+        use miniscript_wasm::analyze::analyze_miniscript_expression;
+        use miniscript_wasm::lift::lift_miniscript_to_semantic_policy;
+
+        let keys = [
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+            "03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd",
+            "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+        ];
+        let descriptor = format!("sh(wsh(multi(2,{})))", keys.join(","));
+
+        // The wrapper decides the context, whatever the caller selected
+        let analysis = analyze_miniscript_expression(&descriptor, "taproot").unwrap();
+        assert!(analysis.success);
+        assert_eq!(analysis.output_type.as_deref(), Some("P2SH-P2WSH (nested segwit)"));
+        let key_info = analysis.keys.unwrap();
+        assert_eq!(key_info.unique_keys.len(), 3);
+        assert_eq!(key_info.min_signatures, Some(2));
+
+        let lifted = lift_miniscript_to_semantic_policy(&descriptor).unwrap();
+        assert!(lifted.starts_with("thresh(2,pk("), "{}", lifted);
+    }
+
+    #[test]
+    fn test_analyze_taproot_key_forms() {
+        use miniscript_wasm::analyze::analyze_miniscript_expression;
+
+        // BIP32 test vector 1 master key, whose public key is 0339a360...
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        let xonly = "b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89";
+        let expression = format!("and_v(v:pk({}),pk({}))", xpub, xonly);
+
+        let key_info = analyze_miniscript_expression(&expression, "taproot").unwrap().keys.unwrap();
+        let forms = key_info.key_forms.unwrap();
+        assert_eq!(forms.len(), key_info.unique_keys.len());
+        let form_of = |key: &str| &forms[key_info.unique_keys.iter().position(|k| k == key).unwrap()];
+
+        let xpub_form = form_of(xpub);
+        assert!(xpub_form.contains("compressed 0339a36013301597daef41fbe593a02cc513d0b55527ec2df1050e2e8ff49c85c2"), "{}", xpub_form);
+        assert!(xpub_form.contains("x-only 39a36013301597daef41fbe593a02cc513d0b55527ec2df1050e2e8ff49c85c2"), "{}", xpub_form);
+        assert!(form_of(xonly).contains("compressed form ambiguous without parity"));
+
+        // Segwit keys are already compressed, so no parallel list is produced
+        let segwit = analyze_miniscript_expression(&format!("pk({})", xpub), "segwit").unwrap();
+        assert!(segwit.keys.unwrap().key_forms.is_none());
+    }
+
+    #[test]
+    fn test_pkh_witness_carries_key_push() {
+        use miniscript_wasm::analyze::analyze_miniscript_expression;
+
+        let size = |expression: &str, context: &str| {
+            analyze_miniscript_expression(expression, context).unwrap().size.unwrap()
+        };
+
+        // pk() keeps the key in the script, so the witness is just the signature push
+        let pk = size("pk(A)", "segwit");
+        assert_eq!(pk.max_witness_bytes, Some(73));
+
+        // pkh() also pushes the 33-byte key (34 with its length byte) for the hash check
+        let pkh = size("pkh(A)", "segwit");
+        assert_eq!(pkh.max_witness_bytes, Some(73 + 34));
+        assert_eq!(pkh.witness_elements, Some(pk.witness_elements.unwrap() + 1));
+
+        // Taproot pushes the 32-byte x-only key instead
+        assert_eq!(size("pkh(A)", "taproot").max_witness_bytes, Some(size("pk(A)", "taproot").max_witness_bytes.unwrap() + 33));
+    }
+
+    #[test]
+    fn test_policy_analysis_branch_weights() {
+        use miniscript_wasm::analyze::{perform_policy_analysis, perform_policy_analysis_with_weights};
+
+        let groups = perform_policy_analysis_with_weights("or(3@pk(A),1@pk(B))", true).unwrap()
+            .spending_paths_grouped.unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].summary.as_deref(), Some("pk(A)"));
+        assert_eq!(groups[0].weight_percent, Some(75.0));
+        assert_eq!(groups[1].weight_percent, Some(25.0));
+
+        // Weights are opt-in
+        let groups = perform_policy_analysis("or(3@pk(A),1@pk(B))").unwrap().spending_paths_grouped.unwrap();
+        assert!(groups.iter().all(|g| g.weight_percent.is_none()));
+    }
+
+    #[test]
+    fn test_cheapest_spend_prefers_key_path() {
+        use miniscript_wasm::analyze::spend::cheapest_spend;
+
+        let owner = "d127f475aba7d9111ff69cc6858305d15e8912205cfa5dcc7a4c66a97ebb8174";
+        let policy = format!(
+            "or(pk({}),and(thresh(2,pk(b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89),pk(e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13)),older(144)))",
+            owner
+        );
+
+        let cheapest = cheapest_spend(&policy, "taproot", 10.0).unwrap();
+        assert_eq!(cheapest.spend_type, "key-path");
+        assert_eq!(cheapest.conditions, vec![format!("{} signs", owner)]);
+        // Witness count, signature length and a 64-byte signature, plus the empty scriptSig
+        assert_eq!(cheapest.satisfaction_weight, 1 + 1 + 64 + 4);
+        assert_eq!(cheapest.fee_sats, 175);
+
+        // In segwit every path pays for the witness script, and the single signature is still cheapest
+        let segwit_policy = policy.replace(owner, "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9")
+            .replace("b2afcd", "03b2afcd").replace("e493db", "02e493db");
+        let segwit = cheapest_spend(&segwit_policy, "segwit", 10.0).unwrap();
+        assert_eq!(segwit.spend_type, "script");
+        assert_eq!(segwit.conditions.len(), 1);
+        assert!(segwit.satisfaction_weight > cheapest.satisfaction_weight);
+    }
+
+    #[test]
+    fn test_weight_breakdown_of_2_of_3_p2wsh() {
+        use miniscript_wasm::analyze::spend::weight_breakdown;
+
+        let descriptor = "wsh(multi(2,02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9,03b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89,02e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13))";
+        let breakdown = weight_breakdown(descriptor, "segwit").unwrap();
+        // OP_2, three 33-byte key pushes, OP_3, OP_CHECKMULTISIG
+        assert_eq!(breakdown.script_bytes, 1 + 3 * 34 + 1 + 1);
+        assert_eq!(breakdown.per_signature_bytes, 72);
+        assert_eq!(breakdown.num_required, 2);
+        assert_eq!(breakdown.control_or_redeem_bytes, 0);
+        // Witness bytes weigh 1 WU, so the parts add up to the total
+        let parts = breakdown.script_bytes + breakdown.num_required * breakdown.per_signature_bytes + breakdown.control_or_redeem_bytes;
+        assert_eq!(parts as u64 + breakdown.overhead_wu, breakdown.total_wu);
+        // Two signature length prefixes, the CHECKMULTISIG dummy and the script length prefix
+        assert_eq!(breakdown.overhead_wu, 4);
+
+        // Anything beyond signatures is not a plain multisig
+        assert!(weight_breakdown("and_v(v:pk(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9),older(144))", "segwit").is_err());
+    }
+
+    #[test]
+    fn test_timelocks_report_consensus_rule() {
+        use miniscript_wasm::analyze::analyze_miniscript_expression;
+
+        let expression = "andor(pk(A),older(144),and_v(v:pk(B),after(800000)))";
+        let timelocks = analyze_miniscript_expression(expression, "taproot").unwrap().timelocks.unwrap();
+
+        assert_eq!(timelocks.relative.len(), 1);
+        assert_eq!(timelocks.relative[0].value, 144);
+        assert!(timelocks.relative[0].consensus_rule.starts_with("BIP68 relative"));
+        assert!(timelocks.relative[0].consensus_rule.contains("BIP112"));
+
+        assert_eq!(timelocks.absolute.len(), 1);
+        assert_eq!(timelocks.absolute[0].value, 800000);
+        assert!(timelocks.absolute[0].consensus_rule.starts_with("BIP65 absolute"));
+        assert!(timelocks.absolute[0].consensus_rule.contains("nLockTime"));
+    }
+
+    #[test]
+    fn test_spending_paths_structured_timelocked_multisig() {
+        use miniscript_wasm::analyze::spending_paths_structured;
+
+        let keys = [
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+            "03b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89",
+            "02e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13",
+        ];
+        let expression = format!("or_d(multi(2,{},{}),and_v(v:pk({}),older(144)))", keys[0], keys[1], keys[2]);
+        let paths = spending_paths_structured(&expression, "segwit").unwrap();
+        assert_eq!(paths.len(), 2);
+
+        let kinds = |i: usize| paths[i].conditions.iter().map(|c| c.kind.as_str()).collect::<Vec<_>>();
+        assert_eq!(kinds(0), vec!["signature", "signature"]);
+        assert_eq!(paths[0].conditions[0].key.as_deref(), Some(keys[0]));
+        assert_eq!(kinds(1), vec!["signature", "older"]);
+        assert_eq!(paths[1].conditions[1].value, Some(144));
+        assert_eq!(paths[1].conditions[1].detail, "wait 144 blocks");
+        assert!(paths.iter().all(|p| p.requires_signature));
+        // The recovery path dissatisfies the multisig before its own signature
+        let (multisig, recovery) = (paths[0].witness_weight.unwrap(), paths[1].witness_weight.unwrap());
+        assert!(recovery > 0 && multisig > recovery);
+
+        // Placeholder keys cannot be planned, but the conditions are still typed
+        let policy = spending_paths_structured("or(pk(A),and(sha256(6c60f404f8167a38fc70eaf8aa17ac351023bef86bcb9d1086a19afe95bd5333),after(800000)))", "policy").unwrap();
+        assert!(policy.iter().all(|p| p.witness_weight.is_none()));
+        let hashlock = &policy[1];
+        assert!(!hashlock.requires_signature);
+        assert_eq!(hashlock.conditions[0].kind, "sha256");
+        assert_eq!(hashlock.conditions[0].hash.as_deref(), Some("6c60f404f8167a38fc70eaf8aa17ac351023bef86bcb9d1086a19afe95bd5333"));
+        assert_eq!(hashlock.conditions[1].kind, "after");
+        assert_eq!(hashlock.conditions[1].value, Some(800000));
+    }
+
+    #[test]
+    fn test_shape_summary_keeps_short_hash_names() {
+        use miniscript_wasm::analyze::shape_summary;
+
+        // Policy hash names can be shorter than the 8 characters shown for a digest
+        let summary = shape_summary("thresh(2,pk(A),pk(B),sha256(H),older(10))", "policy").unwrap();
+        assert!(summary.contains("sha256(H...)"), "{}", summary);
+    }
+
+    #[test]
+    fn test_contract_summary_timelocked_recovery() {
+        use miniscript_wasm::analyze::contract_summary;
+        use std::collections::HashMap;
+
+        let policy = "or(and(pk(Alice),pk(Bob)),and(pk(Carol),older(25920)))";
+        let summary = contract_summary(policy, "policy", &HashMap::new()).unwrap();
+        assert_eq!(summary, "Funds can be spent by: (1) Alice and Bob together, or (2) Carol alone after 6 months.");
+
+        // Keys are shown by name when a key map is given
+        let key = "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+        let names = HashMap::from([(key.to_string(), "Treasury".to_string())]);
+        let miniscript = format!("or_d(pk({}),and_v(v:pk(03b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89),older(144)))", key);
+        let summary = contract_summary(&miniscript, "segwit", &names).unwrap();
+        assert!(summary.starts_with("Funds can be spent by: (1) Treasury alone, or (2) 03b2afcd"), "{}", summary);
+        assert!(summary.ends_with("alone after 1 day."), "{}", summary);
+
+        // Short hash names are shown whole
+        let summary = contract_summary("or(pk(Alice),sha256(H))", "policy", &HashMap::new()).unwrap();
+        assert!(summary.contains("with the SHA256 preimage of H..."), "{}", summary);
+    }
+
+    #[test]
+    fn test_path_limits_truncate_or_enumerate() {
+        use miniscript_wasm::analyze::{perform_policy_analysis_with_limits, PathLimits};
+
+        // 3-of-6: C(6,3) = 20 paths in a single group
+        let policy = "thresh(3,pk(A),pk(B),pk(C),pk(D),pk(E),pk(F))";
+
+        let low = perform_policy_analysis_with_limits(policy, false, &PathLimits::new(Some(5), Some(10))).unwrap();
+        assert_eq!(low.paths_truncated, Some(true));
+        assert_eq!(low.spending_paths.unwrap().len(), 1);
+        let group = &low.spending_paths_grouped.unwrap()[0];
+        assert_eq!(group.path_count, 20);
+        assert!(group.paths.is_none());
+
+        let high = perform_policy_analysis_with_limits(policy, false, &PathLimits::new(Some(50), Some(100))).unwrap();
+        assert_eq!(high.paths_truncated, Some(false));
+        assert_eq!(high.spending_paths.unwrap().len(), 20);
+        let group = &high.spending_paths_grouped.unwrap()[0];
+        assert_eq!(group.paths.as_ref().map(Vec::len), Some(20));
+        assert!(group.preview_paths.is_none());
+    }
+
+    #[test]
+    fn test_path_limits_clamp_to_ceiling() {
+        use miniscript_wasm::analyze::{PathLimits, MAX_PATHS_TO_ENUMERATE_CEILING, MAX_THRESH_COMBINATIONS_CEILING};
+
+        let limits = PathLimits::new(Some(usize::MAX), Some(u64::MAX));
+        assert_eq!(limits.max_paths_to_enumerate, MAX_PATHS_TO_ENUMERATE_CEILING);
+        assert_eq!(limits.max_thresh_combinations, MAX_THRESH_COMBINATIONS_CEILING);
+        assert_eq!(PathLimits::new(None, None), PathLimits::default());
+    }
+
+    #[test]
+    fn test_estimate_path_count_huge_policy_is_fast() {
+        // 10-of-30 where each child has 4 alternatives: C(30,10) * 4^10 paths, far too many to enumerate
+        let children: Vec<String> = (0..30)
+            .map(|i| format!("or(or(pk(K{i}a),pk(K{i}b)),or(pk(K{i}c),pk(K{i}d)))"))
+            .collect();
+        let policy_str = format!("thresh(10,{})", children.join(","));
+
+        let start = std::time::Instant::now();
+        let count = estimate_path_count(&policy_str, "policy").unwrap();
+        assert!(start.elapsed().as_secs() < 1, "estimate should not enumerate paths");
+        assert_eq!(count, 30_045_015 * 4u64.pow(10));
+    }
+
+    #[test]
+    fn test_summarized_thresh_without_keys_needs_no_signature() {
+        use miniscript_wasm::analyze::spending_paths_structured;
+
+        // 10 of 20 hashlocks and timelocks: summarized, and no selection needs a signature
+        let children: Vec<String> = (1..=20)
+            .map(|i| if i % 2 == 0 { format!("sha256(H{})", i) } else { format!("older({})", i) })
+            .collect();
+        let policy_str = format!("thresh(10,{})", children.join(","));
+        let semantic = policy_str.parse::<Concrete<String>>().unwrap().lift().unwrap();
+        let paths = enumerate_spending_paths(&semantic);
+        assert_eq!(paths.len(), 1);
+        assert!(paths[0].contains("184756 combinations") && paths[0].contains("no signature required"), "{}", paths[0]);
+        let structured = spending_paths_structured(&policy_str, "policy").unwrap();
+        assert!(!structured[0].requires_signature);
+
+        // Ten keyed children can still be skipped by picking the other ten (n-k+1 = 11 are needed)
+        let keyed: Vec<String> = (1..=20)
+            .map(|i| if i <= 10 { format!("pk(K{})", i) } else { format!("sha256(H{})", i) })
+            .collect();
+        let semantic = format!("thresh(10,{})", keyed.join(",")).parse::<Concrete<String>>().unwrap().lift().unwrap();
+        assert!(enumerate_spending_paths(&semantic)[0].contains("no signature required"));
+
+        // With eleven keyed children every selection includes a key
+        let keyed: Vec<String> = (1..=20)
+            .map(|i| if i <= 11 { format!("pk(K{})", i) } else { format!("sha256(H{})", i) })
+            .collect();
+        let policy_str = format!("thresh(10,{})", keyed.join(","));
+        let semantic = policy_str.parse::<Concrete<String>>().unwrap().lift().unwrap();
+        assert!(!enumerate_spending_paths(&semantic)[0].contains("no signature required"));
+        assert!(spending_paths_structured(&policy_str, "policy").unwrap()[0].requires_signature);
+    }
+
+    #[test]
+    fn test_thresh_over_combination_cap_is_summarized() {
+        let keys: Vec<String> = (1..=20).map(|i| format!("pk(K{})", i)).collect();
+        let policy: Concrete<String> = format!("thresh(10,{})", keys.join(",")).parse().unwrap();
+        let semantic = policy.lift().unwrap();
+
+        let start = std::time::Instant::now();
+        let paths = enumerate_spending_paths(&semantic);
+        let groups = get_grouped_paths(&semantic);
+        assert!(start.elapsed().as_secs() < 1, "summarizing should not materialize combinations");
+
+        assert_eq!(paths.len(), 1, "a single summary path replaces 184,756 combinations");
+        assert!(paths[0].contains("184756 combinations"));
+        assert!(!paths[0].contains("no signature required"));
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].path_count, 184_756);
+        assert!(groups[0].paths.is_none());
+        assert!(paths_truncated(&semantic));
+
+        // Small thresholds are still enumerated in full
+        let small: Concrete<String> = "thresh(2,pk(A),pk(B),pk(C))".parse().unwrap();
+        let small = small.lift().unwrap();
+        assert_eq!(enumerate_spending_paths(&small).len(), 3);
+        assert!(!paths_truncated(&small));
+    }
+
     #[test]
     fn test_deeply_nested_policy() {
         // or(pk(A), or(pk(B), or(pk(C), pk(D))))
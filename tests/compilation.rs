@@ -198,6 +198,35 @@ fn test_your_compile_complex_miniscript() {
 }
 
 
+#[test]
+fn test_your_compile_heterogeneous_thresh_miniscript() {
+    // thresh() children may be arbitrary subexpressions, not only keys
+    let third_xonly = "d127f475aba7d9111ff69cc6858305d15e8912205cfa5dcc7a4c66a97ebb8174";
+    let second_xonly = "b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89";
+
+    let segwit_str = format!(
+        "thresh(2,pk({}),snj:and_v(v:pk({}),older(144)),s:pk(02{}))",
+        COMPRESSED_KEY, SECOND_COMPRESSED_KEY, third_xonly
+    );
+    let result = compile_segwit_miniscript(&segwit_str, Network::Bitcoin);
+    assert!(result.is_ok(), "Heterogeneous thresh should compile in Segwit: {:?}", result.err());
+    let (script_hex, _, address_opt, _, _, _, _, sanity_check_opt, _, _) = result.unwrap();
+    assert!(!script_hex.is_empty(), "Script hex should not be empty");
+    assert!(address_opt.is_some(), "Segwit thresh should produce an address");
+    assert!(sanity_check_opt.unwrap(), "Heterogeneous thresh should pass the sanity check");
+
+    let taproot_str = format!(
+        "thresh(2,pk({}),snj:and_v(v:pk({}),older(144)),s:pk({}))",
+        XONLY_KEY, second_xonly, third_xonly
+    );
+    let result = compile_taproot_miniscript(&taproot_str, Network::Bitcoin);
+    assert!(result.is_ok(), "Heterogeneous thresh should compile in Taproot: {:?}", result.err());
+    let (script_hex, _, _, _, _, _, _, _, _, normalized_opt) = result.unwrap();
+    assert!(!script_hex.is_empty(), "Script hex should not be empty");
+    let normalized = normalized_opt.unwrap();
+    assert!(normalized.contains("and_v(v:pk(") && normalized.contains("older(144)"), "Compound child should be kept: {}", normalized);
+}
+
 #[test]
 fn test_your_parse_descriptors() {
     let expression = format!("pk([C8FE8D4F/48h/1h/123h/2h]xpub6Ctf53JHVC5K4JHwatPdJyXjzADFQt7pazJdQ4rc7j1chsQW6KcJUHFDbBn6e5mvGDEnFhFBCkX383uvzq14Y9Ado5qn5Y7qBiXi5DtVBda/0/0)");
@@ -243,6 +272,23 @@ fn test_your_extract_internal_key_from_complex_expression() {
     assert_eq!(result, COMPRESSED_KEY, "Your key extraction should extract first key from complex expression");
 }
 
+#[test]
+fn test_your_list_candidate_internal_keys() {
+    use miniscript_wasm::keys::list_candidate_internal_keys;
+
+    let third = "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+    let expression = format!("or(pk({}),or(pk({}),and(pk({}),pk({}))))", XONLY_KEY, SECOND_COMPRESSED_KEY, third, XONLY_KEY);
+    let candidates = list_candidate_internal_keys(&expression);
+
+    // Keys that sign alone on a top-level branch are offered in x-only form, in order of appearance;
+    // the third key only signs together with another, so it would skip that condition as internal key
+    assert_eq!(candidates, vec![XONLY_KEY.to_string(), SECOND_COMPRESSED_KEY[2..].to_string()]);
+    assert!(!candidates.contains(&third.to_string()));
+    assert!(list_candidate_internal_keys(&format!("and(pk({}),pk({}))", XONLY_KEY, third)).is_empty());
+    assert_eq!(list_candidate_internal_keys(&format!("or(pk({}),and(pk({}),older(144)))", XONLY_KEY, third)), vec![XONLY_KEY.to_string()]);
+    assert!(list_candidate_internal_keys("older(144)").is_empty());
+}
+
 #[test]
 fn test_your_extract_internal_key_no_pk() {
     let expression = "and(key1,key2)";
@@ -370,4 +416,1073 @@ fn test_your_large_expression_handling() {
         let (script_hex, _script_asm, _address, _script_size, _context, _normalized, _max_satisfaction_size, _max_weight, _sanity_check, _is_non_malleable) = result.unwrap();
         assert!(!script_hex.is_empty(), "Your expression should produce valid script");
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_your_supported_fragments_matrix() {
+    let fragments = miniscript_wasm::validation::supported_fragments();
+
+    let multi_a = fragments.iter().find(|f| f.name == "multi_a").expect("multi_a should be listed");
+    assert!(multi_a.taproot && !multi_a.segwit && !multi_a.legacy, "multi_a should be Taproot-only");
+
+    let multi = fragments.iter().find(|f| f.name == "multi").expect("multi should be listed");
+    assert!(!multi.taproot && multi.segwit && multi.legacy, "multi should not be available in Taproot");
+
+    // Every probe must be accepted somewhere, otherwise the probe itself is broken
+    for fragment in &fragments {
+        assert!(fragment.legacy || fragment.segwit || fragment.taproot, "{} is not supported in any context", fragment.name);
+    }
+    assert_eq!(fragments.iter().filter(|f| f.kind == "wrapper").count(), 10, "All ten wrappers should be listed");
+}
+
+#[test]
+fn test_your_legacy_max_satisfaction_size_2_of_2() {
+    let miniscript_str = format!("multi(2,{},{})", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+
+    let result = compile_legacy_miniscript(&miniscript_str, Network::Bitcoin);
+    assert!(result.is_ok(), "Legacy 2-of-2 should compile");
+    let (_, _, _, script_size, _, max_satisfaction_size, max_weight, _, _, _) = result.unwrap();
+
+    // scriptSig satisfaction: OP_0 (CHECKMULTISIG dummy) + 2 x (push opcode + 72-byte signature incl. sighash flag)
+    let expected_satisfaction = 1 + 2 * (1 + 72);
+    assert_eq!(max_satisfaction_size, Some(expected_satisfaction), "Satisfaction size should be the scriptSig push size");
+
+    // Legacy weight is (scriptSig bytes) * 4, where scriptSig also pushes the redeemScript
+    let redeem_script_push = 1 + script_size; // direct push opcode for a 71-byte script
+    let script_sig_len = expected_satisfaction + redeem_script_push;
+    assert_eq!(max_weight, Some((script_sig_len * 4) as u64), "Weight should be scriptSig size * 4");
+}
+
+#[test]
+fn test_your_compile_auto_context_prefers_segwit() {
+    use miniscript_wasm::compile::policy::compile_auto_context;
+
+    let keys = [
+        COMPRESSED_KEY,
+        SECOND_COMPRESSED_KEY,
+        "02d127f475aba7d9111ff69cc6858305d15e8912205cfa5dcc7a4c66a97ebb8174",
+        "02b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89",
+        "0250929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0",
+    ];
+    let policy = format!("thresh(3,{})", keys.iter().map(|k| format!("pk({})", k)).collect::<Vec<_>>().join(","));
+
+    let choice = compile_auto_context(&policy).expect("3-of-5 should compile in some context");
+    assert_eq!(choice.context, "segwit", "Segwit should win by weight: {:?}", choice.reasoning);
+    assert_eq!(choice.candidates.len(), 2, "Only Legacy and Segwit are candidates for compressed keys");
+
+    let legacy = choice.candidates.iter().find(|c| c.context == "legacy").unwrap();
+    assert!(legacy.max_weight_to_satisfy.unwrap() > choice.max_weight_to_satisfy.unwrap(), "Legacy should be heavier than Segwit");
+}
+
+#[test]
+fn test_your_compile_auto_context_xonly_is_taproot() {
+    use miniscript_wasm::compile::policy::compile_auto_context;
+
+    let choice = compile_auto_context(&format!("pk({})", XONLY_KEY)).unwrap();
+    assert_eq!(choice.context, "taproot", "x-only keys only fit Taproot");
+}
+
+#[test]
+fn test_your_degenerate_timelocks_rejected() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use miniscript_wasm::compile::policy::compile_policy_to_miniscript;
+
+    let cases = [
+        ("older(0)", "older(0) is not a valid relative timelock"),
+        ("after(0)", "after(0) is not a valid absolute timelock"),
+        ("older(65536)", "exceeds the BIP68 value field"),
+        ("older(4194304)", "zero units"),
+        ("after(2147483648)", "maximum absolute timelock"),
+    ];
+
+    for (lock, expected) in cases {
+        let policy = format!("and(pk({}),{})", COMPRESSED_KEY, lock);
+        let err = compile_policy_to_miniscript(&policy, "segwit").unwrap_err();
+        assert!(err.contains(expected), "policy {}: unexpected error {}", lock, err);
+
+        for context in ["segwit", "taproot"] {
+            let key = if context == "taproot" { XONLY_KEY } else { COMPRESSED_KEY };
+            let miniscript = format!("and_v(v:pk({}),{})", key, lock);
+            let options = CompileOptions::for_miniscript(context, None, None, None).unwrap();
+            let result = compile_unified(&miniscript, options).unwrap();
+            assert!(!result.success, "{} miniscript {} should fail", context, lock);
+            let err = result.error.unwrap();
+            assert!(err.contains(expected), "{} miniscript {}: unexpected error {}", context, lock, err);
+        }
+    }
+
+    // Boundary values are still accepted
+    let policy = format!("and(pk({}),older(65535))", COMPRESSED_KEY);
+    assert!(compile_policy_to_miniscript(&policy, "segwit").is_ok());
+    let policy = format!("and(pk({}),after(2147483647))", COMPRESSED_KEY);
+    assert!(compile_policy_to_miniscript(&policy, "segwit").is_ok());
+}
+
+#[test]
+fn test_your_debug_annotation_covers_all_wrappers() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let keys = [
+        COMPRESSED_KEY,
+        SECOND_COMPRESSED_KEY,
+        "02d127f475aba7d9111ff69cc6858305d15e8912205cfa5dcc7a4c66a97ebb8174",
+        "02b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89",
+        "0250929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0",
+    ];
+    let expression = format!(
+        "and_v(vj:pk({}),andor(n:pk({}),or_i(l:pk({}),u:pk({})),tv:pk({})))",
+        keys[0], keys[1], keys[2], keys[3], keys[4]
+    );
+
+    let mut options = CompileOptions::for_miniscript("segwit", None, None, None).unwrap();
+    options.verbose_debug = true;
+    let result = compile_unified(&expression, options).unwrap();
+    assert!(result.success, "wrapper-heavy miniscript should compile: {:?}", result.error);
+
+    let annotated = result.debug_info.expect("verbose mode returns debug info").annotated_expression;
+    let expected = [
+        "v: [V/onfsm] B→V",
+        "j: [B/ondusm] Bn→Bnd",
+        "n: [B/onduesm] B→Bu",
+        "l: [B/dusm] B→Bd",
+        "u: [B/dusm] B→Bd",
+        "t: [B/onufsm] V→Bu",
+        "c: [B/onduesm] K→B",
+        "andor [B/ufsm]",
+    ];
+    for line in expected {
+        assert!(annotated.contains(line), "missing `{}` in annotation:\n{}", line, annotated);
+    }
+}
+
+#[test]
+fn test_your_expanded_pk_forms_match_sugar() {
+    type Compile = fn(&str, Network) -> Result<(String, String, Option<String>, usize, String, Option<usize>, Option<u64>, Option<bool>, Option<bool>, Option<String>), String>;
+    let compilers: [(&str, Compile, &str); 3] = [
+        ("legacy", compile_legacy_miniscript, COMPRESSED_KEY),
+        ("segwit", compile_segwit_miniscript, COMPRESSED_KEY),
+        ("taproot", compile_taproot_miniscript, XONLY_KEY),
+    ];
+
+    for (context, compile, key) in compilers {
+        for (sugar, expanded) in [("pk", "c:pk_k"), ("pkh", "c:pk_h")] {
+            let short = compile(&format!("{}({})", sugar, key), Network::Bitcoin).unwrap();
+            let long = compile(&format!("{}({})", expanded, key), Network::Bitcoin)
+                .unwrap_or_else(|e| panic!("{} should accept {}: {}", context, expanded, e));
+
+            assert_eq!(short.0, long.0, "{}: {} and {} should encode to the same script", context, sugar, expanded);
+            assert_eq!(short.2, long.2, "{}: {} and {} should give the same address", context, sugar, expanded);
+            assert_eq!(short.9, long.9, "{}: {} should normalize to {}", context, expanded, sugar);
+            assert!(long.9.unwrap().contains(&format!("{}({})", sugar, key)),
+                "{}: normalized output should use the {}() sugar", context, sugar);
+        }
+    }
+}
+
+#[test]
+fn test_your_compile_batch_isolates_errors_and_keeps_order() {
+    use miniscript_wasm::compile::engine::compile_batch;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let expressions = vec![
+        format!("pk({})", COMPRESSED_KEY),
+        "pk(not_a_key".to_string(),
+        format!("or(pk({}),pk({}))", COMPRESSED_KEY, SECOND_COMPRESSED_KEY),
+        format!("and(pk({}),older(0))", COMPRESSED_KEY),
+        String::new(),
+    ];
+    let options = CompileOptions::for_policy("segwit", None, Some(Network::Bitcoin)).unwrap();
+
+    let results = compile_batch(&expressions, &options);
+
+    assert_eq!(results.len(), expressions.len(), "One result per expression");
+    let flags: Vec<bool> = results.iter().map(|r| r.success).collect();
+    assert_eq!(flags, vec![true, false, true, false, false]);
+
+    assert!(results[0].compiled_miniscript.as_deref().unwrap().contains(COMPRESSED_KEY));
+    assert!(results[2].compiled_miniscript.as_deref().unwrap().contains(SECOND_COMPRESSED_KEY));
+    assert!(results[1].error.is_some() && results[4].error.is_some());
+    assert!(results[3].error.as_deref().unwrap().contains("older(0)"));
+
+    // Each item matches what a standalone compile would return
+    let single = miniscript_wasm::compile::engine::compile_unified(&expressions[2], options.clone()).unwrap();
+    assert_eq!(single.script, results[2].script);
+}
+
+#[test]
+fn test_your_compile_reports_ranged_descriptors() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let tpub = "[C8FE8D4F/48h/1h/123h/2h]tpubDDEe6Dc3LW1JEUzExDRZ3XBzcAzYxMTfVU5KojsTwXoJ4st6LzqgbFZ1HhDBdTptjXH9MwgdYG4K7MNJBfQktc6AoS8WeAWFDHwDTu99bZa";
+    let options = CompileOptions::for_miniscript("segwit", None, None, None).unwrap();
+
+    let ranged = compile_unified(&format!("pk({}/0/*)", tpub), options.clone()).unwrap();
+    assert!(ranged.success, "wildcard descriptor should compile: {:?}", ranged.error);
+    assert_eq!(ranged.is_ranged, Some(true));
+    assert_eq!(ranged.derivation_wildcards, Some(1));
+
+    let fixed = compile_unified(&format!("pk({}/0/1)", tpub), options.clone()).unwrap();
+    assert!(fixed.success, "fixed descriptor should compile: {:?}", fixed.error);
+    assert_eq!(fixed.is_ranged, Some(false));
+    assert_eq!(fixed.derivation_wildcards, Some(0));
+
+    // Plain keys carry no range information
+    let plain = compile_unified(&format!("pk({})", COMPRESSED_KEY), options).unwrap();
+    assert_eq!(plain.is_ranged, None);
+    assert_eq!(plain.derivation_wildcards, None);
+}
+
+#[test]
+fn test_your_degenerate_terminals_block_address() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let segwit = CompileOptions::for_miniscript("segwit", None, None, None).unwrap();
+    let taproot = CompileOptions::for_miniscript("taproot", None, None, None).unwrap();
+
+    // A bare `1` already fails to compile, and keeps its own error
+    let trivial = compile_unified("1", segwit.clone()).unwrap();
+    assert!(!trivial.success);
+    assert_eq!(trivial.address, None);
+    assert!(!trivial.error.unwrap_or_default().contains("Address generation blocked"));
+
+    // Taproot accepts a bare `0` leaf, which would otherwise yield a burn address
+    let unsat = compile_unified("0", taproot.clone()).unwrap();
+    assert!(!unsat.success);
+    assert_eq!(unsat.address, None);
+    let error = unsat.error.unwrap_or_default();
+    assert!(error.contains("Address generation blocked") && error.contains("unsatisfiable"), "{}", error);
+
+    let or_i = compile_unified(&format!("or_i(0,pk({}))", COMPRESSED_KEY), segwit).unwrap();
+    assert!(or_i.success, "or_i(0,pk) should compile: {:?}", or_i.error);
+    assert!(or_i.address.is_some());
+    assert!(or_i.warnings.unwrap_or_default().iter().all(|w| !w.contains("Address generation blocked")));
+
+    let or_i_tap = compile_unified(&format!("or_i(0,pk({}))", XONLY_KEY), taproot).unwrap();
+    assert!(or_i_tap.address.is_some());
+}
+
+#[test]
+fn test_your_compile_reports_output_descriptor_type() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let third = "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+    let multisig = format!("multi(2,{},{},{})", COMPRESSED_KEY, SECOND_COMPRESSED_KEY, third);
+
+    let legacy = compile_unified(&multisig, CompileOptions::for_miniscript("legacy", None, None, None).unwrap()).unwrap();
+    assert!(legacy.success, "{:?}", legacy.error);
+    assert_eq!(legacy.output_descriptor_type.as_deref(), Some("P2SH"));
+    assert!(legacy.output_summary.unwrap().starts_with("2-of-3 multisig"));
+
+    let segwit = compile_unified(&multisig, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()).unwrap();
+    assert_eq!(segwit.output_descriptor_type.as_deref(), Some("P2WSH"));
+
+    let wrapped = compile_unified(&format!("wsh({})", multisig), CompileOptions::for_miniscript("segwit", None, None, None).unwrap()).unwrap();
+    assert!(wrapped.success, "{:?}", wrapped.error);
+    assert_eq!(wrapped.output_descriptor_type.as_deref(), Some("P2WSH"));
+
+    // Key-path spend by the first key plus a timelocked script path
+    let tap_expr = format!("or_d(pk({}),and_v(v:pk(c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5),older(144)))", XONLY_KEY);
+    let taproot = compile_unified(&tap_expr, CompileOptions::for_miniscript("taproot", Some("multi-leaf"), None, None).unwrap()).unwrap();
+    assert!(taproot.success, "{:?}", taproot.error);
+    assert_eq!(taproot.output_descriptor_type.as_deref(), Some("P2TR (key+script)"));
+
+    let script_only = compile_unified(&tap_expr, CompileOptions::for_miniscript("taproot", Some("script-path"), None, None).unwrap()).unwrap();
+    assert_eq!(script_only.output_descriptor_type.as_deref(), Some("P2TR (script-only)"));
+}
+
+#[test]
+fn test_your_compile_and_assert_address() {
+    use miniscript_wasm::compile::engine::compile_and_assert;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    // BIP86 test vector: first receive key of the "abandon ... about" mnemonic (m/86'/0'/0'/0/0)
+    let bip86 = "tr(cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115)";
+    let bip86_address = "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr";
+    let options = CompileOptions::for_miniscript("taproot", None, None, Some(Network::Bitcoin)).unwrap();
+
+    let (matches, actual) = compile_and_assert(bip86, options.clone(), bip86_address).unwrap();
+    assert!(matches);
+    assert_eq!(actual, bip86_address);
+
+    let wrong = "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9";
+    let (matches, actual) = compile_and_assert(bip86, options, wrong).unwrap();
+    assert!(!matches);
+    assert_eq!(actual, bip86_address);
+
+    // Regular miniscript goes through the compiler
+    let segwit = CompileOptions::for_miniscript("segwit", None, None, Some(Network::Bitcoin)).unwrap();
+    let expression = format!("pk({})", COMPRESSED_KEY);
+    let (_, segwit_address) = compile_and_assert(&expression, segwit.clone(), "").unwrap();
+    assert!(compile_and_assert(&expression, segwit.clone(), &segwit_address).unwrap().0);
+
+    assert!(compile_and_assert("pk(not_a_key)", segwit, bip86_address).is_err());
+}
+
+#[test]
+fn test_your_multiline_policy_compiles_like_single_line() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let single = format!("or(pk({}),and(pk({}),older(144)))", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+    let pretty = format!("or(\n  pk({}),\n  and(\n\tpk({}),\n    older(144)\n  )\n)\n", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+
+    for context in ["legacy", "segwit"] {
+        let options = CompileOptions::for_policy(context, None, Some(Network::Bitcoin)).unwrap();
+        let expected = compile_unified(&single, options.clone()).unwrap();
+        let actual = compile_unified(&pretty, options).unwrap();
+
+        assert!(actual.success, "{} multi-line policy should compile: {:?}", context, actual.error);
+        assert_eq!(actual.script, expected.script);
+        assert_eq!(actual.compiled_miniscript, expected.compiled_miniscript);
+        assert_eq!(actual.address, expected.address);
+    }
+
+    // Whitespace inside an argument is reported instead of silently joining the pieces
+    let options = CompileOptions::for_policy("segwit", None, None).unwrap();
+    let err = compile_unified(&format!("and(pk({}),older(1 44))", COMPRESSED_KEY), options.clone()).err().expect("split number should be rejected");
+    assert!(err.contains("'1 44'"), "{}", err);
+    let split_key = format!("pk({} {})", &COMPRESSED_KEY[..33], &COMPRESSED_KEY[33..]);
+    let err = compile_unified(&split_key, options).err().expect("split key should be rejected");
+    assert!(err.contains("Unexpected whitespace"), "{}", err);
+}
+
+#[test]
+fn test_your_auto_convert_keys_is_opt_in() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let compressed = format!("pk({})", COMPRESSED_KEY);
+
+    // Strict by default: compressed keys are rejected in Taproot
+    let strict = CompileOptions::for_miniscript("taproot", None, None, Some(Network::Bitcoin)).unwrap();
+    assert!(!compile_unified(&compressed, strict.clone()).unwrap().success);
+
+    let mut converting = strict.clone();
+    converting.auto_convert_keys = true;
+    let converted = compile_unified(&compressed, converting).unwrap();
+    let xonly = compile_unified(&format!("pk({})", XONLY_KEY), strict).unwrap();
+    assert!(converted.success, "converted key should compile: {:?}", converted.error);
+    assert_eq!(converted.address, xonly.address);
+    assert!(converted.warnings.unwrap_or_default().iter().any(|w| w.contains("x-only")));
+
+    // x-only -> compressed would have to guess the parity prefix
+    let mut legacy = CompileOptions::for_miniscript("legacy", None, None, Some(Network::Bitcoin)).unwrap();
+    legacy.auto_convert_keys = true;
+    let err = match compile_unified(&format!("pk({})", XONLY_KEY), legacy) {
+        Err(e) => e,
+        Ok(_) => panic!("x-only key should not be converted for legacy"),
+    };
+    assert!(err.contains("ambiguous"), "{}", err);
+}
+
+#[test]
+fn test_your_absolute_timelock_range_checks() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let compile = |lock: &str| {
+        let options = CompileOptions::for_miniscript("segwit", None, None, None).unwrap();
+        compile_unified(&format!("and_v(v:pk({}),{})", COMPRESSED_KEY, lock), options).unwrap()
+    };
+    let advisories = |warnings: Option<Vec<String>>| {
+        warnings.unwrap_or_default().into_iter().filter(|w| w.contains("after(")).collect::<Vec<_>>()
+    };
+
+    // A future block height and a 2025 timestamp are accepted without advisories
+    for lock in ["after(900000)", "after(1735689600)"] {
+        let result = compile(lock);
+        assert!(result.success, "{} should compile: {:?}", lock, result.error);
+        assert!(advisories(result.warnings).is_empty(), "{} should not warn", lock);
+    }
+
+    // Values beyond the 32-bit nLockTime field are rejected
+    let overflow = compile("after(9999999999)");
+    assert!(!overflow.success);
+    assert!(overflow.error.unwrap().contains("32-bit"));
+
+    // A tiny height compiles but is flagged as a probable date typo
+    let typo = compile("after(10)");
+    assert!(typo.success, "{:?}", typo.error);
+    let warnings = advisories(typo.warnings);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("already been mined"), "{}", warnings[0]);
+}
+
+#[test]
+fn test_your_compile_returns_wrapped_script_pubkey() {
+    use bitcoin::hashes::{hash160, sha256, Hash};
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let expression = format!("and_v(v:pk({}),pk({}))", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+
+    let segwit = compile_unified(&expression, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()).unwrap();
+    assert!(segwit.success, "{:?}", segwit.error);
+    let witness_script = hex::decode(segwit.script.unwrap()).unwrap();
+    let expected = format!("0020{}", hex::encode(sha256::Hash::hash(&witness_script).to_byte_array()));
+    assert_eq!(segwit.script_pubkey_hex.unwrap(), expected);
+
+    let legacy = compile_unified(&expression, CompileOptions::for_miniscript("legacy", None, None, None).unwrap()).unwrap();
+    let redeem_script = hex::decode(legacy.script.unwrap()).unwrap();
+    let expected = format!("a914{}87", hex::encode(hash160::Hash::hash(&redeem_script).to_byte_array()));
+    assert_eq!(legacy.script_pubkey_hex.unwrap(), expected);
+}
+
+#[test]
+fn test_your_compile_rejects_over_limit_expression() {
+    use miniscript_wasm::analyze::perform_policy_analysis;
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use miniscript_wasm::validation::MAX_EXPRESSION_LENGTH;
+
+    let oversized = format!("pk({}){}", COMPRESSED_KEY, " ".repeat(MAX_EXPRESSION_LENGTH));
+    let expected = format!("Expression too long (limit {} chars)", MAX_EXPRESSION_LENGTH);
+
+    let options = CompileOptions::for_miniscript("segwit", None, None, None).unwrap();
+    match compile_unified(&oversized, options) {
+        Err(e) => assert_eq!(e, expected),
+        Ok(_) => panic!("over-limit expression should be rejected"),
+    }
+    assert_eq!(perform_policy_analysis(&oversized).unwrap_err(), expected);
+
+    // The limit is configurable per compile
+    let mut options = CompileOptions::for_miniscript("segwit", None, None, None).unwrap();
+    options.max_expression_length = Some(10);
+    match compile_unified(&format!("pk({})", COMPRESSED_KEY), options) {
+        Err(e) => assert_eq!(e, "Expression too long (limit 10 chars)"),
+        Ok(_) => panic!("expression over a custom limit should be rejected"),
+    }
+
+    // Under the limit compiles normally
+    let options = CompileOptions::for_miniscript("segwit", None, None, None).unwrap();
+    let result = compile_unified(&format!("pk({})", COMPRESSED_KEY), options).unwrap();
+    assert!(result.success, "{:?}", result.error);
+}
+
+#[test]
+fn test_your_compile_multi_a_under_taproot() {
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let secp = Secp256k1::new();
+    let keys: Vec<String> = (1..=25u8)
+        .map(|i| SecretKey::from_slice(&[i; 32]).unwrap().x_only_public_key(&secp).0.to_string())
+        .collect();
+    let compile = |expression: &str, context: &str, mode: Option<&str>| {
+        let options = CompileOptions::for_miniscript(context, mode, None, None).unwrap();
+        let result = compile_unified(expression, options).unwrap();
+        (result.success, result.error, result.compiled_miniscript)
+    };
+
+    // 3-of-5 compiles to a CHECKSIG followed by a CHECKSIGADD chain in the leaf
+    let (success, error, compiled) = compile(&format!("multi_a(3,{})", keys[..5].join(",")), "taproot", Some("single-leaf"));
+    assert!(success, "{:?}", error);
+    let leaf_asm = compiled.unwrap().split("|LEAF_ASM:").nth(1).unwrap().to_string();
+    assert_eq!(leaf_asm.matches("OP_CHECKSIGADD").count(), 4, "{}", leaf_asm);
+    assert!(leaf_asm.ends_with("OP_PUSHNUM_3 OP_NUMEQUAL"), "{}", leaf_asm);
+
+    // No 15/20-key cap under tapscript
+    let (success, error, _) = compile(&format!("multi_a(20,{})", keys.join(",")), "taproot", None);
+    assert!(success, "{:?}", error);
+
+    let (success, error, _) = compile(&format!("multi_a(6,{})", keys[..5].join(",")), "taproot", None);
+    assert!(!success);
+    assert_eq!(error.unwrap(), "multi_a threshold 6 exceeds the 5 keys provided");
+
+    let (success, error, _) = compile(&format!("multi_a(1,{},{})", COMPRESSED_KEY, SECOND_COMPRESSED_KEY), "segwit", None);
+    assert!(!success);
+    assert!(error.unwrap().contains("multi_a is only valid in Taproot"));
+}
+
+#[test]
+fn test_your_compile_warns_on_non_standard_segwit_spend() {
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let secp = Secp256k1::new();
+    let keys: Vec<String> = (1..=100u8)
+        .map(|i| bitcoin::PublicKey::new(SecretKey::from_slice(&[i; 32]).unwrap().public_key(&secp)).to_string())
+        .collect();
+    let compile = |expression: &str| {
+        let options = CompileOptions::for_miniscript("segwit", None, None, None).unwrap();
+        let result = compile_unified(expression, options).unwrap();
+        assert!(result.success, "{:?}", result.error);
+        result.warnings.unwrap_or_default().into_iter()
+            .filter(|w| w.contains("Non-standard"))
+            .collect::<Vec<_>>()
+    };
+
+    // Five 20-of-20 multisigs need 105 witness items: consensus-valid, but over the relay limit of 100
+    let branches: Vec<String> = keys.chunks(20).enumerate()
+        .map(|(i, chunk)| format!("{}multi(20,{})", if i == 0 { "" } else { "a:" }, chunk.join(",")))
+        .collect();
+    let warnings = compile(&format!("thresh(5,{})", branches.join(",")));
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert!(warnings[0].contains("105 witness stack items"), "{}", warnings[0]);
+
+    // Four of them stay within the limit
+    assert!(compile(&format!("thresh(4,{})", branches[..4].join(","))).is_empty());
+}
+
+#[test]
+fn test_your_compile_still_rejects_segwit_over_op_limit() {
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let secp = Secp256k1::new();
+    let keys: Vec<String> = (1..=80u8)
+        .map(|i| bitcoin::PublicKey::new(SecretKey::from_slice(&[i; 32]).unwrap().public_key(&secp)).to_string())
+        .collect();
+    let branches: Vec<String> = keys.iter().enumerate()
+        .map(|(i, key)| format!("{}pk({})", if i == 0 { "" } else { "s:" }, key))
+        .collect();
+
+    // Over 201 opcodes the script can never be spent, so relaxing the relay limits must not let it through
+    let options = CompileOptions::for_miniscript("segwit", None, None, None).unwrap();
+    let result = compile_unified(&format!("thresh(1,{})", branches.join(",")), options).unwrap();
+    assert!(!result.success);
+    assert!(result.address.is_none());
+    assert!(result.error.unwrap().contains("opcode"));
+}
+
+#[test]
+fn test_your_compile_reports_stage_timings() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let expression = format!("or(pk({}),and(pk({}),older(144)))", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+
+    let mut options = CompileOptions::for_policy("segwit", None, None).unwrap();
+    options.timing = true;
+    let result = compile_unified(&expression, options).unwrap();
+    assert!(result.success, "{:?}", result.error);
+    let timings = result.timings.expect("timing was requested");
+    for stage in ["preprocess", "compile", "post_process", "total"] {
+        assert!(timings.get(stage).is_some_and(|ms| *ms >= 0.0), "missing stage {}: {:?}", stage, timings);
+    }
+
+    // Descriptor expansion and address derivation are timed apart from compilation
+    let xpub = "[C8FE8D4F/48h/1h/123h/2h]xpub6Ctf53JHVC5K4JHwatPdJyXjzADFQt7pazJdQ4rc7j1chsQW6KcJUHFDbBn6e5mvGDEnFhFBCkX383uvzq14Y9Ado5qn5Y7qBiXi5DtVBda/0/0";
+    let mut options = CompileOptions::for_miniscript("segwit", None, None, None).unwrap();
+    options.timing = true;
+    let result = compile_unified(&format!("pk({})", xpub), options).unwrap();
+    assert!(result.success, "{:?}", result.error);
+    let timings = result.timings.expect("timing was requested");
+    for stage in ["expand_descriptors", "compile", "address"] {
+        assert!(timings.contains_key(stage), "missing stage {}: {:?}", stage, timings);
+    }
+
+    // And so is re-deriving a taproot address for another network
+    let mut options = CompileOptions::for_miniscript("taproot", None, None, Some(Network::Testnet)).unwrap();
+    options.timing = true;
+    let result = compile_unified(&format!("pk({})", XONLY_KEY), options).unwrap();
+    assert!(result.success, "{:?}", result.error);
+    let timings = result.timings.expect("timing was requested");
+    for stage in ["compile", "address"] {
+        assert!(timings.contains_key(stage), "missing stage {}: {:?}", stage, timings);
+    }
+
+    // Off by default
+    let result = compile_unified(&expression, CompileOptions::for_policy("segwit", None, None).unwrap()).unwrap();
+    assert!(result.timings.is_none());
+}
+
+#[test]
+fn test_your_compile_reports_empty_arguments() {
+    use miniscript_wasm::analyze::perform_policy_analysis;
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let error = |policy: &str| {
+        let options = CompileOptions::for_policy("segwit", None, None).unwrap();
+        match compile_unified(policy, options) {
+            Err(e) => e,
+            Ok(result) => result.error.unwrap_or_else(|| panic!("{} should be rejected", policy)),
+        }
+    };
+
+    let a = format!("pk({})", COMPRESSED_KEY);
+    let b = format!("pk({})", SECOND_COMPRESSED_KEY);
+    assert_eq!(error(&format!("or({},)", a)), "Empty argument in or(...) at position 2");
+    assert_eq!(error(&format!("thresh(2,{},,{})", a, b)), "Empty argument in thresh(...) at position 3");
+    assert_eq!(error(&format!("thresh(,{},{})", a, b)), "Empty argument in thresh(...) at position 1");
+    assert_eq!(perform_policy_analysis("and(pk(A),pk())").unwrap_err(), "Empty argument in pk(...) at position 1");
+
+    // Well-formed policies, including multi-line ones, are untouched
+    let options = CompileOptions::for_policy("segwit", None, None).unwrap();
+    assert!(compile_unified(&format!("or(\n  {},\n  {}\n)", a, b), options).unwrap().success);
+}
+
+#[test]
+fn test_your_typecheck_fragment_in_isolation() {
+    use miniscript_wasm::compile::debug::typecheck_fragment;
+
+    // v: turns the B-type pk() into a V-type fragment with no result on the stack
+    let verify = typecheck_fragment("v:pk(A)", "segwit").unwrap().type_properties;
+    assert!(verify.verify);
+    assert!(!verify.base);
+    assert!(!verify.unit);
+
+    let base = typecheck_fragment("pk(A)", "segwit").unwrap().type_properties;
+    assert!(base.base);
+    assert!(!base.verify);
+    assert!(base.unit);
+    assert!(base.dissatisfiable);
+
+    let err = typecheck_fragment("v:older(144)x", "segwit").unwrap_err();
+    assert!(err.contains("fragment"), "{}", err);
+}
+
+#[test]
+fn test_input_type_confusion_hint() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let error = |expression: &str, options: CompileOptions| match compile_unified(expression, options) {
+        Ok(result) => {
+            assert!(!result.success);
+            result.error.unwrap()
+        }
+        Err(e) => e,
+    };
+
+    // Probability weights only exist in policies
+    let weighted = format!("or(9@pk({}),1@pk({}))", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+    assert_eq!(
+        error(&weighted, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()),
+        "This looks like a policy, but you selected miniscript"
+    );
+
+    // Wrapper fragments like v: only exist in miniscript
+    let wrapped = format!("and_v(v:pk({}),pk({}))", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+    assert_eq!(
+        error(&wrapped, CompileOptions::for_policy("segwit", None, None).unwrap()),
+        "This looks like a miniscript, but you selected policy"
+    );
+
+    // Both compile fine under their own input type
+    assert!(compile_unified(&weighted, CompileOptions::for_policy("segwit", None, None).unwrap()).unwrap().success);
+    assert!(compile_unified(&wrapped, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()).unwrap().success);
+
+    // A bad key in a well-formed miniscript keeps its own error
+    let bad_key = error("and_v(v:pk(02zz),pk(03zz))", CompileOptions::for_miniscript("segwit", None, None, None).unwrap());
+    assert!(!bad_key.starts_with("This looks like"), "{}", bad_key);
+}
+
+#[test]
+fn test_boolean_combinator_type_hints() {
+    use miniscript_wasm::compile::debug::boolean_combinator_hint;
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    // or_b needs a W second argument; a bare pk() is B
+    let expression = format!("or_b(pk({}),pk({}))", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+    let result = compile_unified(&expression, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()).unwrap();
+    assert!(!result.success);
+    let error = result.error.unwrap();
+    assert!(error.contains("cannot accept children"), "{}", error);
+    assert!(error.contains("Hint: or_b requires its second argument to be wrapped (e.g. s:pk or a:...)"), "{}", error);
+
+    // The hint names the offending argument of the innermost bad combinator
+    let hint = boolean_combinator_hint("or_d(pk(A),and_b(v:pk(B),s:pk(C)))", "taproot").unwrap();
+    assert!(hint.starts_with("and_b requires its first argument to be a B expression"), "{}", hint);
+    assert!(hint.contains("v:pk(B) is V"), "{}", hint);
+
+    // Correctly wrapped combinators compile and get no hint
+    let wrapped = format!("or_b(pk({}),s:pk({}))", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+    assert!(boolean_combinator_hint(&wrapped, "segwit").is_none());
+    assert!(compile_unified(&wrapped, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()).unwrap().success);
+}
+
+#[test]
+fn test_compiled_descriptor_info() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    // Multi-leaf taproot: one leaf per policy branch under the extracted internal key
+    let policy = "or(pk(d127f475aba7d9111ff69cc6858305d15e8912205cfa5dcc7a4c66a97ebb8174),or(pk(b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89),pk(e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13)))";
+    let result = compile_unified(policy, CompileOptions::for_policy("taproot", Some("multi-leaf"), None).unwrap()).unwrap();
+    let info = result.descriptor_info.unwrap();
+    assert_eq!(info.kind, "tr");
+    assert!(info.has_tree);
+    assert_eq!(info.leaf_count, 3);
+    assert_eq!(info.internal_key.as_deref(), Some("d127f475aba7d9111ff69cc6858305d15e8912205cfa5dcc7a4c66a97ebb8174"));
+    assert!(!info.is_ranged);
+
+    // Segwit multisig, as an explicit descriptor and as a bare miniscript
+    let multisig = format!("multi(2,{},{})", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+    for expression in [format!("wsh({})", multisig), multisig.clone()] {
+        let result = compile_unified(&expression, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()).unwrap();
+        let info = result.descriptor_info.unwrap();
+        assert_eq!(info.kind, "wsh");
+        assert!(!info.has_tree);
+        assert_eq!(info.leaf_count, 0);
+        assert!(info.internal_key.is_none());
+    }
+
+    let nested = compile_unified(&format!("sh(wsh({}))", multisig), CompileOptions::for_miniscript("segwit", None, None, None).unwrap()).unwrap();
+    assert_eq!(nested.descriptor_info.unwrap().kind, "sh-wsh");
+}
+
+#[test]
+fn test_taproot_tree_braces_match_compiled_tree() {
+    use miniscript::descriptor::Descriptor;
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let policy = "or(pk(d127f475aba7d9111ff69cc6858305d15e8912205cfa5dcc7a4c66a97ebb8174),or(pk(b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89),pk(e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13)))";
+    let result = compile_unified(policy, CompileOptions::for_policy("taproot", Some("script-path"), None).unwrap()).unwrap();
+    let braces = result.tree_braces.expect("multi-leaf tree should report its braces");
+    let descriptor = result.compiled_miniscript.unwrap();
+    assert!(descriptor.contains(&braces), "{} should contain {}", descriptor, braces);
+
+    // Leaf depths read off the brace nesting (the leaves are single pk() fragments, so no inner commas)
+    let mut depth = 0;
+    let mut brace_depths = Vec::new();
+    let mut leaf_start = true;
+    for c in braces.chars() {
+        match c {
+            '{' => { depth += 1; leaf_start = true; }
+            '}' => depth -= 1,
+            ',' => leaf_start = true,
+            _ if leaf_start => { brace_depths.push(depth); leaf_start = false; }
+            _ => {}
+        }
+    }
+
+    let parsed: Descriptor<bitcoin::XOnlyPublicKey> = descriptor.parse().unwrap();
+    let Descriptor::Tr(tr) = parsed else { panic!("expected a tr() descriptor") };
+    let tree_depths: Vec<usize> = tr.iter_scripts().map(|(depth, _)| depth as usize).collect();
+    assert_eq!(brace_depths.len(), 3);
+    assert_eq!(brace_depths, tree_depths);
+
+    // A single-leaf tree has no braces to show
+    let single = compile_unified(policy, CompileOptions::for_policy("taproot", Some("single-leaf"), None).unwrap()).unwrap();
+    assert!(single.tree_braces.is_none());
+}
+
+#[test]
+fn test_policy_to_optimal_miniscript_stats() {
+    use miniscript_wasm::compile::policy::policy_to_optimal_miniscript;
+
+    let optimal = policy_to_optimal_miniscript("thresh(2,pk(A),pk(B),pk(C))", "segwit").unwrap();
+    assert_eq!(optimal.miniscript, "multi(2,A,B,C)");
+    // <2> <key>x3 <3> CHECKMULTISIG, with 33-byte keys
+    assert_eq!(optimal.script_size, 1 + 3 * 34 + 1 + 1);
+    assert_eq!(optimal.ops, 1);
+    // Empty dummy element, two signatures, and the witness script itself
+    assert_eq!(optimal.max_sat_weight, Some(1 + 2 * 73 + 1 + 105));
+    assert!(optimal.chosen_over.is_empty());
+
+    // Tapscript has no CHECKMULTISIG, so the compiler switches to multi_a
+    let taproot = policy_to_optimal_miniscript("thresh(2,pk(A),pk(B),pk(C))", "taproot").unwrap();
+    assert_eq!(taproot.miniscript, "multi_a(2,A,B,C)");
+
+    assert!(policy_to_optimal_miniscript("thresh(2,pk(A),pk(B),pk(C))", "bare").is_err());
+}
+
+#[test]
+fn test_explain_malleability_names_fragment() {
+    use miniscript_wasm::compile::debug::explain_malleability;
+
+    // The signature does not cover the witness, so either timelock branch can be swapped in
+    let sources = explain_malleability("and_v(v:pk(A),or_i(older(144),after(100)))", "segwit").unwrap();
+    assert_eq!(sources.len(), 1);
+    assert!(sources[0].starts_with("or_i(older(144),after(100)): "), "{}", sources[0]);
+    assert!(sources[0].contains("neither branch requires a signature"));
+
+    let sources = explain_malleability("thresh(1,pk(A),sln:older(10),sln:after(10))", "segwit").unwrap();
+    assert!(sources[0].contains("at least 2 of the 3 arguments must require a signature"), "{}", sources[0]);
+
+    assert!(explain_malleability("or_d(pk(A),older(10))", "segwit").unwrap().is_empty());
+}
+
+#[test]
+fn test_simplify_policy_flattens_and_deduplicates() {
+    use miniscript_wasm::compile::simplify::simplify_policy;
+
+    // Nested ANDs collapse into one all-of threshold
+    let (simplified, applied) = simplify_policy("and(and(pk(A),pk(B)),pk(C))").unwrap();
+    assert_eq!(simplified, "thresh(3,pk(A),pk(B),pk(C))");
+    assert_eq!(applied, vec!["Flattened nested and: and(pk(A),pk(B))"]);
+    assert!(simplified.parse::<Concrete<String>>().is_ok());
+
+    // An or with two identical branches is just that branch
+    let (simplified, applied) = simplify_policy("or(and(pk(A),older(144)),and(pk(A),older(144)))").unwrap();
+    assert_eq!(simplified, "and(pk(A),older(144))");
+    assert_eq!(applied, vec!["Removed duplicate or branch: and(pk(A),older(144))"]);
+
+    // Weighted ors stay nested so their probabilities keep their meaning
+    let weighted = "or(9@pk(A),1@or(pk(B),pk(C)))";
+    let (simplified, applied) = simplify_policy(weighted).unwrap();
+    assert_eq!(simplified, weighted.parse::<Concrete<String>>().unwrap().to_string());
+    assert!(applied.is_empty());
+
+    let (simplified, _) = simplify_policy("or(pk(A),or(pk(B),pk(C)))").unwrap();
+    assert_eq!(simplified, "thresh(1,pk(A),pk(B),pk(C))");
+}
+
+#[test]
+fn test_mixed_key_types_rejected() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use miniscript_wasm::validation::{check_mixed_key_types, MIXED_KEY_TYPES_ERROR};
+
+    let mixed = format!("or(pk({}),pk({}))", XONLY_KEY, SECOND_COMPRESSED_KEY);
+    for context in ["legacy", "segwit", "taproot"] {
+        let Err(err) = compile_unified(&mixed, CompileOptions::for_policy(context, None, None).unwrap()) else {
+            panic!("mixed keys should be rejected in {}", context);
+        };
+        assert_eq!(err, "Mixed key types: found both x-only (Taproot) and compressed (Legacy/Segwit) keys — use a single key type");
+    }
+    assert_eq!(check_mixed_key_types(&mixed).unwrap_err(), MIXED_KEY_TYPES_ERROR);
+
+    // A sha256 digest is 64 hex characters but not an x-only key
+    let hashlock = format!("and_v(v:pk({}),sha256({}))", COMPRESSED_KEY, XONLY_KEY);
+    assert!(check_mixed_key_types(&hashlock).is_ok());
+    assert!(check_mixed_key_types(&format!("or(pk({}),pk({}))", COMPRESSED_KEY, SECOND_COMPRESSED_KEY)).is_ok());
+}
+
+#[test]
+fn test_multipath_descriptor_receive_and_change_addresses() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let a = "[C8FE8D4F/48h/1h/123h/2h]tpubDC5H7ejMEWt2JF3AF2kdKKokQsAHcksHoHHMgw6S9x5sTs8mZ4rpNPpuNYSJr7RuwiqUgJpYnA6XftMUNW5hGkTduGCtFfBdyj2hXJKN2Xf";
+    let b = "tpubDC8Anx4AbMFdpAygLRf4NqUrmKZysVXSodQBbqmKhmaLgjFCR9xHYsgGytkKDTj8n8abDRsYQmv2voqnxdPekdLWHsyt99yqttghUyCYYE8";
+    let options = || CompileOptions::for_miniscript("segwit", None, None, Some(Network::Testnet)).unwrap();
+
+    let multipath = format!("wsh(multi(2,{}/<0;1>/*,{}/<0;1>/*))", a, b);
+    let result = compile_unified(&multipath, options()).unwrap();
+    let addresses = result.receive_change_addresses.expect("multipath descriptor should report receive and change");
+    assert_ne!(addresses.receive, addresses.change);
+    for address in [&addresses.receive, &addresses.change] {
+        let parsed: bitcoin::Address<bitcoin::address::NetworkUnchecked> = address.parse().unwrap();
+        assert!(parsed.is_valid_for_network(Network::Testnet), "{} should be a testnet address", address);
+    }
+
+    // A single-path descriptor has no change branch
+    let single = format!("wsh(multi(2,{}/0/*,{}/0/*))", a, b);
+    let result = compile_unified(&single, options()).unwrap();
+    assert!(result.receive_change_addresses.is_none());
+}
+
+#[test]
+fn test_pasted_key_invisible_and_confusable_characters() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let options = || CompileOptions::for_miniscript("segwit", None, None, None).unwrap();
+    let clean = compile_unified(&format!("pk({})", SECOND_COMPRESSED_KEY), options()).unwrap();
+
+    // A zero-width space inside the key (and a leading BOM) is stripped before parsing
+    let zero_width = format!("\u{FEFF}pk({}\u{200B}{})", &SECOND_COMPRESSED_KEY[..10], &SECOND_COMPRESSED_KEY[10..]);
+    let stripped = compile_unified(&zero_width, options()).unwrap();
+    assert_eq!(stripped.script, clean.script);
+
+    // Cyrillic 'а' (U+0430) in place of the Latin 'a' at the key's third character
+    let cyrillic = format!("pk({})", SECOND_COMPRESSED_KEY.replacen('a', "\u{0430}", 1));
+    let Err(err) = compile_unified(&cyrillic, options()) else {
+        panic!("a Cyrillic lookalike in a key should be rejected");
+    };
+    assert_eq!(err, "Invalid character (non-ASCII) in key — possible copy-paste artifact at position 6");
+}
+
+#[test]
+fn test_original_and_normalized_expression() {
+    use miniscript_wasm::analyze::analyze_miniscript_expression;
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    // Pretty-printed policy: the canonical form has no whitespace and spells out the or() weights
+    let policy = format!("or(\n  pk({}),\n  and(pk({}), older(144))\n)", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+    let result = compile_unified(&policy, CompileOptions::for_policy("segwit", None, None).unwrap()).unwrap();
+    assert!(result.success);
+    assert_eq!(result.original_expression.as_deref(), Some(policy.as_str()));
+    assert_eq!(
+        result.normalized_expression.unwrap(),
+        format!("or(1@pk({}),1@and(pk({}),older(144)))", COMPRESSED_KEY, SECOND_COMPRESSED_KEY)
+    );
+
+    // c:pk_k() is spelled pk() canonically
+    let miniscript = format!("\u{200B}c:pk_k({})", COMPRESSED_KEY);
+    let result = compile_unified(&miniscript, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()).unwrap();
+    assert_eq!(result.original_expression.unwrap(), format!("c:pk_k({})", COMPRESSED_KEY));
+    assert_eq!(result.normalized_expression.unwrap(), format!("pk({})", COMPRESSED_KEY));
+
+    let analysis = analyze_miniscript_expression(&format!("c:pk_k({})", COMPRESSED_KEY), "segwit").unwrap();
+    assert_eq!(analysis.original_expression.unwrap(), format!("c:pk_k({})", COMPRESSED_KEY));
+    assert_eq!(analysis.normalized_expression.unwrap(), format!("pk({})", COMPRESSED_KEY));
+}
+
+#[test]
+fn test_script_pubkey_witness_script_and_spend_sizes() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let multisig = format!("multi(2,{},{})", COMPRESSED_KEY, SECOND_COMPRESSED_KEY);
+    let result = compile_unified(&multisig, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()).unwrap();
+
+    // P2WSH output: OP_0 <32-byte hash>
+    let script_pubkey = result.script_pubkey_bytes.unwrap();
+    assert_eq!(script_pubkey, 34);
+    // OP_2 <33> <33> OP_2 OP_CHECKMULTISIG
+    let witness_script = result.witness_script_bytes.unwrap();
+    assert_eq!(witness_script, 71);
+    assert_eq!(Some(witness_script), result.script_size);
+
+    // 41 non-witness bytes (outpoint, empty scriptSig, sequence) plus a quarter of the witness:
+    // item count, the CHECKMULTISIG dummy, two 73-byte signatures and the length-prefixed witness script
+    let vbytes = result.worst_case_spend_vbytes.unwrap();
+    let witness_weight = 1 + 1 + 2 * 73 + 1 + witness_script as u64;
+    assert_eq!(vbytes, (41 * 4 + witness_weight).div_ceil(4));
+    assert!(script_pubkey < vbytes as usize && vbytes as usize != witness_script);
+
+    // Legacy P2SH has a redeem script, not a witness script
+    let legacy = compile_unified(&multisig, CompileOptions::for_miniscript("legacy", None, None, None).unwrap()).unwrap();
+    assert_eq!(legacy.script_pubkey_bytes, Some(23));
+    assert!(legacy.witness_script_bytes.is_none());
+}
+
+#[test]
+fn test_multisig_keys_distinct_and_on_curve() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use miniscript_wasm::validation::check_multisig_keys;
+
+    let duplicate = format!("multi(2,{},{},{})", COMPRESSED_KEY, SECOND_COMPRESSED_KEY, COMPRESSED_KEY);
+    let Err(err) = compile_unified(&duplicate, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()) else {
+        panic!("a repeated multisig key should be rejected");
+    };
+    assert_eq!(err, format!("Duplicate key in multisig: {}", COMPRESSED_KEY));
+
+    // x = 2^256 - 1 is past the field size, so no point has it
+    let off_curve = format!("02{}", "f".repeat(64));
+    let policy = format!("thresh(2,pk({}),pk({}),pk({}))", COMPRESSED_KEY, off_curve, SECOND_COMPRESSED_KEY);
+    let Err(err) = compile_unified(&policy, CompileOptions::for_policy("segwit", None, None).unwrap()) else {
+        panic!("an off-curve key should be rejected");
+    };
+    assert_eq!(err, format!("Invalid key point: {}", off_curve));
+
+    // Repeats across separate multisigs and placeholder names are fine
+    assert!(check_multisig_keys(&format!("or_d(multi(1,{0}),multi(1,{0}))", COMPRESSED_KEY)).is_ok());
+    assert!(check_multisig_keys("multi_a(2,Alice,Bob,Carol)").is_ok());
+    assert!(check_multisig_keys("thresh(2,pk(A),s:pk(B),s:pk(A))").is_err());
+}
+
+#[test]
+fn test_compile_descriptor_with_checksum() {
+    use miniscript::descriptor::checksum::desc_checksum;
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let body = format!("wsh(and_v(v:pk({}),older(10)))", COMPRESSED_KEY);
+    let checksum = desc_checksum(&body).unwrap();
+    let pasted = format!("{}#{}", body, checksum);
+    let result = compile_unified(&pasted, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()).unwrap();
+    assert!(result.success, "{:?}", result.error);
+    let compiled = result.compiled_miniscript.unwrap();
+    let descriptor = compiled.strip_prefix("Valid descriptor: ").unwrap();
+    let (compiled_body, compiled_checksum) = descriptor.split_once('#').expect("checksum should be re-attached");
+    assert_eq!(compiled_checksum, desc_checksum(compiled_body).unwrap());
+    assert_eq!(compiled_body.matches('#').count(), 0);
+
+    let tampered = format!("{}#{}", body, "qqqqqqqq");
+    let Err(err) = compile_unified(&tampered, CompileOptions::for_miniscript("segwit", None, None, None).unwrap()) else {
+        panic!("a wrong checksum should be rejected");
+    };
+    assert_eq!(err, format!("Descriptor checksum mismatch: got 'qqqqqqqq', expected '{}'", checksum));
+}
+
+#[test]
+fn test_compile_and_assert_reports_network_mismatch() {
+    use miniscript_wasm::address::network_mismatch;
+    use miniscript_wasm::compile::engine::compile_and_assert;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    // Same BIP86 witness program, but the expected address is for mainnet
+    let bip86 = "tr(cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115)";
+    let mainnet_address = "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr";
+    let testnet = CompileOptions::for_miniscript("taproot", None, None, Some(Network::Testnet)).unwrap();
+    let (matches, testnet_address) = compile_and_assert(bip86, testnet, mainnet_address).unwrap();
+    assert!(!matches);
+    assert!(testnet_address.starts_with("tb1p"));
+    assert_eq!(
+        network_mismatch(mainnet_address, &testnet_address).as_deref(),
+        Some("network mismatch: expected mainnet, compiled testnet")
+    );
+
+    // A different script on another network is an ordinary mismatch
+    let other = "bc1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5sspknck9";
+    assert_eq!(network_mismatch(other, &testnet_address), None);
+    assert_eq!(network_mismatch(mainnet_address, mainnet_address), None);
+}
+
+#[test]
+fn test_taproot_policy_reports_leaf_miniscripts() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let (a, b) = (
+        "d127f475aba7d9111ff69cc6858305d15e8912205cfa5dcc7a4c66a97ebb8174",
+        "b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89",
+    );
+    let policy = format!("or(pk({}),and(pk({}),older(144)))", a, b);
+    let result = compile_unified(&policy, CompileOptions::for_policy("taproot", Some("script-path"), None).unwrap()).unwrap();
+    assert!(result.success, "{:?}", result.error);
+    let leaves = result.tap_leaf_miniscripts.expect("script-path compile should list its leaves");
+    assert_eq!(leaves.len(), 2);
+    assert!(leaves.contains(&format!("pk({})", a)), "{:?}", leaves);
+    assert!(leaves.iter().any(|leaf| leaf.contains(b) && leaf.contains("older(144)")), "{:?}", leaves);
+
+    // Miniscript input has no policy split to report
+    let miniscript = compile_unified(&format!("pk({})", a), CompileOptions::for_miniscript("taproot", None, None, None).unwrap()).unwrap();
+    assert!(miniscript.tap_leaf_miniscripts.is_none());
+}
+
+#[test]
+fn test_xonly_policy_outside_taproot_fails_early() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use miniscript_wasm::compile::policy::XONLY_POLICY_NEEDS_TAPROOT;
+
+    let policy = "or(pk(d127f475aba7d9111ff69cc6858305d15e8912205cfa5dcc7a4c66a97ebb8174),and(pk(b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89),older(144)))";
+    let result = compile_unified(policy, CompileOptions::for_policy("segwit", None, None).unwrap()).unwrap();
+    assert!(!result.success);
+    assert_eq!(result.error.as_deref(), Some(XONLY_POLICY_NEEDS_TAPROOT));
+
+    let taproot = compile_unified(policy, CompileOptions::for_policy("taproot", Some("multi-leaf"), None).unwrap()).unwrap();
+    assert!(taproot.success, "{:?}", taproot.error);
+}
+
+#[test]
+fn test_segwit_reports_witness_script_hash() {
+    use bitcoin::Address;
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use std::str::FromStr;
+
+    let options = CompileOptions::for_miniscript("segwit", None, None, Some(Network::Bitcoin)).unwrap();
+    let result = compile_unified(&format!("pk({})", COMPRESSED_KEY), options).unwrap();
+    assert!(result.success, "{:?}", result.error);
+    let address = Address::from_str(result.address.as_deref().unwrap()).unwrap().assume_checked();
+    let program = address.witness_program().expect("P2WSH address has a witness program");
+    assert_eq!(result.witness_script_hash.as_deref(), Some(hex::encode(program.program().as_bytes()).as_str()));
+    assert!(result.redeem_script_hash.is_none());
+
+    // Nested P2SH-P2WSH also reports the hash160 of the redeem script
+    let options = CompileOptions::for_miniscript("segwit", None, None, Some(Network::Bitcoin)).unwrap();
+    let nested = compile_unified(&format!("sh(wsh(pk({})))", COMPRESSED_KEY), options).unwrap();
+    assert!(nested.success, "{:?}", nested.error);
+    assert_eq!(nested.witness_script_hash, result.witness_script_hash);
+    let descriptor = miniscript::Descriptor::<PublicKey>::from_str(&format!("sh(wsh(pk({})))", COMPRESSED_KEY)).unwrap();
+    let script_pubkey = descriptor.script_pubkey();
+    assert_eq!(nested.redeem_script_hash.as_deref(), Some(hex::encode(&script_pubkey.as_bytes()[2..22]).as_str()));
+}
@@ -301,4 +301,424 @@ fn test_taproot_comprehensive_result_validation() {
     println!("  Address: {}", address);
     println!("  Script type: {}", result.script_type);
     println!("  Network: {:?}", result.network);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_taproot_control_block_two_leaves() {
+    let descriptor = format!(
+        "tr({},{{pk({}),pk({})}})",
+        TEST_INTERNAL_KEY_SCRIPT_ONLY, TEST_INTERNAL_KEY_MULTI_LEAF, "b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89"
+    );
+
+    for leaf_index in 0..2 {
+        let info = taproot::control::taproot_control_block(&descriptor, leaf_index).unwrap();
+        assert_eq!(info.depth, 1, "Both leaves should be at depth 1");
+        assert_eq!(info.leaf_version, 0xc0, "Leaf version should be TapScript");
+        // 33 bytes (version/parity + internal key) + 32 bytes per merkle path step
+        assert_eq!(info.control_block_hex.len() / 2, 33 + 32 * info.depth as usize);
+        assert_eq!(&info.control_block_hex[2..66], TEST_INTERNAL_KEY_SCRIPT_ONLY, "Control block should carry the internal key");
+        assert_eq!(info.leaf_script_hex.len(), 68, "pk(x-only) leaf is 34 bytes");
+    }
+
+    let out_of_range = taproot::control::taproot_control_block(&descriptor, 2);
+    assert!(out_of_range.is_err(), "Leaf index past the last leaf should be rejected");
+}
+
+#[test]
+fn test_taproot_leaf_details_three_leaves() {
+    use bitcoin::hashes::Hash;
+    use bitcoin::taproot::{LeafVersion, TapLeafHash};
+    use bitcoin::ScriptBuf;
+
+    let descriptor = format!(
+        "tr({},{{pk({}),{{pk({}),pk({})}}}})",
+        TEST_INTERNAL_KEY_SCRIPT_ONLY, TEST_INTERNAL_KEY_MULTI_LEAF,
+        "b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89",
+        "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+    );
+
+    let leaves = taproot::control::taproot_leaf_details(&descriptor).unwrap();
+    assert_eq!(leaves.len(), 3);
+    for (leaf, expected_branch) in leaves.iter().zip([1, 2, 2]) {
+        let control = taproot::control::taproot_control_block(&descriptor, leaf.index).unwrap();
+        let script = ScriptBuf::from_hex(&control.leaf_script_hex).unwrap();
+        let tapleaf_hash = TapLeafHash::from_script(&script, LeafVersion::TapScript);
+
+        assert_eq!(leaf.tapleaf_hash, hex::encode(tapleaf_hash.to_byte_array()));
+        assert_eq!(leaf.merkle_branch.len(), expected_branch, "leaf {} sits at depth {}", leaf.index, expected_branch);
+        assert_eq!(leaf.control_block_hex, control.control_block_hex);
+        assert!(leaf.control_block_hex.ends_with(&leaf.merkle_branch.concat()), "control block should end with the merkle branch");
+        assert!(leaf.script_asm.ends_with("OP_CHECKSIG"));
+    }
+
+    assert!(taproot::control::taproot_leaf_details(&format!("tr({})", TEST_INTERNAL_KEY_MULTI_LEAF)).is_err());
+}
+
+#[test]
+fn test_taproot_descriptor_standardness() {
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+    use miniscript_wasm::validation::taproot_standardness_warnings;
+
+    // Small leaves spend well within every relay limit
+    let descriptor = format!("tr({},{{pk({}),and_v(v:pk({}),sha256({}))}})",
+        TEST_INTERNAL_KEY_SCRIPT_ONLY, TEST_INTERNAL_KEY_MULTI_LEAF, "b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89", "11".repeat(32));
+    assert!(taproot_standardness_warnings(&descriptor).unwrap().is_empty());
+
+    // One leaf choosing between 14 multi_a(1,...) of 970 keys each: a ~460 kB script, which
+    // consensus allows (under 1000 stack items, under the block weight) but no relayed transaction can carry
+    let secp = Secp256k1::new();
+    let keys: Vec<String> = (1..=14 * 970u32)
+        .map(|i| {
+            let mut secret = [0u8; 32];
+            secret[28..].copy_from_slice(&i.to_be_bytes());
+            SecretKey::from_slice(&secret).unwrap().x_only_public_key(&secp).0.to_string()
+        })
+        .collect();
+    let branches: Vec<String> = keys.chunks(970).map(|chunk| format!("multi_a(1,{})", chunk.join(","))).collect();
+    let leaf = branches.iter().rev().skip(1)
+        .fold(branches.last().unwrap().clone(), |rest, branch| format!("or_i({},{})", branch, rest));
+    let warnings = taproot_standardness_warnings(&format!("tr({},{})", TEST_INTERNAL_KEY_SCRIPT_ONLY, leaf)).unwrap();
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert!(warnings[0].contains("spending leaf 0 (depth 0)"), "{}", warnings[0]);
+    assert!(warnings[0].contains("above the 400000 WU standard transaction weight"), "{}", warnings[0]);
+
+    assert!(taproot_standardness_warnings(&format!("wsh(pk({}))", "02".to_string() + TEST_INTERNAL_KEY_MULTI_LEAF)).is_err());
+}
+
+#[test]
+fn test_tagged_hash_matches_bip341_vectors() {
+    use taproot::utils::tagged_hash;
+
+    // BIP341 wallet test vectors: leaf hash of a tapscript leaf (0xc0 || compact_size || script)
+    assert_eq!(
+        tagged_hash("TapLeaf", "c02220b617298552a72ade070667e86ca63b8f5789a9fe8731ef91202a91c9f3459007ac").unwrap(),
+        "c525714a7f49c28aedbbba78c005931a81c234b2f6c99a73e4d06082adc8bf2b"
+    );
+    // Key-only output: the tweak commits to the internal key alone
+    assert_eq!(
+        tagged_hash("TapTweak", "d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d").unwrap(),
+        "b86e7be8f39bab32a6f2c0443abbc210f0edac0e2c53d501b36b64437d9c6c70"
+    );
+    // Internal key || merkle root
+    assert_eq!(
+        tagged_hash(
+            "TapTweak",
+            "187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf275b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21",
+        ).unwrap(),
+        "cbd8679ba636c1110ea247542cfbd964131a6be84f873f7f3b62a777528ed001"
+    );
+
+    // The tweak reported for a descriptor is the same tagged hash
+    let details = taproot::tweak::taproot_tweak_details("tr(d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d)").unwrap();
+    assert_eq!(details.tweak_scalar_hex, "b86e7be8f39bab32a6f2c0443abbc210f0edac0e2c53d501b36b64437d9c6c70");
+
+    assert!(tagged_hash("TapLeaf", "zz").is_err());
+}
+
+#[test]
+fn test_tapleaf_hash_matches_bip341_vectors() {
+    use taproot::control::tapleaf_hash;
+
+    // BIP341 wallet test vectors: a tapscript leaf and a leaf with a future version
+    assert_eq!(
+        tapleaf_hash("20b617298552a72ade070667e86ca63b8f5789a9fe8731ef91202a91c9f3459007ac", Some(0xc0)).unwrap(),
+        "c525714a7f49c28aedbbba78c005931a81c234b2f6c99a73e4d06082adc8bf2b"
+    );
+    assert_eq!(
+        tapleaf_hash("06424950333431", Some(250)).unwrap(),
+        "f224a923cd0021ab202ab139cc56802ddb92dcfc172b9212261a539df79a112a"
+    );
+
+    // Tapscript is the default, and the hash matches the leaf found in a descriptor tree
+    let descriptor = format!("tr({},pk({}))", TEST_INTERNAL_KEY_SCRIPT_ONLY, TEST_INTERNAL_KEY_MULTI_LEAF);
+    let control = taproot::control::taproot_control_block(&descriptor, 0).unwrap();
+    let leaf = &taproot::control::taproot_leaf_details(&descriptor).unwrap()[0];
+    assert_eq!(tapleaf_hash(&control.leaf_script_hex, None).unwrap(), leaf.tapleaf_hash);
+
+    // Odd versions are not valid leaf versions
+    assert!(tapleaf_hash("51", Some(0xc1)).is_err());
+    assert!(tapleaf_hash("zz", None).is_err());
+}
+
+#[test]
+fn test_taproot_tweak_details_match_bip_vectors() {
+    // BIP341 wallet test vector: one pk() leaf
+    let details = taproot::tweak::taproot_tweak_details(
+        "tr(187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27,pk(d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8))"
+    ).unwrap();
+    assert_eq!(details.internal_key, "187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27");
+    assert_eq!(details.merkle_root.as_deref(), Some("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"));
+    assert_eq!(details.tweak_scalar_hex, "cbd8679ba636c1110ea247542cfbd964131a6be84f873f7f3b62a777528ed001");
+    assert_eq!(details.output_key, "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3");
+
+    // BIP86 first receive key: key-only, so no merkle root
+    let details = taproot::tweak::taproot_tweak_details("tr(cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115)").unwrap();
+    assert!(details.merkle_root.is_none());
+    assert_eq!(details.output_key, "a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c");
+
+    assert!(taproot::tweak::taproot_tweak_details("wpkh(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9)").is_err());
+}
+
+#[test]
+fn test_taproot_internal_key_reused_in_leaf_warns() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use miniscript_wasm::keys::extract_internal_key_from_expression;
+
+    // The first pk() becomes the internal key, and it also sits in the leaf
+    assert_eq!(extract_internal_key_from_expression(TEST_MINISCRIPT_TAPROOT), TEST_INTERNAL_KEY_MULTI_LEAF);
+
+    let options = CompileOptions::for_miniscript("taproot", Some("multi-leaf"), None, Some(Network::Bitcoin)).unwrap();
+    let result = compile_unified(TEST_MINISCRIPT_TAPROOT, options).unwrap();
+    assert!(result.success, "compilation should continue despite the warning: {:?}", result.error);
+
+    let warnings = result.warnings.expect("key reuse should produce a warning");
+    assert!(warnings.iter().any(|w| w.contains(TEST_INTERNAL_KEY_MULTI_LEAF)
+        && w.contains("internal key") && w.contains("is also used in a script leaf")));
+
+    // Script-path mode uses the NUMS key, which never appears in a leaf
+    let options = CompileOptions::for_miniscript("taproot", Some("script-path"), None, Some(Network::Bitcoin)).unwrap();
+    let result = compile_unified(TEST_MINISCRIPT_TAPROOT, options).unwrap();
+    assert!(result.success);
+    assert!(result.warnings.is_none());
+}
+
+#[test]
+fn test_cached_nums_point_used_by_compile_paths() {
+    use bitcoin::XOnlyPublicKey;
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use miniscript_wasm::taproot::utils::{get_nums_descriptor_key, get_nums_point, NUMS_POINT};
+    use std::str::FromStr;
+
+    let fresh = XOnlyPublicKey::from_str(NUMS_POINT).unwrap();
+    assert_eq!(get_nums_point(), fresh);
+    assert_eq!(get_nums_point(), get_nums_point());
+    assert_eq!(get_nums_descriptor_key().to_string(), NUMS_POINT);
+
+    let expression = "and_v(v:pk(b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89),older(144))";
+    for mode in ["single-leaf", "script-path"] {
+        let options = CompileOptions::for_miniscript("taproot", Some(mode), None, Some(Network::Bitcoin)).unwrap();
+        let result = compile_unified(expression, options).unwrap();
+        assert!(result.success, "{} should compile: {:?}", mode, result.error);
+        let descriptor = result.compiled_miniscript.unwrap();
+        assert!(descriptor.starts_with(&format!("tr({},", get_nums_point())),
+            "{} should use the NUMS internal key: {}", mode, descriptor);
+    }
+}
+
+#[test]
+fn test_taproot_mode_difference_note_names_selected_mode() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let mut options = CompileOptions::for_miniscript("taproot", Some("single-leaf"), None, Some(Network::Bitcoin)).unwrap();
+    options.compare_taproot_modes = true;
+    let result = compile_unified(TEST_MINISCRIPT_TAPROOT, options).unwrap();
+    assert!(result.success, "Should compile: {:?}", result.error);
+
+    let warnings = result.warnings.unwrap_or_default();
+    assert!(warnings.iter().any(|w| w.contains("differs across taproot modes") && w.contains("you selected single-leaf")),
+        "Expected a cross-mode note, got {:?}", warnings);
+
+    // The comparison is opt-in
+    let options = CompileOptions::for_miniscript("taproot", Some("single-leaf"), None, Some(Network::Bitcoin)).unwrap();
+    let result = compile_unified(TEST_MINISCRIPT_TAPROOT, options).unwrap();
+    assert!(!result.warnings.unwrap_or_default().iter().any(|w| w.contains("differs across taproot modes")));
+}
+
+#[test]
+fn test_script_path_unique_derives_distinct_unspendable_keys() {
+    use miniscript::Descriptor;
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use miniscript_wasm::taproot::utils::{derive_unspendable_internal_key, get_nums_point};
+    use bitcoin::XOnlyPublicKey;
+    use std::str::FromStr;
+
+    let internal_key = |expression: &str| {
+        let options = CompileOptions::for_miniscript("taproot", Some("script-path-unique"), None, Some(Network::Bitcoin)).unwrap();
+        let result = compile_unified(expression, options).unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output_descriptor_type.as_deref(), Some("P2TR (script-only)"));
+        assert_eq!(result.internal_key_source.as_deref(), Some("NUMS-derived"));
+        let Descriptor::Tr(tr) = Descriptor::<XOnlyPublicKey>::from_str(&result.compiled_miniscript.unwrap()).unwrap() else {
+            panic!("expected a tr() descriptor");
+        };
+        let key = *tr.internal_key();
+        // The key is H + rG for r committed to this tree, so nobody can spend by key path
+        assert_eq!(derive_unspendable_internal_key(tr.spend_info().merkle_root().unwrap()).unwrap(), key);
+        key
+    };
+
+    let first = internal_key(TEST_MINISCRIPT_TAPROOT);
+    let second = internal_key("and_v(v:pk(b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89),older(144))");
+    assert_ne!(first, second);
+    assert_ne!(first, get_nums_point());
+    assert_ne!(second, get_nums_point());
+}
+
+#[test]
+fn test_taproot_mode_weights_compare_cheapest_paths() {
+    use miniscript_wasm::taproot::weights::taproot_mode_weights;
+
+    let policy = "or(pk(d127f475aba7d9111ff69cc6858305d15e8912205cfa5dcc7a4c66a97ebb8174),and(pk(b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89),pk(e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13)))";
+    let modes = taproot_mode_weights(policy).unwrap();
+    let mode = |name: &str| modes.iter().find(|m| m.mode == name).unwrap();
+    assert_eq!(modes.len(), 3);
+
+    // One NUMS leaf: every spend pays for the whole combined script, but the cheapest one
+    // dissatisfies a key with an empty item and signs once instead of twice
+    let single = mode("single-leaf");
+    assert_eq!(single.worst_case_weight - single.best_case_weight, 66 - 1);
+    // Item count, empty item and signature, the 104-byte leaf script and the 33-byte control block
+    assert_eq!(single.best_case_weight, 1 + (1 + 66) + (1 + 104) + (1 + 33));
+
+    // The extracted key is spendable, so multi-leaf's cheapest path is a lone signature
+    let multi = mode("multi-leaf");
+    assert_eq!(multi.best_case_path, "key-path");
+    assert!(multi.best_case_weight < single.best_case_weight);
+    assert!(multi.worst_case_weight < single.worst_case_weight);
+
+    // Script-path keeps the NUMS key but still splits the branches into cheaper leaves
+    let script_path = mode("script-path");
+    assert!(script_path.best_case_path.starts_with("pk("));
+    // One signature, the 34-byte pk() leaf and a depth-1 control block
+    assert_eq!(script_path.best_case_weight, 1 + 66 + (1 + 34) + (1 + 65));
+    assert!(script_path.best_case_weight < single.best_case_weight);
+}
+
+#[test]
+fn test_taproot_tree_depth_limit() {
+    use std::sync::Arc;
+    use miniscript::descriptor::TapTree;
+    use miniscript::{Miniscript, Tap};
+    use miniscript_wasm::taproot::utils::{validate_tap_tree, MAX_TAPROOT_TREE_DEPTH};
+
+    let leaf = || TapTree::Leaf(Arc::new("pk(A)".parse::<Miniscript<String, Tap>>().unwrap()));
+
+    // Each combine adds one level, so chaining onto a leaf builds a maximally unbalanced tree
+    let mut tree = leaf();
+    for _ in 0..MAX_TAPROOT_TREE_DEPTH {
+        tree = TapTree::combine(leaf(), tree);
+    }
+    assert_eq!(tree.height(), MAX_TAPROOT_TREE_DEPTH);
+    assert_eq!(validate_tap_tree(&tree), Ok(None));
+
+    let too_deep = TapTree::combine(leaf(), tree);
+    let err = validate_tap_tree(&too_deep).unwrap_err();
+    assert!(err.contains("depth 129 exceeds the maximum of 128"), "{}", err);
+}
+
+#[test]
+fn test_taproot_leaf_size_warning() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let secp = bitcoin::secp256k1::Secp256k1::new();
+    let keys: Vec<String> = (1..=320u32).map(|i| {
+        let mut secret = [0u8; 32];
+        secret[28..].copy_from_slice(&i.to_be_bytes());
+        bitcoin::secp256k1::SecretKey::from_slice(&secret).unwrap().x_only_public_key(&secp).0.to_string()
+    }).collect();
+    let leaf_warnings = |miniscript: &str| {
+        let options = CompileOptions::for_miniscript("taproot", Some("single-leaf"), None, None).unwrap();
+        let result = compile_unified(miniscript, options).unwrap();
+        assert!(result.success, "{:?}", result.error);
+        result.warnings.unwrap_or_default().into_iter().filter(|w| w.starts_with("tapleaf")).collect::<Vec<_>>()
+    };
+
+    // 320 keys at 34 bytes each put the leaf past 10,000 bytes
+    let oversized = leaf_warnings(&format!("multi_a(2,{})", keys.join(",")));
+    assert_eq!(oversized.len(), 1);
+    assert!(oversized[0].contains("above the 10000-byte standardness limit"), "{}", oversized[0]);
+
+    assert!(leaf_warnings(&format!("multi_a(2,{})", keys[..3].join(","))).is_empty());
+}
+
+#[test]
+fn test_taproot_policy_with_explicit_internal_key() {
+    use miniscript::descriptor::Descriptor;
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+
+    let a = "b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89";
+    let b = "e493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13";
+    let policy = format!("tr({}, or(pk({}),pk({})))", TEST_INTERNAL_KEY_MULTI_LEAF, a, b);
+
+    let result = compile_unified(&policy, CompileOptions::for_policy("taproot", None, None).unwrap()).unwrap();
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!(result.internal_key_source.as_deref(), Some("user-provided"));
+    assert_eq!(result.output_descriptor_type.as_deref(), Some("P2TR (key+script)"));
+
+    // The given key is the internal key and both OR branches stay in the tree
+    let descriptor: Descriptor<bitcoin::XOnlyPublicKey> = result.compiled_miniscript.unwrap().parse().unwrap();
+    let Descriptor::Tr(tr) = descriptor else { panic!("expected a tr() descriptor") };
+    assert_eq!(tr.internal_key().to_string(), TEST_INTERNAL_KEY_MULTI_LEAF);
+    let leaves: Vec<String> = tr.iter_scripts().map(|(_, ms)| ms.to_string()).collect();
+    assert_eq!(leaves, vec![format!("pk({})", a), format!("pk({})", b)]);
+
+    // Outside taproot the internal key has no meaning
+    let segwit = compile_unified(&policy, CompileOptions::for_policy("segwit", None, None).unwrap()).unwrap();
+    assert!(!segwit.success);
+    assert!(segwit.error.unwrap().contains("taproot"));
+}
+
+#[test]
+fn test_multi_in_taproot_suggests_multi_a() {
+    use miniscript_wasm::compile::engine::compile_unified;
+    use miniscript_wasm::compile::options::CompileOptions;
+    use miniscript_wasm::validation::MULTI_IN_TAPROOT_ERROR;
+
+    let keys = "d127f475aba7d9111ff69cc6858305d15e8912205cfa5dcc7a4c66a97ebb8174,b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89";
+    let error = match compile_unified(&format!("multi(2,{})", keys), CompileOptions::for_miniscript("taproot", None, None, None).unwrap()) {
+        Ok(result) => result.error.expect("multi() should not compile under taproot"),
+        Err(e) => e,
+    };
+    assert_eq!(error, MULTI_IN_TAPROOT_ERROR);
+    assert!(error.contains("use multi_a()"));
+
+    let multi_a = compile_unified(&format!("multi_a(2,{})", keys), CompileOptions::for_miniscript("taproot", None, None, None).unwrap()).unwrap();
+    assert!(multi_a.success, "{:?}", multi_a.error);
+}
+
+#[test]
+fn test_psbt_taproot_fields_two_leaves() {
+    use bitcoin::hashes::Hash;
+    use bitcoin::taproot::{LeafVersion, TapLeafHash, TapNodeHash};
+    use bitcoin::ScriptBuf;
+
+    let a = "b2afcd04877595b269282f860135bb03c8706046b0a57b17f252cf66e35cce89";
+    let b = "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+    let descriptor = format!("tr({},{{pk({}),pk({})}})", TEST_INTERNAL_KEY_MULTI_LEAF, a, b);
+
+    let fields = taproot::control::psbt_taproot_fields(&descriptor).unwrap();
+    assert_eq!(fields.internal_key, TEST_INTERNAL_KEY_MULTI_LEAF);
+    assert_eq!(fields.tap_tree.len(), 2);
+
+    let leaf_hashes: Vec<TapLeafHash> = [a, b].iter().zip(&fields.tap_tree).map(|(key, leaf)| {
+        // <key> OP_CHECKSIG
+        let script = ScriptBuf::from_hex(&format!("20{}ac", key)).unwrap();
+        let hash = TapLeafHash::from_script(&script, LeafVersion::TapScript);
+        assert_eq!(leaf.script_hex, script.to_hex_string());
+        assert_eq!(leaf.leaf_hash, hex::encode(hash.to_byte_array()));
+        assert_eq!((leaf.depth, leaf.leaf_version), (1, 0xc0));
+        hash
+    }).collect();
+
+    let root = TapNodeHash::from_node_hashes(leaf_hashes[0].into(), leaf_hashes[1].into());
+    assert_eq!(fields.merkle_root, Some(hex::encode(root.to_byte_array())));
+
+    // The internal key signs on the key path only; each leaf key signs for its own leaf
+    let keys: Vec<(&str, Vec<String>)> = fields.key_leaf_hashes.iter()
+        .map(|entry| (entry.key.as_str(), entry.leaf_hashes.clone()))
+        .collect();
+    assert_eq!(keys, vec![
+        (TEST_INTERNAL_KEY_MULTI_LEAF, vec![]),
+        (a, vec![fields.tap_tree[0].leaf_hash.clone()]),
+        (b, vec![fields.tap_tree[1].leaf_hash.clone()]),
+    ]);
+
+    let key_only = taproot::control::psbt_taproot_fields(&format!("tr({})", TEST_INTERNAL_KEY_MULTI_LEAF)).unwrap();
+    assert!(key_only.merkle_root.is_none() && key_only.tap_tree.is_empty());
+}
@@ -225,3 +225,166 @@ fn test_roundtrip_pure_rust_miniscript() {
     println!("The '20' after OP_PUSHBYTES_1 is HEX 0x20 = decimal 32");
     println!("This is used by OP_SIZE to check signature size (32 bytes for Schnorr, 33 for ECDSA)");
 }
+
+#[test]
+fn test_policy_miniscript_concrete_policy_roundtrip() {
+    use miniscript::policy::Concrete;
+    use miniscript_wasm::lift::miniscript_to_concrete_policy;
+
+    let policy: Concrete<String> = "and(pk(A),pk(B))".parse().unwrap();
+    let compiled = policy.compile::<Segwitv0>().unwrap().to_string();
+
+    let recovered = miniscript_to_concrete_policy(&compiled, "segwit").unwrap();
+    assert_eq!(recovered, "and(pk(A),pk(B))");
+
+    // Timelocks, hashlocks and thresholds map back to their concrete fragments
+    let recovered = miniscript_to_concrete_policy(
+        "or_d(pk(A),and_v(v:pk(B),older(144)))", "segwit").unwrap();
+    assert_eq!(recovered, "or(pk(A),and(pk(B),older(144)))");
+    let recovered = miniscript_to_concrete_policy(
+        "thresh(2,pk(A),s:pk(B),sln:after(500000))", "taproot").unwrap();
+    assert_eq!(recovered, "thresh(2,pk(A),pk(B),after(500000))");
+
+    // A miniscript with no spending condition has no concrete equivalent
+    assert!(miniscript_to_concrete_policy("1", "segwit").is_err());
+}
+
+#[test]
+fn test_unliftable_script_reports_structured_attempts() {
+    use miniscript_wasm::lift::lift_script_to_miniscript;
+
+    // P2PKH whose hash is truncated by one byte matches no miniscript fragment at all
+    let p2pkh = "76a9137e76e8199196d454941c45d1b3a323f1433bd688ac";
+    let (message, attempts) = lift_script_to_miniscript(p2pkh).unwrap_err();
+
+    assert!(message.contains("not liftable"), "{}", message);
+    let contexts: Vec<&str> = attempts.iter().map(|a| a.context.as_str()).collect();
+    assert_eq!(contexts, vec!["Legacy", "Segwit", "Taproot"]);
+    for attempt in &attempts {
+        assert!(attempt.parse_insane_error.is_some(), "{:?}", attempt);
+        assert!(attempt.parse_error.is_some(), "{:?}", attempt);
+    }
+}
+
+#[test]
+fn test_lift_pkh_fragment_recovers_raw_pkh_with_warning() {
+    use miniscript_wasm::lift::lift_script_to_miniscript;
+
+    // Witness script of wsh(or_d(pk(A),pkh(B))): B only appears as its hash160
+    let expression = "or_d(pk(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9),pkh(03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd))";
+    let ms: Miniscript<miniscript::bitcoin::PublicKey, Segwitv0> = expression.parse().unwrap();
+    let witness_script = hex::encode(ms.encode().as_bytes());
+
+    let (lifted, warnings, verified) = lift_script_to_miniscript(&witness_script).unwrap();
+    assert!(verified, "expr_raw_pkh encodes back to the same hash160 check");
+    assert!(lifted.contains("pk(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9)"), "{}", lifted);
+    assert!(lifted.contains("expr_raw_pkh("), "{}", lifted);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("unknown until it is revealed"), "{}", warnings[0]);
+
+    // Scripts with real keys lift without caveats
+    let plain: Miniscript<miniscript::bitcoin::PublicKey, Segwitv0> = "pk(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9)".parse().unwrap();
+    let (_, warnings, _) = lift_script_to_miniscript(&hex::encode(plain.encode().as_bytes())).unwrap();
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_lift_verifies_script_roundtrip() {
+    use miniscript_wasm::lift::lift_script_to_miniscript;
+
+    // A compiled witness script lifts back to a miniscript with identical bytes
+    let expression = "and_v(v:pk(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9),or_d(pk(03a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd),older(144)))";
+    let ms: Miniscript<miniscript::bitcoin::PublicKey, Segwitv0> = expression.parse().unwrap();
+    let script_hex = hex::encode(ms.encode().as_bytes());
+
+    let (lifted, warnings, verified) = lift_script_to_miniscript(&script_hex).unwrap();
+    assert_eq!(lifted, expression);
+    assert!(verified);
+    assert!(warnings.is_empty(), "{:?}", warnings);
+
+    // The same holds for ASM input and for insane scripts that only parse_insane accepts
+    let (_, _, verified) = lift_script_to_miniscript(&ms.encode().to_asm_string()).unwrap();
+    assert!(verified);
+    let (lifted, _, verified) = lift_script_to_miniscript("60b2").unwrap();
+    assert_eq!(lifted, "older(16)");
+    assert!(verified);
+}
+
+#[test]
+fn test_lift_batch_keeps_order_and_isolates_errors() {
+    use miniscript_wasm::lift::lift_batch;
+
+    let key = "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+    let ms: Miniscript<miniscript::bitcoin::PublicKey, Segwitv0> = format!("pk({})", key).parse().unwrap();
+    let scripts = vec![
+        hex::encode(ms.encode().as_bytes()),
+        "76a9137e76e8199196d454941c45d1b3a323f1433bd688ac".to_string(),
+        "".to_string(),
+        format!("{} OP_CHECKSIG", key),
+    ];
+
+    let results = lift_batch(&scripts);
+    assert_eq!(results.len(), 4);
+
+    assert!(results[0].success);
+    assert_eq!(results[0].miniscript.as_deref(), Some(format!("pk({})", key).as_str()));
+
+    // The unliftable script fails alone and keeps its per-context attempts
+    assert!(!results[1].success);
+    assert_eq!(results[1].liftable, Some(false));
+    assert_eq!(results[1].attempts.as_ref().map(Vec::len), Some(3));
+
+    assert!(!results[2].success);
+    assert_eq!(results[2].error.as_deref(), Some("Empty Bitcoin script"));
+
+    // ASM input lifts like hex after a failing entry
+    assert!(results[3].success);
+    assert_eq!(results[3].miniscript, results[0].miniscript);
+}
+
+#[test]
+fn test_lift_flags_non_minimal_encodings() {
+    use miniscript_wasm::lift::lift_batch;
+
+    let key = "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+    let key2 = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    let scripts = vec![
+        format!("21{}ac", key),
+        // The 33-byte key pushed with OP_PUSHDATA1
+        format!("4c21{}ac", key),
+        // older(5) with the number encoded as 0x0500
+        format!("21{}ad020500b2", key),
+        // multi(2,A,B) with k pushed as 0x0200 before the keys
+        format!("5221{}21{}52ae", key, key2),
+        format!("02020021{}21{}52ae", key, key2),
+        // thresh(1,pk(A),s:pk(B)) with k pushed as 0x0100 before OP_EQUAL
+        format!("21{}ac7c21{}ac935187", key, key2),
+        format!("21{}ac7c21{}ac9302010087", key, key2),
+    ];
+
+    let results = lift_batch(&scripts);
+    assert!(results[0].success);
+    assert_eq!(results[0].non_minimal, Some(false));
+    assert_eq!(results[1].non_minimal, Some(true));
+    assert_eq!(results[2].non_minimal, Some(true));
+    assert!(results[3].success);
+    assert_eq!(results[3].non_minimal, Some(false));
+    assert_eq!(results[4].non_minimal, Some(true));
+    assert!(results[5].success);
+    assert_eq!(results[5].non_minimal, Some(false));
+    assert_eq!(results[6].non_minimal, Some(true));
+}
+
+#[test]
+fn test_non_minimal_check_ignores_short_data_pushes() {
+    use miniscript_wasm::lift::has_non_minimal_encoding;
+
+    let key = "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+    // A two-byte data push ending in 0x00 that is dropped, not used as a number
+    let dropped = ScriptBuf::from_hex(&format!("0201007521{}ac", key)).unwrap();
+    assert!(!has_non_minimal_encoding(&dropped));
+
+    // The same bytes as the operand of OP_CSV are a non-minimal number
+    let timelock = ScriptBuf::from_hex(&format!("21{}ad020100b2", key)).unwrap();
+    assert!(has_non_minimal_encoding(&timelock));
+}